@@ -0,0 +1,55 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion, Throughput};
+use serde::Deserialize;
+use std::io::Write;
+
+const RECORDS: usize = 1000;
+
+#[derive(Deserialize)]
+#[allow(dead_code)]
+struct Record {
+    timestamp: u64,
+    id: u32,
+    counters: [u16; 8],
+    offsets: [i32; 4],
+    readings: [f32; 4],
+}
+
+fn input() -> Vec<u8> {
+    let mut csv = Vec::new();
+    for i in 0..RECORDS {
+        write!(csv, "{},{}", 1_680_000_000_000u64 + i as u64, i).unwrap();
+        for j in 0..8 {
+            write!(csv, ",{}", (i * 8 + j) % 65536).unwrap();
+        }
+        for j in 0..4i32 {
+            write!(csv, ",{}", j * 1000 - i as i32).unwrap();
+        }
+        for j in 0..4 {
+            write!(csv, ",{}", (i + j) as f32 / 7.0).unwrap();
+        }
+        writeln!(csv).unwrap();
+    }
+    csv
+}
+
+fn deserialize(c: &mut Criterion) {
+    let csv = input();
+
+    let mut group = c.benchmark_group("deserialize");
+    group.throughput(Throughput::Elements(RECORDS as u64));
+    group.bench_function("wide_numeric_record", |b| {
+        b.iter(|| {
+            let mut reader = serde_csv_core::Reader::<32>::new();
+            let mut nread = 0;
+            while nread < csv.len() {
+                let (record, n) = reader.deserialize::<Record>(&csv[nread..]).unwrap();
+                black_box(record);
+                nread += n;
+            }
+        })
+    });
+    group.finish();
+}
+
+criterion_group!(benches, deserialize);
+criterion_main!(benches);
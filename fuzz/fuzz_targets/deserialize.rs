@@ -0,0 +1,108 @@
+//! `Reader` must never panic, whatever the input and the configuration.
+//!
+//! The first 4 bytes of the input select the configuration, the rest is CSV data.
+#![no_main]
+
+use heapless::{String, Vec};
+use libfuzzer_sys::fuzz_target;
+use serde::Deserialize;
+use serde_csv_core::de::Headers;
+use serde_csv_core::{Encoding, Reader};
+
+#[allow(dead_code)]
+#[derive(Deserialize)]
+enum Enum {
+    Unit,
+    Newtype(u8),
+    Tuple(i8, bool),
+    Struct { x: i16 },
+}
+
+#[allow(dead_code)]
+#[derive(Deserialize)]
+struct Newtype(u32);
+
+#[allow(dead_code)]
+#[derive(Deserialize)]
+struct Record<'a> {
+    nested_option: Option<Option<u8>>,
+    array: [bool; 2],
+    #[serde(borrow)]
+    borrowed: &'a str,
+    vec: Vec<i16, 3>,
+    enumeration: Enum,
+    optional_enum: Option<Enum>,
+    tuple: (f32, char),
+    string: String<4>,
+    unit: (),
+    newtype: Newtype,
+    ignored: serde::de::IgnoredAny,
+    optional_vec: Option<Vec<u64, 2>>,
+    float: f64,
+}
+
+fn configure<const N: usize>(reader: &mut Reader<N>, config: u32) {
+    let bit = |i: u32| config & (1 << i) != 0;
+    let small = |shift: u32, max: u32| ((config >> shift) % max) as usize;
+    reader
+        .pad_short_records(bit(0))
+        .lossy_utf8(bit(1))
+        .unsanitize_formulas(bit(2))
+        .percent(bit(3))
+        .record_len_includes_terminator(bit(4))
+        .quoted_empty_is_none(!bit(25))
+        .accounting_negatives(bit(26))
+        .row_per_struct(bit(27))
+        .enum_tag_column(small(8, 4));
+    if bit(5) {
+        reader.encoding(Encoding::Windows1252);
+    }
+    if bit(6) {
+        reader.max_fields_per_record(small(12, 8));
+    }
+    if bit(7) {
+        reader.max_record_len(small(16, 32));
+    }
+    if bit(20) {
+        reader.with_expected_fields(small(21, 8));
+    }
+    if bit(24) {
+        reader.null_literal(Some(b"\\N"));
+    }
+}
+
+fn deserialize_all<const N: usize>(reader: &mut Reader<N>, input: &[u8]) {
+    let mut rest = input;
+    while !rest.is_empty() {
+        let _ = reader.deserialize::<Record>(rest);
+        let _ = reader.deserialize::<(Option<Enum>, Vec<u8, 2>, Option<Option<String<3>>>)>(rest);
+        let _ = reader.deserialize::<Vec<Vec<u8, 2>, 2>>(rest);
+        let _ = reader.deserialize_with_raw::<(u8, &str)>(rest);
+        let _ = reader.find_by_key::<(u8, u8)>(rest, b"key");
+        if let Ok(s) = core::str::from_utf8(rest) {
+            let _ = reader.deserialize_from_str::<(&str, Option<&str>)>(s);
+        }
+        let mut headers: Headers<4, 16> = Headers::new();
+        if reader.read_headers(rest, &mut headers).is_ok() {
+            let _ = reader.deserialize_with_headers::<Record, 4, 16>(rest, &headers);
+        }
+        let nread = reader.skip_record(rest);
+        if nread == 0 {
+            break;
+        }
+        rest = rest.get(nread..).unwrap_or_default();
+    }
+}
+
+fuzz_target!(|data: &[u8]| {
+    let Some((config, input)) = data.split_first_chunk::<4>() else {
+        return;
+    };
+    let config = u32::from_le_bytes(*config);
+    let mut small: Reader<4> = Reader::new();
+    configure(&mut small, config);
+    deserialize_all(&mut small, input);
+    let mut large: Reader<64> = Reader::new();
+    configure(&mut large, config);
+    deserialize_all(&mut large, input);
+});
@@ -0,0 +1,123 @@
+//! Eight flags stored in a single field as a hexadecimal byte, like `0xA5`.
+//!
+//! Use it with `#[serde(with = "serde_csv_core::bits8")]` on a field of type `[bool; 8]` or `u8`.
+//! The `i`-th flag is the `i`-th least significant bit of the byte.
+//! The byte is written as `0x` followed by two uppercase hexadecimal digits
+//! and read back from `0x` or `0X` followed by two hexadecimal digits of either case.
+//!
+//! # Example
+//! ```
+//! use serde::{Deserialize, Serialize};
+//!
+//! #[derive(Debug, PartialEq, Serialize, Deserialize)]
+//! struct Record {
+//!     pub id: u8,
+//!     #[serde(with = "serde_csv_core::bits8")]
+//!     pub flags: [bool; 8],
+//! }
+//!
+//! let record = Record {
+//!     id: 1,
+//!     flags: [true, false, true, false, false, false, false, true],
+//! };
+//!
+//! let mut csv = [0; 16];
+//! let nwritten = serde_csv_core::Writer::new().serialize(&record, &mut csv)?;
+//! assert_eq!(&csv[..nwritten], b"1,0x85\n");
+//!
+//! let (read, _) = serde_csv_core::Reader::<8>::new().deserialize::<Record>(&csv[..nwritten])?;
+//! assert_eq!(read, record);
+//! # Ok::<(), serde_csv_core::Error>(())
+//! ```
+
+use serde::{de, Deserializer, Serializer};
+
+/// A value stored as eight flags.
+pub trait Bits8: Sized {
+    /// Returns the flags as a byte.
+    fn to_bits(&self) -> u8;
+
+    /// Creates a value from flags stored in a byte.
+    fn from_bits(bits: u8) -> Self;
+}
+
+impl Bits8 for u8 {
+    fn to_bits(&self) -> u8 {
+        *self
+    }
+
+    fn from_bits(bits: u8) -> Self {
+        bits
+    }
+}
+
+impl Bits8 for [bool; 8] {
+    fn to_bits(&self) -> u8 {
+        self.iter()
+            .rev()
+            .fold(0, |bits, &flag| bits << 1 | u8::from(flag))
+    }
+
+    fn from_bits(bits: u8) -> Self {
+        core::array::from_fn(|i| bits & (1 << i) != 0)
+    }
+}
+
+/// Serializes flags as a hexadecimal byte.
+pub fn serialize<T, S>(value: &T, serializer: S) -> Result<S::Ok, S::Error>
+where
+    T: Bits8,
+    S: Serializer,
+{
+    let bits = value.to_bits();
+    let text = [
+        b'0',
+        b'x',
+        crate::HEX_DIGITS[usize::from(bits >> 4)],
+        crate::HEX_DIGITS[usize::from(bits & 0xF)],
+    ];
+    // The text is ASCII.
+    serializer.serialize_str(core::str::from_utf8(&text).unwrap_or_default())
+}
+
+/// Deserializes flags from a hexadecimal byte.
+pub fn deserialize<'de, T, D>(deserializer: D) -> Result<T, D::Error>
+where
+    T: Bits8,
+    D: Deserializer<'de>,
+{
+    deserializer
+        .deserialize_bytes(Bits8Visitor)
+        .map(T::from_bits)
+}
+
+struct Bits8Visitor;
+
+impl de::Visitor<'_> for Bits8Visitor {
+    type Value = u8;
+
+    fn expecting(&self, formatter: &mut core::fmt::Formatter) -> core::fmt::Result {
+        formatter.write_str("a hexadecimal byte like 0xFF")
+    }
+
+    fn visit_bytes<E>(self, v: &[u8]) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        let digit = |b: u8| char::from(b).to_digit(16);
+        match v {
+            [b'0', b'x' | b'X', high, low] => digit(*high)
+                .zip(digit(*low))
+                .map(|(high, low)| (high << 4 | low) as u8)
+                .ok_or_else(|| E::invalid_value(de::Unexpected::Bytes(v), &self)),
+            _ => Err(E::invalid_value(de::Unexpected::Bytes(v), &self)),
+        }
+    }
+
+    fn visit_str<E>(self, v: &str) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        self.visit_bytes(v.as_bytes())
+    }
+}
@@ -0,0 +1,106 @@
+/// Checksum stored in the last field of every record,
+/// set by [`Writer::checksum`](crate::Writer::checksum) and [`Reader::checksum`](crate::Reader::checksum).
+///
+/// The checksum is written as a field of uppercase hexadecimal digits,
+/// padded with zeros to the width of the checksum.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Checksum {
+    /// CRC-8/SMBUS: polynomial `0x07`, initial value `0x00`, no reflection.
+    /// Written as 2 digits.
+    Crc8,
+    /// CRC-16/CCITT-FALSE: polynomial `0x1021`, initial value `0xFFFF`, no reflection.
+    /// Written as 4 digits.
+    Crc16Ccitt,
+    /// CRC-32 used by zlib and Ethernet: reflected polynomial `0xEDB88320`,
+    /// initial value and final XOR `0xFFFFFFFF`. Written as 8 digits.
+    Crc32,
+}
+
+impl Checksum {
+    /// Returns the register of a checksum of no bytes.
+    pub(crate) fn init(self) -> u32 {
+        match self {
+            Checksum::Crc8 => 0,
+            Checksum::Crc16Ccitt => 0xFFFF,
+            Checksum::Crc32 => 0xFFFF_FFFF,
+        }
+    }
+
+    /// Updates the register `crc` with `bytes`.
+    pub(crate) fn update(self, mut crc: u32, bytes: &[u8]) -> u32 {
+        // Computed bit by bit, which needs no lookup tables.
+        for &b in bytes {
+            match self {
+                Checksum::Crc8 => {
+                    crc ^= u32::from(b);
+                    for _ in 0..8 {
+                        crc = if crc & 0x80 != 0 {
+                            (crc << 1) ^ 0x07
+                        } else {
+                            crc << 1
+                        };
+                    }
+                    crc &= 0xFF;
+                }
+                Checksum::Crc16Ccitt => {
+                    crc ^= u32::from(b) << 8;
+                    for _ in 0..8 {
+                        crc = if crc & 0x8000 != 0 {
+                            (crc << 1) ^ 0x1021
+                        } else {
+                            crc << 1
+                        };
+                    }
+                    crc &= 0xFFFF;
+                }
+                Checksum::Crc32 => {
+                    crc ^= u32::from(b);
+                    for _ in 0..8 {
+                        crc = if crc & 1 != 0 {
+                            (crc >> 1) ^ 0xEDB8_8320
+                        } else {
+                            crc >> 1
+                        };
+                    }
+                }
+            }
+        }
+        crc
+    }
+
+    /// Returns the checksum in the register `crc`.
+    pub(crate) fn value(self, crc: u32) -> u32 {
+        match self {
+            Checksum::Crc8 | Checksum::Crc16Ccitt => crc,
+            Checksum::Crc32 => !crc,
+        }
+    }
+
+    /// Returns the number of hexadecimal digits of the checksum.
+    fn width(self) -> usize {
+        match self {
+            Checksum::Crc8 => 2,
+            Checksum::Crc16Ccitt => 4,
+            Checksum::Crc32 => 8,
+        }
+    }
+
+    /// Writes `value` as hexadecimal digits to `buf`, returning the number of digits.
+    pub(crate) fn to_hex(self, value: u32, buf: &mut [u8; 8]) -> usize {
+        let width = self.width();
+        for (i, digit) in buf[..width].iter_mut().enumerate() {
+            *digit = crate::HEX_DIGITS[(value >> (4 * (width - 1 - i)) & 0xF) as usize];
+        }
+        width
+    }
+
+    /// Parses a checksum written by [`Checksum::to_hex`], accepting digits of either case.
+    pub(crate) fn parse_hex(self, hex: &[u8]) -> Option<u32> {
+        if hex.len() != self.width() {
+            return None;
+        }
+        hex.iter().try_fold(0, |value, &b| {
+            char::from(b).to_digit(16).map(|digit| value << 4 | digit)
+        })
+    }
+}
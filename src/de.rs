@@ -1,55 +1,2122 @@
 //! Deserialize CSV data into a Rust data structure.
 
+use crate::{Checksum, Encoding};
 use core::borrow::Borrow;
-use lexical_parse_float::FromLexical;
-use serde::{de::DeserializeSeed, Deserialize};
+use float::Float;
+#[cfg(feature = "heapless")]
+use heapless::Vec;
+use serde::{
+    de::{DeserializeOwned, DeserializeSeed},
+    Deserialize,
+};
+
+mod builder;
+mod decoder;
+mod float;
+mod headers;
+mod snippet;
+
+pub use builder::ReaderBuilder;
+pub use decoder::FieldDecoder;
+#[cfg(feature = "percent-encoding")]
+pub use decoder::PercentDecoder;
+use headers::{Columns, HeaderNames};
+pub use headers::{DuplicateHeaders, Headers};
+pub use snippet::Snippet;
 
 /// Wrapper for [`csv_core::Reader`] that provides methods for deserialization using [`serde`].
 ///
 /// `N` is a capacity of an internal buffer that's used to temporarily store unescaped fields.
+///
+/// A reader is `Send` and `Sync`, so it can be moved to another thread or task,
+/// or shared between them, e.g. in a `static` guarded by a mutex.
+/// All the state it holds is owned, apart from the `'static` configuration,
+/// like a [`FieldDecoder`], which is required to be `Sync`.
 #[derive(Debug)]
 pub struct Reader<const N: usize> {
     inner: csv_core::Reader,
     field_buffer: [u8; N],
+    config: Config,
+    last_nread: usize,
+    in_record: bool,
+    // Index of the next column of an unfinished record.
+    fixed_column: usize,
+}
+
+/// Serde-level configuration of a [`Reader`].
+#[derive(Debug, Clone, Copy)]
+struct Config {
+    lossy_utf8: bool,
+    encoding: Encoding,
+    unsanitize_formulas: bool,
+    formula_guard: u8,
+    field_decoder: Option<&'static dyn FieldDecoder>,
+    max_fields_per_record: usize,
+    pad_short_records: bool,
+    enum_tag_column: usize,
+    max_record_len: usize,
+    max_str_len: usize,
+    fixed_widths: Option<&'static [usize]>,
+    trim_padding: bool,
+    record_len_includes_terminator: bool,
+    require_terminator: bool,
+    // Record terminator of `Reader::inner`, which csv-core doesn't expose.
+    terminator: csv_core::Terminator,
+    expected_fields: Option<usize>,
+    null_literal: &'static [u8],
+    quoted_empty_is_none: bool,
+    percent: bool,
+    accounting_negatives: bool,
+    reject_leading_zeros: bool,
+    row_per_struct: bool,
+    unordered_headers: bool,
+    duplicate_headers: DuplicateHeaders,
+    case_insensitive_headers: bool,
+    checksum: Option<Checksum>,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            lossy_utf8: false,
+            encoding: Encoding::Utf8,
+            unsanitize_formulas: false,
+            formula_guard: b'\'',
+            field_decoder: None,
+            max_fields_per_record: usize::MAX,
+            pad_short_records: false,
+            enum_tag_column: 0,
+            max_record_len: usize::MAX,
+            max_str_len: usize::MAX,
+            fixed_widths: None,
+            trim_padding: false,
+            record_len_includes_terminator: false,
+            require_terminator: false,
+            terminator: csv_core::Terminator::CRLF,
+            expected_fields: None,
+            null_literal: b"",
+            quoted_empty_is_none: true,
+            percent: false,
+            accounting_negatives: false,
+            reject_leading_zeros: false,
+            row_per_struct: false,
+            unordered_headers: false,
+            duplicate_headers: DuplicateHeaders::Error,
+            case_insensitive_headers: false,
+            checksum: None,
+        }
+    }
+}
+
+impl<const N: usize> Default for Reader<N> {
+    fn default() -> Self {
+        Self::from_builder(csv_core::ReaderBuilder::new())
+    }
 }
 
-impl<const N: usize> Default for Reader<N> {
-    fn default() -> Self {
-        Self::from_builder(csv_core::ReaderBuilder::new())
+impl<const N: usize> Reader<N> {
+    /// Constructs a new reader.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Constructs a new reader from [`csv_core::ReaderBuilder`].
+    ///
+    /// The reader can't tell the record terminator set by the builder, and assumes
+    /// the default [`csv_core::Terminator::CRLF`] where it looks for the end of a record itself,
+    /// e.g. for fixed-width records. Use [`ReaderBuilder`] for a custom terminator.
+    ///
+    /// # Example
+    /// ```
+    /// use serde_csv_core::csv_core;
+    ///
+    /// let reader = serde_csv_core::Reader::<16>::from_builder(
+    ///     csv_core::ReaderBuilder::new()
+    ///         .delimiter(b'-')
+    /// );
+    /// ```
+    pub fn from_builder(builder: impl Borrow<csv_core::ReaderBuilder>) -> Self {
+        Self {
+            inner: builder.borrow().build(),
+            field_buffer: [0; N],
+            config: Config::default(),
+            last_nread: 0,
+            in_record: false,
+            fixed_column: 0,
+        }
+    }
+
+    /// Constructs a new reader on the heap.
+    ///
+    /// Unlike `Box::new(Reader::new())`, the internal buffer is never placed on the stack,
+    /// so it's suitable for large `N`.
+    #[cfg(feature = "alloc")]
+    pub fn new_boxed() -> alloc::boxed::Box<Self> {
+        Self::from_builder_boxed(csv_core::ReaderBuilder::new())
+    }
+
+    /// Constructs a new reader on the heap from [`csv_core::ReaderBuilder`].
+    ///
+    /// See [`Reader::new_boxed`].
+    #[cfg(feature = "alloc")]
+    pub fn from_builder_boxed(
+        builder: impl Borrow<csv_core::ReaderBuilder>,
+    ) -> alloc::boxed::Box<Self> {
+        use core::ptr::addr_of_mut;
+
+        // Fails to compile if a field is added to `Reader`, until it's initialized below.
+        let _ = |reader: &Self| {
+            let Self {
+                inner: _,
+                field_buffer: _,
+                config: _,
+                last_nread: _,
+                in_record: _,
+                fixed_column: _,
+            } = reader;
+        };
+        let mut reader = alloc::boxed::Box::<Self>::new_uninit();
+        let ptr = reader.as_mut_ptr();
+        // SAFETY:
+        // `ptr` points to an allocation for a `Reader`, and every field is written in place
+        // before the reader is assumed to be initialized
+        unsafe {
+            addr_of_mut!((*ptr).inner).write(builder.borrow().build());
+            addr_of_mut!((*ptr).field_buffer).write_bytes(0, 1);
+            addr_of_mut!((*ptr).config).write(Config::default());
+            addr_of_mut!((*ptr).last_nread).write(0);
+            addr_of_mut!((*ptr).in_record).write(false);
+            addr_of_mut!((*ptr).fixed_column).write(0);
+            reader.assume_init()
+        }
+    }
+
+    /// Enables lossy decoding of UTF-8 strings.
+    ///
+    /// When enabled, invalid UTF-8 sequences in fields deserialized as strings or chars
+    /// are replaced with `U+FFFD REPLACEMENT CHARACTER` instead of causing
+    /// [`Error::InvalidUtf8String`] or [`Error::InvalidUtf8Char`].
+    /// The replacement takes place in the internal buffer, so [`Error::Overflow`] is returned
+    /// if the decoded field no longer fits in `N` bytes.
+    ///
+    /// Disabled by default.
+    ///
+    /// # Example
+    /// ```
+    /// use heapless::String;
+    ///
+    /// let mut reader = serde_csv_core::Reader::<16>::new();
+    /// reader.lossy_utf8(true);
+    ///
+    /// let (value, _) = reader.deserialize::<String<16>>(b"caf\xe9")?;
+    ///
+    /// assert_eq!(value, "caf\u{FFFD}");
+    /// # Ok::<(), serde_csv_core::de::Error>(())
+    /// ```
+    pub fn lossy_utf8(&mut self, yes: bool) -> &mut Self {
+        self.config.lossy_utf8 = yes;
+        self
+    }
+
+    /// Sets the character encoding of text fields.
+    ///
+    /// Fields deserialized as strings or chars are transcoded to UTF-8 in the internal buffer
+    /// before being passed to the visitor, so a single byte may take up to 3 bytes of the `N`
+    /// available. [`Error::TranscodedTooLong`] is returned if the transcoded field doesn't fit.
+    /// Other fields, like numbers and booleans, are not affected.
+    ///
+    /// Defaults to [`Encoding::Utf8`].
+    ///
+    /// # Example
+    /// ```
+    /// use heapless::String;
+    /// use serde_csv_core::Encoding;
+    ///
+    /// let mut reader = serde_csv_core::Reader::<16>::new();
+    /// reader.encoding(Encoding::Latin1);
+    ///
+    /// let (value, _) = reader.deserialize::<String<16>>(b"caf\xe9")?;
+    ///
+    /// assert_eq!(value, "café");
+    /// # Ok::<(), serde_csv_core::de::Error>(())
+    /// ```
+    pub fn encoding(&mut self, encoding: Encoding) -> &mut Self {
+        self.config.encoding = encoding;
+        self
+    }
+
+    /// Enables removal of guards added by [`Writer::sanitize_formulas`](crate::Writer::sanitize_formulas).
+    ///
+    /// When enabled, a single guard byte (see [`Reader::formula_guard`]) is stripped from
+    /// strings, chars and byte slices that start with it, if it's followed by more guard bytes
+    /// and a formula trigger character (`=`, `+`, `-`, `@`, tab or carriage return).
+    /// Fields starting with a guard byte followed by anything else are left untouched.
+    ///
+    /// Disabled by default.
+    ///
+    /// # Example
+    /// ```
+    /// use heapless::String;
+    ///
+    /// let mut reader = serde_csv_core::Reader::<16>::new();
+    /// reader.unsanitize_formulas(true);
+    ///
+    /// let ((formula, text), _) = reader.deserialize::<(String<16>, String<16>)>(b"'=1+2,'quoted'")?;
+    ///
+    /// assert_eq!(formula, "=1+2");
+    /// assert_eq!(text, "'quoted'");
+    /// # Ok::<(), serde_csv_core::de::Error>(())
+    /// ```
+    pub fn unsanitize_formulas(&mut self, yes: bool) -> &mut Self {
+        self.config.unsanitize_formulas = yes;
+        self
+    }
+
+    /// Sets the byte stripped by [`Reader::unsanitize_formulas`].
+    ///
+    /// It must match the guard used by the writer. Defaults to `'`.
+    pub fn formula_guard(&mut self, guard: u8) -> &mut Self {
+        self.config.formula_guard = guard;
+        self
+    }
+
+    /// Sets the decoder that reverses the encoding of every field after it's unescaped,
+    /// e.g. the one applied by [`Writer::field_encoder`](crate::Writer::field_encoder).
+    ///
+    /// The decoded field is what the other options and the target type see.
+    /// A `PercentDecoder` is available with the `percent-encoding` feature.
+    ///
+    /// Fields are read as they are by default.
+    ///
+    /// # Example
+    /// ```
+    /// use heapless::String;
+    /// use serde_csv_core::de::{FieldDecoder, Result};
+    ///
+    /// #[derive(Debug)]
+    /// struct Underscores;
+    ///
+    /// impl FieldDecoder for Underscores {
+    ///     fn decode(&self, field: &mut [u8]) -> Result<usize> {
+    ///         field.iter_mut().filter(|b| **b == b'_').for_each(|b| *b = b' ');
+    ///         Ok(field.len())
+    ///     }
+    /// }
+    ///
+    /// let mut reader = serde_csv_core::Reader::<16>::new();
+    /// reader.field_decoder(Some(&Underscores));
+    ///
+    /// let (record, _) = reader.deserialize::<(String<8>, String<8>)>(b"a_b,c\n")?;
+    ///
+    /// assert_eq!(record, ("a b".into(), "c".into()));
+    /// # Ok::<(), serde_csv_core::de::Error>(())
+    /// ```
+    pub fn field_decoder(&mut self, decoder: Option<&'static dyn FieldDecoder>) -> &mut Self {
+        self.config.field_decoder = decoder;
+        self
+    }
+
+    /// Sets the literal that stands for `None`.
+    ///
+    /// A field exactly equal to the literal is deserialized as `None`, so with
+    /// a non-empty literal, e.g. `\N` of the PostgreSQL text format,
+    /// an empty field is `Some("")`.
+    /// `None` restores the default, where an empty field is `None`.
+    ///
+    /// # Example
+    /// ```
+    /// use heapless::String;
+    ///
+    /// let mut reader = serde_csv_core::Reader::<16>::new();
+    /// reader.null_literal(Some(b"\\N"));
+    ///
+    /// let (record, _) = reader.deserialize::<(Option<String<8>>, Option<String<8>>)>(b"\\N,\n")?;
+    ///
+    /// assert_eq!(record, (None, Some(String::new())));
+    /// # Ok::<(), serde_csv_core::de::Error>(())
+    /// ```
+    pub fn null_literal(&mut self, literal: Option<&'static [u8]>) -> &mut Self {
+        self.config.null_literal = literal.unwrap_or_default();
+        self
+    }
+
+    /// Sets whether a quoted empty field, `""`, is deserialized as `None`
+    /// like an empty one when the null literal is empty. Enabled by default.
+    ///
+    /// When disabled, a quoted empty field is read as `Some(None)` by an `Option<Option<T>>`,
+    /// while an empty field is `None`. [`crate::Writer`] writes both as an empty field.
+    /// A quoted empty field is then `Some` of an empty field for other types,
+    /// e.g. `Some("")` for strings and an error for numbers.
+    ///
+    /// A quoted empty field read by an option nested in `Some` is always `None`.
+    /// Fixed-width records have no quotes, so their empty fields are always `None`,
+    /// including fields emptied by [`Reader::trim_padding`].
+    ///
+    /// # Example
+    /// ```
+    /// let mut reader = serde_csv_core::Reader::<16>::new();
+    /// reader.quoted_empty_is_none(false);
+    ///
+    /// let (record, _) = reader.deserialize::<[Option<Option<u8>>; 3]>(b"1,,\"\"\n")?;
+    ///
+    /// assert_eq!(record, [Some(Some(1)), None, Some(None)]);
+    /// # Ok::<(), serde_csv_core::de::Error>(())
+    /// ```
+    pub fn quoted_empty_is_none(&mut self, yes: bool) -> &mut Self {
+        self.config.quoted_empty_is_none = yes;
+        self
+    }
+
+    /// Enables reading floating-point numbers formatted as percentages.
+    ///
+    /// When enabled, a single trailing `%` is stripped from a float field
+    /// and the number is divided by 100. Fields without `%` are read as usual.
+    ///
+    /// Disabled by default.
+    ///
+    /// # Example
+    /// ```
+    /// let mut reader = serde_csv_core::Reader::<16>::new();
+    /// reader.percent(true);
+    ///
+    /// let (record, _) = reader.deserialize::<(f64, f64)>(b"12.5%,0.5\n")?;
+    ///
+    /// assert_eq!(record, (0.125, 0.5));
+    /// # Ok::<(), serde_csv_core::de::Error>(())
+    /// ```
+    pub fn percent(&mut self, yes: bool) -> &mut Self {
+        self.config.percent = yes;
+        self
+    }
+
+    /// Enables reading negative numbers in the accounting format, wrapped in parentheses.
+    ///
+    /// When enabled, an integer or a float field like `(123)` is read as `-123`.
+    /// A field with an opening parenthesis but no closing one, or a sign inside
+    /// the parentheses, is invalid.
+    /// Padding removed by [`Reader::trim_padding`] may surround the parentheses,
+    /// but spaces inside them are invalid, like in any other number.
+    ///
+    /// Disabled by default.
+    ///
+    /// # Example
+    /// ```
+    /// let mut reader = serde_csv_core::Reader::<16>::new();
+    /// reader.accounting_negatives(true);
+    ///
+    /// let (record, _) = reader.deserialize::<(i32, f64, u8)>(b"(123),(12.5),7\n")?;
+    ///
+    /// assert_eq!(record, (-123, -12.5, 7));
+    /// # Ok::<(), serde_csv_core::de::Error>(())
+    /// ```
+    pub fn accounting_negatives(&mut self, yes: bool) -> &mut Self {
+        self.config.accounting_negatives = yes;
+        self
+    }
+
+    /// Enables rejecting integers with leading zeros, like `007`.
+    ///
+    /// When enabled, [`Error::LeadingZero`] is returned for an integer field whose digits
+    /// start with `0`, unless the `0` is the only digit. The sign isn't counted,
+    /// so `-07` is rejected as well, while `0` and `-0` are accepted. Floats aren't affected.
+    ///
+    /// Disabled by default.
+    ///
+    /// # Example
+    /// ```
+    /// use serde_csv_core::de::Error;
+    ///
+    /// let mut reader = serde_csv_core::Reader::<16>::new();
+    /// reader.reject_leading_zeros(true);
+    ///
+    /// assert_eq!(reader.deserialize::<(u8, u8)>(b"0,7\n"), Ok(((0, 7), 4)));
+    /// assert_eq!(reader.deserialize::<(u8, u8)>(b"0,07\n"), Err(Error::LeadingZero));
+    /// ```
+    pub fn reject_leading_zeros(&mut self, yes: bool) -> &mut Self {
+        self.config.reject_leading_zeros = yes;
+        self
+    }
+
+    /// Limits the number of fields read from a single record.
+    ///
+    /// [`Error::TooManyFields`] is returned as soon as deserialization tries to read more fields
+    /// than allowed, regardless of the target type. This bounds the work done on malformed input,
+    /// e.g. a line with a million delimiters deserialized into a sequence.
+    ///
+    /// While a limit is set, fields that the target type doesn't take are read and discarded
+    /// too, so that they count against the limit, and the whole record is always consumed.
+    ///
+    /// Unlimited by default.
+    ///
+    /// # Example
+    /// ```
+    /// use heapless::Vec;
+    /// use serde_csv_core::de::Error;
+    ///
+    /// let mut reader = serde_csv_core::Reader::<16>::new();
+    /// reader.max_fields_per_record(3);
+    ///
+    /// let result = reader.deserialize::<Vec<u8, 8>>(b"1,2,3,4\n");
+    ///
+    /// assert_eq!(result, Err(Error::TooManyFields));
+    /// ```
+    pub fn max_fields_per_record(&mut self, max: usize) -> &mut Self {
+        self.config.max_fields_per_record = max;
+        self
+    }
+
+    /// Enables padding of records that have fewer fields than a struct or tuple needs.
+    ///
+    /// When enabled, missing trailing fields are deserialized as if they were empty,
+    /// so they have to be of a type that accepts an empty field, like `Option<T>` or a string.
+    /// Sequences of unknown length, e.g. `heapless::Vec`, are not padded.
+    ///
+    /// Disabled by default.
+    ///
+    /// # Example
+    /// ```
+    /// use heapless::String;
+    /// use serde::Deserialize;
+    ///
+    /// #[derive(Debug, PartialEq, Eq, Deserialize)]
+    /// struct Record {
+    ///     pub city: String<32>,
+    ///     pub population: Option<u32>,
+    /// }
+    ///
+    /// let mut reader = serde_csv_core::Reader::<32>::new();
+    /// reader.pad_short_records(true);
+    ///
+    /// let (record, _) = reader.deserialize::<Record>(b"Cracow\n")?;
+    ///
+    /// assert_eq!(record, Record { city: "Cracow".into(), population: None });
+    /// # Ok::<(), serde_csv_core::de::Error>(())
+    /// ```
+    pub fn pad_short_records(&mut self, yes: bool) -> &mut Self {
+        self.config.pad_short_records = yes;
+        self
+    }
+
+    /// Sets whether structs nested in the deserialized value are read from rows of their own.
+    ///
+    /// When enabled, a struct that is a field or an element of the deserialized value,
+    /// or of its arrays, starts a new row, unless no field of the current row was read yet.
+    /// Unread fields of the previous row are skipped.
+    /// Structs nested deeper are flattened into the row of the struct containing them.
+    /// Other fields of the outer value are read from its first row, so they must precede
+    /// the nested structs. The number of bytes read covers all rows.
+    ///
+    /// Disabled by default.
+    ///
+    /// # Example
+    /// ```
+    /// use serde::Deserialize;
+    ///
+    /// #[derive(Debug, PartialEq, Eq, Deserialize)]
+    /// struct Sample {
+    ///     pub time: u8,
+    ///     pub value: i16,
+    /// }
+    ///
+    /// #[derive(Debug, PartialEq, Eq, Deserialize)]
+    /// struct Burst {
+    ///     pub id: u8,
+    ///     pub samples: [Sample; 2],
+    /// }
+    ///
+    /// let mut reader = serde_csv_core::Reader::<16>::new();
+    /// reader.row_per_struct(true);
+    ///
+    /// let (burst, nread) = reader.deserialize::<Burst>(b"7\n0,10\n1,-5\n")?;
+    ///
+    /// assert_eq!(burst.id, 7);
+    /// assert_eq!(burst.samples[1], Sample { time: 1, value: -5 });
+    /// assert_eq!(nread, 12);
+    /// # Ok::<(), serde_csv_core::de::Error>(())
+    /// ```
+    pub fn row_per_struct(&mut self, yes: bool) -> &mut Self {
+        self.config.row_per_struct = yes;
+        self
+    }
+
+    /// Sets whether [`Reader::validate_headers`] accepts the columns in any order.
+    ///
+    /// Disabled by default, so the columns must be in the order of the struct fields.
+    ///
+    /// # Example
+    /// ```
+    /// use serde::Deserialize;
+    ///
+    /// #[derive(Deserialize)]
+    /// struct Record {
+    ///     pub city: heapless::String<32>,
+    ///     pub population: u32,
+    /// }
+    ///
+    /// let mut reader = serde_csv_core::Reader::<16>::new();
+    /// assert!(reader.validate_headers::<Record>(b"population,city\n").is_err());
+    ///
+    /// reader.unordered_headers(true);
+    /// assert_eq!(reader.validate_headers::<Record>(b"population,city\n"), Ok(16));
+    /// ```
+    pub fn unordered_headers(&mut self, yes: bool) -> &mut Self {
+        self.config.unordered_headers = yes;
+        self
+    }
+
+    /// Sets how [`Reader::read_headers`] handles columns with the same name.
+    ///
+    /// Defaults to [`DuplicateHeaders::Error`]. A column that loses to another one is skipped
+    /// by [`Reader::deserialize_with_headers`].
+    ///
+    /// # Example
+    /// ```
+    /// use serde::Deserialize;
+    /// use serde_csv_core::de::{DuplicateHeaders, Headers};
+    ///
+    /// #[derive(Debug, PartialEq, Deserialize)]
+    /// struct Record {
+    ///     pub value: i8,
+    /// }
+    ///
+    /// let mut reader = serde_csv_core::Reader::<16>::new();
+    /// reader.duplicate_headers(DuplicateHeaders::LastWins);
+    ///
+    /// let csv = b"value,value\n1,2\n";
+    /// let mut headers = Headers::<4, 32>::new();
+    /// let nread = reader.read_headers(csv, &mut headers)?;
+    /// let (record, _) = reader.deserialize_with_headers::<Record, 4, 32>(&csv[nread..], &headers)?;
+    ///
+    /// assert_eq!(record, Record { value: 2 });
+    /// # Ok::<(), serde_csv_core::de::Error>(())
+    /// ```
+    pub fn duplicate_headers(&mut self, policy: DuplicateHeaders) -> &mut Self {
+        self.config.duplicate_headers = policy;
+        self
+    }
+
+    /// Sets whether column names are matched ignoring ASCII case.
+    ///
+    /// Applies to duplicates found by [`Reader::read_headers`], to struct fields matched by
+    /// [`Reader::deserialize_with_headers`] and to [`Reader::validate_headers`].
+    /// Field names are the ones seen by serde, so they include aliases and follow
+    /// `rename` and `rename_all` attributes. Map keys are the column names as they are.
+    /// Disabled by default.
+    ///
+    /// [`Error::AmbiguousField`] is returned if two field names of the target struct,
+    /// including aliases, differ only in case. [`Reader::validate_headers`] can be used
+    /// to detect it before reading any records.
+    ///
+    /// # Example
+    /// ```
+    /// use serde_csv_core::de::{Error, Headers};
+    ///
+    /// let mut reader = serde_csv_core::Reader::<16>::new();
+    /// let mut headers = Headers::<4, 32>::new();
+    /// assert_eq!(reader.read_headers(b"Temp,temp\n", &mut headers), Ok(10));
+    ///
+    /// reader.case_insensitive_headers(true);
+    /// assert_eq!(
+    ///     reader.read_headers(b"Temp,temp\n", &mut headers),
+    ///     Err(Error::DuplicateHeader { index_a: 0, index_b: 1 })
+    /// );
+    /// ```
+    pub fn case_insensitive_headers(&mut self, yes: bool) -> &mut Self {
+        self.config.case_insensitive_headers = yes;
+        self
+    }
+
+    /// Limits the length of a single record in bytes.
+    ///
+    /// The length is measured in bytes of the input, including delimiters and quotes,
+    /// from the start of the record. The record terminator is counted only if
+    /// [`Reader::record_len_includes_terminator`] is enabled.
+    /// [`Error::RecordTooLong`] is returned as soon as a field that exceeds the limit is read.
+    ///
+    /// Unlimited by default.
+    ///
+    /// # Example
+    /// ```
+    /// use serde_csv_core::de::Error;
+    ///
+    /// let mut reader = serde_csv_core::Reader::<16>::new();
+    /// reader.max_record_len(8);
+    ///
+    /// let result = reader.deserialize::<(u32, u32)>(b"1234,5678\n");
+    ///
+    /// assert_eq!(result, Err(Error::RecordTooLong { len: 9 }));
+    /// ```
+    pub fn max_record_len(&mut self, max: usize) -> &mut Self {
+        self.config.max_record_len = max;
+        self
+    }
+
+    /// Sets whether the record terminator counts toward [`Reader::max_record_len`].
+    ///
+    /// A `\r\n` terminator counts as 2 bytes. A record at the end of the input
+    /// doesn't need a terminator, so it's never counted in that case.
+    ///
+    /// Disabled by default.
+    pub fn record_len_includes_terminator(&mut self, yes: bool) -> &mut Self {
+        self.config.record_len_includes_terminator = yes;
+        self
+    }
+
+    /// Sets whether every record must end with a record terminator.
+    ///
+    /// When enabled, [`Error::MissingTerminator`] is returned for a record that ends
+    /// at the end of the input instead, e.g. the last record of a truncated file.
+    ///
+    /// Disabled by default.
+    ///
+    /// # Example
+    /// ```
+    /// use serde_csv_core::de::Error;
+    ///
+    /// let csv = b"a,b\nc,d";
+    ///
+    /// let mut reader = serde_csv_core::Reader::<16>::new();
+    /// reader.require_terminator(true);
+    ///
+    /// let ((a, b), nread) = reader.deserialize::<(char, char)>(&csv[..])?;
+    /// assert_eq!((a, b), ('a', 'b'));
+    ///
+    /// let result = reader.deserialize::<(char, char)>(&csv[nread..]);
+    /// assert_eq!(result, Err(Error::MissingTerminator));
+    /// # Ok::<(), Error>(())
+    /// ```
+    pub fn require_terminator(&mut self, yes: bool) -> &mut Self {
+        self.config.require_terminator = yes;
+        self
+    }
+
+    /// Limits the length of strings in bytes.
+    ///
+    /// The limit is checked after a string is unescaped and decoded to UTF-8,
+    /// independently of the field buffer size `N`.
+    /// [`Error::StrTooLong`] is returned for a longer string.
+    /// It applies to all strings read, including chars and header names, but not to byte slices.
+    ///
+    /// Unlimited by default.
+    ///
+    /// # Example
+    /// ```
+    /// use heapless::String;
+    /// use serde_csv_core::de::Error;
+    ///
+    /// let mut reader = serde_csv_core::Reader::<64>::new();
+    /// reader.max_str_len(4);
+    ///
+    /// let result = reader.deserialize::<String<64>>(b"abcde\n");
+    ///
+    /// assert_eq!(result, Err(Error::StrTooLong { len: 5 }));
+    /// ```
+    pub fn max_str_len(&mut self, max: usize) -> &mut Self {
+        self.config.max_str_len = max;
+        self
+    }
+
+    /// Sets the widths in bytes of the columns of fixed-width records,
+    /// which are read instead of delimited ones.
+    ///
+    /// A record is split into fields at the column boundaries and ends with a record terminator
+    /// right after its last column, or at the end of the input.
+    /// Fields aren't unquoted or unescaped, and other options of the CSV format don't apply.
+    /// [`Error::ShortRecord`] is returned for a record that ends before its last column,
+    /// [`Error::MissingTerminator`] for one that doesn't end after it,
+    /// and [`Error::InvalidLength`] of 0 fields if the input ends before a record starts.
+    /// [`Reader::skip_record`], [`Reader::find_by_key`] and [`Reader::find_record`]
+    /// split records into columns in the same way.
+    /// [`Reader::checksum`] isn't supported for fixed-width records.
+    ///
+    /// Disabled by default.
+    ///
+    /// # Example
+    /// ```
+    /// use heapless::String;
+    ///
+    /// let csv = b"Cracow  766683\nWarsaw 1860281\n";
+    ///
+    /// let mut reader = serde_csv_core::Reader::<16>::new();
+    /// reader.fixed_widths(Some(&[7, 7])).trim_padding(true);
+    ///
+    /// let ((city, population), nread) = reader.deserialize::<(String<7>, u32)>(&csv[..])?;
+    /// assert_eq!((city.as_str(), population), ("Cracow", 766_683));
+    ///
+    /// let ((city, population), _) = reader.deserialize::<(String<7>, u32)>(&csv[nread..])?;
+    /// assert_eq!((city.as_str(), population), ("Warsaw", 1_860_281));
+    /// # Ok::<(), serde_csv_core::de::Error>(())
+    /// ```
+    pub fn fixed_widths(&mut self, widths: Option<&'static [usize]>) -> &mut Self {
+        self.config.fixed_widths = widths;
+        self
+    }
+
+    /// Sets whether leading and trailing spaces of the fields of fixed-width records are removed,
+    /// see [`Reader::fixed_widths`].
+    ///
+    /// Disabled by default.
+    ///
+    /// # Example
+    /// ```
+    /// use heapless::String;
+    ///
+    /// let mut reader = serde_csv_core::Reader::<16>::new();
+    /// reader.fixed_widths(Some(&[4, 4]));
+    ///
+    /// let (record, _) = reader.deserialize::<(String<4>, String<4>)>(b" ab cd  \n")?;
+    /// assert_eq!(record, (" ab ".into(), "cd  ".into()));
+    ///
+    /// reader.trim_padding(true);
+    ///
+    /// let (record, _) = reader.deserialize::<(String<4>, String<4>)>(b" ab cd  \n")?;
+    /// assert_eq!(record, ("ab".into(), "cd".into()));
+    /// # Ok::<(), serde_csv_core::de::Error>(())
+    /// ```
+    pub fn trim_padding(&mut self, yes: bool) -> &mut Self {
+        self.config.trim_padding = yes;
+        self
+    }
+
+    /// Sets the exact number of fields of every record.
+    ///
+    /// The fields of a record are counted regardless of the target type, so
+    /// the rest of a record with fields not read by the type is read as well.
+    /// [`Error::FieldCount`] is returned for records with a different number of fields.
+    /// [`Reader::skip_record`] doesn't count fields, so that it can always skip a record;
+    /// [`Reader::skip_record_checked`] validates the skipped record on request.
+    ///
+    /// Any number of fields is allowed by default.
+    ///
+    /// # Example
+    /// ```
+    /// use serde_csv_core::de::Error;
+    ///
+    /// let mut reader = serde_csv_core::Reader::<16>::new();
+    /// reader.with_expected_fields(3);
+    ///
+    /// let result = reader.deserialize::<(u8, u8)>(b"1,2,3,4\n");
+    ///
+    /// assert_eq!(result, Err(Error::FieldCount { expected: 3, got: 4 }));
+    /// ```
+    pub fn with_expected_fields(&mut self, count: usize) -> &mut Self {
+        self.config.expected_fields = Some(count);
+        self
+    }
+
+    /// Sets the index of the field that holds the variant name of an enum.
+    ///
+    /// The index is relative to the first field of the enum, which is the first field
+    /// of the record for a top-level enum. The remaining fields, both before and after
+    /// the variant name, are deserialized as the variant's content.
+    /// [`Error::InvalidLength`] is returned if the record ends before the variant name.
+    ///
+    /// Defaults to `0`.
+    ///
+    /// # Example
+    /// ```
+    /// use serde::Deserialize;
+    ///
+    /// #[derive(Debug, PartialEq, Deserialize)]
+    /// enum Event {
+    ///     Temperature { timestamp: u32, celsius: f32 },
+    ///     Door { timestamp: u32, open: bool },
+    /// }
+    ///
+    /// let mut reader = serde_csv_core::Reader::<16>::new();
+    /// reader.enum_tag_column(1);
+    ///
+    /// let (event, _) = reader.deserialize::<Event>(b"1700,Door,true\n")?;
+    ///
+    /// assert_eq!(event, Event::Door { timestamp: 1700, open: true });
+    /// # Ok::<(), serde_csv_core::de::Error>(())
+    /// ```
+    pub fn enum_tag_column(&mut self, column: usize) -> &mut Self {
+        self.config.enum_tag_column = column;
+        self
+    }
+
+    /// Enables verification of the checksum in the last field of every record,
+    /// as written by [`Writer::checksum`](crate::Writer::checksum).
+    ///
+    /// The whole record is read and its checksum is verified before any of its fields are
+    /// deserialized. [`Error::ChecksumMismatch`] is returned if the checksum doesn't match,
+    /// and [`Error::InvalidChecksum`] if the last field isn't a checksum. In both cases,
+    /// [`Reader::last_bytes_read`] returns the length of the record, so it can be skipped.
+    /// Otherwise, the checksum field is hidden from the deserialized type.
+    ///
+    /// The checksum covers the bytes of the record as they are read, excluding the delimiter
+    /// before the checksum field, the record terminator and empty lines before the record.
+    /// Header records read by [`Reader::read_headers`] are verified as well.
+    ///
+    /// Disabled by default.
+    ///
+    /// # Example
+    /// ```
+    /// use serde_csv_core::{de::Error, Checksum};
+    ///
+    /// let mut reader = serde_csv_core::Reader::<16>::new();
+    /// reader.checksum(Some(Checksum::Crc16Ccitt));
+    ///
+    /// let (record, nread) = reader.deserialize::<(u32,)>(b"123456789,29B1\n")?;
+    /// assert_eq!(record, (123_456_789,));
+    /// assert_eq!(nread, 15);
+    ///
+    /// let result = reader.deserialize::<(u32,)>(b"123456780,29B1\n");
+    /// assert_eq!(result, Err(Error::ChecksumMismatch { expected: 0x29B1, actual: 0xB898 }));
+    /// # Ok::<(), serde_csv_core::de::Error>(())
+    /// ```
+    pub fn checksum(&mut self, checksum: Option<Checksum>) -> &mut Self {
+        self.config.checksum = checksum;
+        self
+    }
+
+    /// Deserializes a given CSV byte slice into a value of type `T`.
+    ///
+    /// The second element of the resulting tuple is a number of bytes read.
+    ///
+    /// A sequence of unknown length, like `heapless::Vec<T, N>`, consumes all the remaining
+    /// fields of a record, so it can only be the last field of `T`. Otherwise, [`Error::SeqNotLast`]
+    /// is returned. [`Error::SeqTooLong`] is returned if the sequence runs out of capacity.
+    /// A map, like `BTreeMap<K, V>`, consumes the remaining fields in the same way,
+    /// reading them as alternating keys and values. [`Error::FieldCount`] is returned
+    /// if the last key has no value.
+    ///
+    /// # Example
+    /// ```
+    /// use heapless::String;
+    /// use serde::Deserialize;
+    ///
+    /// #[derive(Debug, PartialEq, Eq, Deserialize)]
+    /// struct Record {
+    ///     pub country: String<32>,
+    ///     pub city: String<32>,
+    ///     pub population: u32,
+    /// }
+    ///
+    /// let csv = b"Poland,Cracow,766683\n";
+    ///
+    /// let mut reader = serde_csv_core::Reader::<32>::new();
+    /// let (record, nread)  = reader.deserialize::<Record>(&csv[..])?;
+    ///
+    /// assert_eq!(record, Record {
+    ///     country: "Poland".into(),
+    ///     city: "Cracow".into(),
+    ///     population: 766_683,
+    /// });
+    /// assert_eq!(nread, 21);
+    /// # Ok::<(), serde_csv_core::de::Error>(())
+    /// ```
+    pub fn deserialize<'de, T>(&mut self, input: &[u8]) -> Result<(T, usize)>
+    where
+        T: Deserialize<'de>,
+    {
+        self.deserialize_impl(input, None, None, None)
+            .map(|(value, nread, _)| (value, nread))
+    }
+
+    /// Deserializes a given CSV byte slice into a value of type `T`, verifying `checksum`
+    /// in the last field of the record, as written by
+    /// [`Writer::serialize_with_crc`](crate::Writer::serialize_with_crc).
+    ///
+    /// Equivalent to [`Reader::deserialize`] with [`Reader::checksum`] set for this record only.
+    ///
+    /// # Example
+    /// ```
+    /// use serde_csv_core::{de::Error, Checksum};
+    ///
+    /// let mut reader = serde_csv_core::Reader::<16>::new();
+    ///
+    /// let (record, _) = reader.deserialize_with_crc::<(u32,)>(b"123456789,F4\n", Checksum::Crc8)?;
+    /// assert_eq!(record, (123_456_789,));
+    ///
+    /// let result = reader.deserialize_with_crc::<(u32,)>(b"123456780,F4\n", Checksum::Crc8);
+    /// assert_eq!(result, Err(Error::ChecksumMismatch { expected: 0xF4, actual: 0xCB }));
+    /// # Ok::<(), serde_csv_core::de::Error>(())
+    /// ```
+    pub fn deserialize_with_crc<'de, T>(
+        &mut self,
+        input: &[u8],
+        checksum: Checksum,
+    ) -> Result<(T, usize)>
+    where
+        T: Deserialize<'de>,
+    {
+        let config = self.config;
+        self.config.checksum = Some(checksum);
+        let result = self.deserialize(input);
+        self.config = config;
+        result
+    }
+
+    /// Deserializes a given CSV string into a value of type `T`.
+    ///
+    /// Behaves like [`Reader::deserialize`], but skips UTF-8 validation of string fields
+    /// that are known to be valid. csv-core copies a field that isn't quoted or escaped
+    /// as is, so if it starts and ends on char boundaries of `input`, it's a valid `str`.
+    /// Other fields, e.g. quoted ones or ones split by a non-ASCII delimiter, are still validated.
+    ///
+    /// # Example
+    /// ```
+    /// use heapless::String;
+    ///
+    /// let mut reader = serde_csv_core::Reader::<16>::new();
+    /// let (record, nread) = reader.deserialize_from_str::<(String<16>, u32)>("Kraków,766683\n")?;
+    ///
+    /// assert_eq!(record, ("Kraków".into(), 766_683));
+    /// assert_eq!(nread, 15);
+    /// # Ok::<(), serde_csv_core::de::Error>(())
+    /// ```
+    pub fn deserialize_from_str<'de, T>(&mut self, input: &str) -> Result<(T, usize)>
+    where
+        T: Deserialize<'de>,
+    {
+        self.deserialize_impl(input.as_bytes(), None, None, Some(input))
+            .map(|(value, nread, _)| (value, nread))
+    }
+
+    /// Reads a record with a single field containing a number of records,
+    /// written by [`Writer::write_count`](crate::Writer::write_count).
+    ///
+    /// Returns the count and the number of bytes read.
+    /// [`Error::FieldCount`] is returned if the record has more than one field.
+    ///
+    /// # Example
+    /// ```
+    /// let csv = b"2\na,1\nb,2\n";
+    ///
+    /// let mut reader = serde_csv_core::Reader::<16>::new();
+    /// let (count, nread) = reader.read_count(&csv[..])?;
+    ///
+    /// assert_eq!(count, 2);
+    /// assert_eq!(nread, 2);
+    /// # Ok::<(), serde_csv_core::de::Error>(())
+    /// ```
+    pub fn read_count(&mut self, input: &[u8]) -> Result<(u32, usize)> {
+        let config = self.config;
+        self.config.expected_fields = Some(1);
+        let result = self.deserialize(input);
+        self.config = config;
+        result
+    }
+
+    /// Reads a header record into `headers`, replacing their previous contents.
+    ///
+    /// Returns the number of bytes read.
+    /// [`Error::DuplicateHeader`] is returned if two columns have the same name,
+    /// unless [`Reader::duplicate_headers`] is set otherwise,
+    /// and [`Error::Overflow`] if the names don't fit in `headers`.
+    ///
+    /// # Example
+    /// ```
+    /// use serde_csv_core::de::{Error, Headers};
+    ///
+    /// let mut reader = serde_csv_core::Reader::<16>::new();
+    /// let mut headers = Headers::<4, 32>::new();
+    ///
+    /// let result = reader.read_headers(b"id,value,value\n", &mut headers);
+    ///
+    /// assert_eq!(result, Err(Error::DuplicateHeader { index_a: 1, index_b: 2 }));
+    /// ```
+    pub fn read_headers<const C: usize, const B: usize>(
+        &mut self,
+        input: &[u8],
+        headers: &mut Headers<C, B>,
+    ) -> Result<usize> {
+        headers.clear();
+        let policy = self.config.duplicate_headers;
+        let case_insensitive = self.config.case_insensitive_headers;
+        let input = Input::single(&input);
+        let checked = self.verify_checksum(input)?;
+        let mut deserializer = Deserializer::new(self, input.before_checksum(checked));
+        let mut result = Ok(());
+        while result.is_ok() && !deserializer.record_end {
+            result = deserializer
+                .read_str()
+                .and_then(|name| headers.push(name, policy, case_insensitive));
+        }
+        let nread = deserializer.bytes_read();
+        let in_record = !deserializer.record_end;
+        self.last_nread = nread;
+        self.in_record = in_record;
+        self.skip_checksum(checked);
+        result.map(|_| self.last_nread)
+    }
+
+    /// Reads a header record and checks that its column names are the field names
+    /// of the struct `T`, as seen by serde, in the same order unless
+    /// [`Reader::unordered_headers`] is set.
+    ///
+    /// Returns the number of bytes read.
+    /// [`Error::HeaderMismatch`] is returned for the first column that doesn't match,
+    /// a missing column or an extra one. [`Error::Unsupported`] is returned if `T` isn't
+    /// a struct, or if it has more than 128 fields and the columns may be in any order.
+    ///
+    /// # Example
+    /// ```
+    /// use serde::Deserialize;
+    /// use serde_csv_core::de::{Error, Snippet};
+    ///
+    /// #[derive(Deserialize)]
+    /// struct Record {
+    ///     pub city: heapless::String<32>,
+    ///     pub population: u32,
+    /// }
+    ///
+    /// let mut reader = serde_csv_core::Reader::<16>::new();
+    ///
+    /// assert_eq!(reader.validate_headers::<Record>(b"city,population\n"), Ok(16));
+    /// assert_eq!(
+    ///     reader.validate_headers::<Record>(b"city,populaton\n"),
+    ///     Err(Error::HeaderMismatch {
+    ///         index: 1,
+    ///         expected: Snippet::new(b"population"),
+    ///         found: Snippet::new(b"populaton"),
+    ///     })
+    /// );
+    /// ```
+    pub fn validate_headers<'de, T>(&mut self, input: &[u8]) -> Result<usize>
+    where
+        T: Deserialize<'de>,
+    {
+        let fields = headers::struct_fields::<T>().ok_or(Error::Unsupported(
+            "Reader::validate_headers of a type other than a struct",
+        ))?;
+        let unordered = self.config.unordered_headers;
+        let case_insensitive = self.config.case_insensitive_headers;
+        if case_insensitive {
+            headers::check_case_folding(fields)?;
+        }
+        let same = |i: usize, name: &str| headers::same_name(fields[i], name, case_insensitive);
+        if unordered && fields.len() > 128 {
+            return Err(Error::Unsupported(
+                "Reader::validate_headers of a struct with over 128 fields in any order",
+            ));
+        }
+        let mismatch = |index: usize, expected: &str, found: &str| Error::HeaderMismatch {
+            index,
+            expected: Snippet::new(expected.as_bytes()),
+            found: Snippet::new(found.as_bytes()),
+        };
+        // Names of one field, including its aliases, are next to each other in `fields`.
+        let mut groups = headers::field_groups::<T>(fields);
+        // First names of the fields, used if the columns may be in any order.
+        let starts = match unordered {
+            true => groups
+                .by_ref()
+                .fold(0u128, |starts, group| starts | 1 << group.start),
+            false => 0,
+        };
+        let group_of = |i: usize| (0..=i).rev().find(|&start| starts & 1 << start != 0);
+        self.read_parts(Input::single(&input), None, None, None, |deserializer| {
+            // Fields matched by the columns read so far, used if the columns may be in any order.
+            let mut seen = 0u128;
+            let mut index = 0;
+            while !deserializer.record_end {
+                let name = deserializer.read_str()?;
+                let expected = match unordered {
+                    true => {
+                        let group = (0..fields.len())
+                            .find(|&i| same(i, name))
+                            .and_then(group_of)
+                            .filter(|&start| seen & 1 << start == 0);
+                        if let Some(start) = group {
+                            seen |= 1 << start;
+                        }
+                        group.ok_or("")
+                    }
+                    false => match groups.next() {
+                        Some(group) if group.clone().any(|i| same(i, name)) => Ok(group.start),
+                        Some(group) => Err(fields[group.start]),
+                        None => Err(""),
+                    },
+                };
+                if let Err(expected) = expected {
+                    return Err(mismatch(index, expected, name));
+                }
+                index += 1;
+            }
+            let missing = match unordered {
+                true => (0..fields.len()).find(|&i| starts & !seen & 1 << i != 0),
+                false => groups.next().map(|group| group.start),
+            };
+            match missing {
+                Some(i) => Err(mismatch(index, fields[i], "")),
+                None => Ok(()),
+            }
+        })
+        .map(|(_, nread, _)| nread)
+    }
+
+    /// Deserializes a given CSV byte slice into a value of type `T`, matching fields
+    /// to struct fields or map keys by the column names in `headers`.
+    ///
+    /// The first field of the record belongs to the first column and so on.
+    /// Columns without a matching struct field are skipped. Struct fields without a matching
+    /// column are left to serde, so `Option` fields are `None`, fields with `#[serde(default)]`
+    /// take their defaults and [`Error::MissingField`] is returned for the other ones.
+    /// Types other than top-level structs and maps are deserialized as in [`Reader::deserialize`].
+    ///
+    /// # Example
+    /// ```
+    /// use heapless::String;
+    /// use serde::Deserialize;
+    /// use serde_csv_core::de::Headers;
+    ///
+    /// #[derive(Debug, PartialEq, Eq, Deserialize)]
+    /// struct Record {
+    ///     pub city: String<32>,
+    ///     pub population: u32,
+    /// }
+    ///
+    /// let csv = b"population,country,city\n766683,Poland,Cracow\n";
+    ///
+    /// let mut reader = serde_csv_core::Reader::<32>::new();
+    /// let mut headers = Headers::<8, 64>::new();
+    /// let nread = reader.read_headers(&csv[..], &mut headers)?;
+    /// let (record, _): (Record, _) = reader.deserialize_with_headers(&csv[nread..], &headers)?;
+    ///
+    /// assert_eq!(record, Record {
+    ///     city: "Cracow".into(),
+    ///     population: 766_683,
+    /// });
+    /// # Ok::<(), serde_csv_core::de::Error>(())
+    /// ```
+    pub fn deserialize_with_headers<'de, T, const C: usize, const B: usize>(
+        &mut self,
+        input: &[u8],
+        headers: &Headers<C, B>,
+    ) -> Result<(T, usize)>
+    where
+        T: Deserialize<'de>,
+    {
+        self.deserialize_impl(input, Some(headers.names()), None, None)
+            .map(|(value, nread, _)| (value, nread))
+    }
+
+    /// Deserializes a map, like `heapless::LinearMap<K, V, N>`, from two records:
+    /// a record of keys followed by a record of values.
+    ///
+    /// The first field of the first record is the key of the first field of the second one
+    /// and so on. Returns the map and the number of bytes read from both records.
+    /// [`Error::FieldCount`] is returned if the records have different numbers of fields,
+    /// [`Error::FieldTooLong`] if a key or a value doesn't fit in its type
+    /// and [`Error::MapTooLong`] if the map runs out of capacity.
+    /// A struct is read like a map, so its fields are matched by the keys.
+    /// Both records are consumed even if an error is returned.
+    ///
+    /// # Example
+    /// ```
+    /// use heapless::{LinearMap, String};
+    ///
+    /// let csv = b"gain,\"offset, mV\"\n1.5,-20\n";
+    ///
+    /// let mut reader = serde_csv_core::Reader::<16>::new();
+    /// let (map, nread) = reader.deserialize_keyed::<LinearMap<String<16>, f32, 4>>(&csv[..])?;
+    ///
+    /// assert_eq!(map.get(&String::from("gain")), Some(&1.5));
+    /// assert_eq!(map.get(&String::from("offset, mV")), Some(&-20.0));
+    /// assert_eq!(nread, 26);
+    /// # Ok::<(), serde_csv_core::de::Error>(())
+    /// ```
+    pub fn deserialize_keyed<'de, T>(&mut self, input: &[u8]) -> Result<(T, usize)>
+    where
+        T: Deserialize<'de>,
+    {
+        let input = Input::single(&input);
+        let keys = RecordCursor::new(0, self.inner.line());
+        let (nkeys, keys_len) = self.scan_record(input, keys.offset);
+        let values = RecordCursor::new(keys_len, self.inner.line());
+        let (nvalues, values_len) = self.scan_record(input, values.offset);
+        let nread = keys_len + values_len;
+        let line = self.inner.line();
+        self.last_nread = nread;
+        self.in_record = false;
+        if nkeys != nvalues {
+            return Err(Error::FieldCount {
+                expected: nkeys,
+                got: nvalues,
+            });
+        }
+        let mut deserializer = Deserializer::new(self, input);
+        deserializer.keyed = Some(KeyedRecords {
+            keys,
+            values,
+            len: nkeys,
+        });
+        let result = T::deserialize(&mut deserializer);
+        self.inner.reset();
+        self.inner.set_line(line);
+        Ok((result?, nread))
+    }
+
+    /// Deserializes a record whose first field selects how the rest of it is deserialized.
+    ///
+    /// The first field is read as a string, then `select` is called with a [`Tagged`] record
+    /// that exposes the field with [`Tagged::tag`] and deserializes the remaining fields
+    /// with [`Tagged::deserialize`], so the tag can choose their type.
+    /// Otherwise it behaves like [`Reader::deserialize`].
+    ///
+    /// # Example
+    /// ```
+    /// use serde::Deserialize;
+    /// use serde_csv_core::de::Error;
+    ///
+    /// #[derive(Debug, PartialEq, Deserialize)]
+    /// struct Gps {
+    ///     lat: f32,
+    ///     lon: f32,
+    /// }
+    ///
+    /// #[derive(Debug, PartialEq, Deserialize)]
+    /// struct Imu {
+    ///     ax: i16,
+    ///     ay: i16,
+    ///     az: i16,
+    /// }
+    ///
+    /// #[derive(Debug, PartialEq)]
+    /// enum Sample {
+    ///     Gps(Gps),
+    ///     Imu(Imu),
+    /// }
+    ///
+    /// let csv = b"GPS,50.06,19.94\nIMU,1,-2,3\n";
+    ///
+    /// let mut reader = serde_csv_core::Reader::<16>::new();
+    /// let mut read = |input| {
+    ///     reader.deserialize_tagged(input, |record| match record.tag() {
+    ///         "GPS" => record.deserialize().map(Sample::Gps),
+    ///         "IMU" => record.deserialize().map(Sample::Imu),
+    ///         _ => Err(Error::Custom),
+    ///     })
+    /// };
+    ///
+    /// let (first, nread) = read(&csv[..])?;
+    /// let (second, _) = read(&csv[nread..])?;
+    ///
+    /// assert_eq!(first, Sample::Gps(Gps { lat: 50.06, lon: 19.94 }));
+    /// assert_eq!(second, Sample::Imu(Imu { ax: 1, ay: -2, az: 3 }));
+    /// # Ok::<(), Error>(())
+    /// ```
+    pub fn deserialize_tagged<T, F>(&mut self, input: &[u8], select: F) -> Result<(T, usize)>
+    where
+        F: FnOnce(Tagged<'_, '_, N>) -> Result<T>,
+    {
+        self.read_parts(Input::single(&input), None, None, None, |deserializer| {
+            let len = deserializer.read_str()?.len();
+            select(Tagged { deserializer, len })
+        })
+        .map(|(value, nread, _)| (value, nread))
+    }
+
+    /// Reads a record starting at `offset` without deserializing it,
+    /// returning the number of its fields and bytes.
+    fn scan_record(&mut self, input: Input<'_>, offset: usize) -> (usize, usize) {
+        if self.config.fixed_widths.is_some() {
+            return self.skip_fixed_record(input, offset, 0);
+        }
+        // Doesn't use `field_buffer`, which could have no capacity at all.
+        let mut scratch = [0; 16];
+        let mut nfields = 0;
+        let mut nread = 0;
+        loop {
+            let (result, r, _) = self
+                .inner
+                .read_field(input.rest(offset + nread), &mut scratch);
+            nread += r;
+            match result {
+                csv_core::ReadFieldResult::InputEmpty | csv_core::ReadFieldResult::OutputFull => {}
+                csv_core::ReadFieldResult::Field { record_end } => {
+                    nfields += 1;
+                    if record_end {
+                        break;
+                    }
+                }
+                csv_core::ReadFieldResult::End => break,
+            }
+        }
+        nread += self.consume_crlf(input, offset + nread);
+        (nfields, nread)
+    }
+
+    /// Deserializes a given CSV byte slice into a value of type `T`,
+    /// passing string fields through `interner`.
+    ///
+    /// Fields interned by `interner` are passed to the visitor as borrowed strings,
+    /// so they can be deserialized into `&str` without being copied.
+    /// Other string fields are passed as transient strings, as in [`Reader::deserialize`].
+    ///
+    /// # Example
+    /// ```
+    /// use serde_csv_core::de::FieldInterner;
+    ///
+    /// struct Levels;
+    ///
+    /// impl FieldInterner for Levels {
+    ///     fn intern(&mut self, value: &str) -> Option<&'static str> {
+    ///         ["INFO", "WARN", "ERROR"].into_iter().find(|level| *level == value)
+    ///     }
+    /// }
+    ///
+    /// let mut reader = serde_csv_core::Reader::<16>::new();
+    /// let ((id, level), _) = reader.deserialize_with_interner::<(u32, &str)>(b"1,WARN\n", &mut Levels)?;
+    ///
+    /// assert_eq!((id, level), (1, "WARN"));
+    /// # Ok::<(), serde_csv_core::de::Error>(())
+    /// ```
+    pub fn deserialize_with_interner<'de, T>(
+        &mut self,
+        input: &[u8],
+        interner: &mut dyn FieldInterner,
+    ) -> Result<(T, usize)>
+    where
+        T: Deserialize<'de>,
+    {
+        self.deserialize_impl(input, None, Some(interner), None)
+            .map(|(value, nread, _)| (value, nread))
+    }
+
+    /// Deserializes a given CSV byte slice into a value of type `T` that may borrow from it.
+    ///
+    /// String fields that appear in `input` exactly as they are deserialized, e.g. ones that
+    /// aren't quoted or escaped, are passed to the visitor as strings borrowed from `input`,
+    /// so they can be deserialized into `&str`, or into `Cow<str>` with `#[serde(borrow)]`
+    /// without being copied. Other string fields are passed as transient strings,
+    /// as in [`Reader::deserialize`], so a `Cow<str>` owns a copy of them.
+    ///
+    /// # Example
+    /// ```
+    /// use serde::Deserialize;
+    ///
+    /// #[derive(Debug, PartialEq, Deserialize)]
+    /// struct Record<'a> {
+    ///     city: &'a str,
+    ///     population: u32,
+    /// }
+    ///
+    /// let csv = b"Cracow,766683\n";
+    ///
+    /// let mut reader = serde_csv_core::Reader::<16>::new();
+    /// let (record, _) = reader.deserialize_borrowed::<Record>(csv)?;
+    ///
+    /// assert_eq!(record, Record { city: "Cracow", population: 766_683 });
+    /// # Ok::<(), serde_csv_core::de::Error>(())
+    /// ```
+    pub fn deserialize_borrowed<'de, T>(&mut self, input: &'de [u8]) -> Result<(T, usize)>
+    where
+        T: Deserialize<'de>,
+    {
+        self.read_parts(Input::single(&input), None, None, None, |deserializer| {
+            deserializer.borrowed = Some(input);
+            T::deserialize(deserializer)
+        })
+        .map(|(value, nread, _)| (value, nread))
+    }
+
+    /// Deserializes CSV data split into multiple slices into a value of type `T`.
+    ///
+    /// The slices are read in order as if they were one contiguous slice, e.g. the two halves
+    /// of a ring buffer, so fields can span multiple slices.
+    /// The second element of the resulting tuple is a number of bytes read from all the slices.
+    ///
+    /// # Example
+    /// ```
+    /// use heapless::String;
+    ///
+    /// let mut reader = serde_csv_core::Reader::<16>::new();
+    /// let parts: [&[u8]; 2] = [b"1,\"Cracow, Po", b"land\"\n2,Tokyo\n"];
+    /// let (record, nread) = reader.deserialize_from_slices::<(u8, String<16>)>(&parts)?;
+    ///
+    /// assert_eq!(record, (1, "Cracow, Poland".into()));
+    /// assert_eq!(nread, 19);
+    /// # Ok::<(), serde_csv_core::de::Error>(())
+    /// ```
+    pub fn deserialize_from_slices<'de, T>(&mut self, parts: &[&[u8]]) -> Result<(T, usize)>
+    where
+        T: Deserialize<'de>,
+    {
+        self.deserialize_parts(Input::new(parts), None, None, None)
+            .map(|(value, nread, _)| (value, nread))
+    }
+
+    fn deserialize_impl<'de, T>(
+        &mut self,
+        input: &[u8],
+        headers: Option<HeaderNames<'_>>,
+        interner: Option<&mut dyn FieldInterner>,
+        str_input: Option<&str>,
+    ) -> Result<(T, usize, usize)>
+    where
+        T: Deserialize<'de>,
+    {
+        self.deserialize_parts(Input::single(&input), headers, interner, str_input)
+    }
+
+    /// Returns `true` if `b` terminates records.
+    fn is_terminator(&self, b: u8) -> bool {
+        match self.config.terminator {
+            csv_core::Terminator::Any(terminator) => b == terminator,
+            _ => matches!(b, b'\r' | b'\n'),
+        }
+    }
+
+    /// Consumes the `\n` of a `\r\n` record terminator of a record that ends at `offset`
+    /// of `input`, which is otherwise left to be discarded at the start of the next record.
+    ///
+    /// Returns the number of bytes consumed.
+    fn consume_crlf(&mut self, input: Input<'_>, offset: usize) -> usize {
+        let after_cr = offset > 0 && input.get(offset - 1) == Some(b'\r');
+        if matches!(self.config.terminator, csv_core::Terminator::CRLF)
+            && after_cr
+            && input.get(offset) == Some(b'\n')
+        {
+            let (_, r, _) = self.inner.read_field(b"\n", &mut [0]);
+            return r;
+        }
+        0
+    }
+
+    fn deserialize_parts<'de, T>(
+        &mut self,
+        input: Input<'_>,
+        headers: Option<HeaderNames<'_>>,
+        interner: Option<&mut dyn FieldInterner>,
+        str_input: Option<&str>,
+    ) -> Result<(T, usize, usize)>
+    where
+        T: Deserialize<'de>,
+    {
+        self.read_parts(input, headers, interner, str_input, |deserializer| {
+            T::deserialize(deserializer)
+        })
+    }
+
+    /// Reads a record from `input` with `f`, like [`Reader::deserialize_parts`].
+    fn read_parts<'i, T, F>(
+        &mut self,
+        input: Input<'_>,
+        headers: Option<HeaderNames<'_>>,
+        interner: Option<&mut dyn FieldInterner>,
+        str_input: Option<&str>,
+        f: F,
+    ) -> Result<(T, usize, usize)>
+    where
+        F: FnOnce(&mut Deserializer<'_, 'i, N>) -> Result<T>,
+    {
+        let checked = self.verify_checksum(input)?;
+        let mut deserializer = Deserializer::new(self, input.before_checksum(checked));
+        deserializer.headers = headers;
+        deserializer.str_input = str_input;
+        if let Some(interner) = interner {
+            deserializer.interner = Some(interner);
+        }
+        let result = f(&mut deserializer);
+        let result = match deserializer.reader.config.expected_fields {
+            Some(expected) => deserializer.check_field_count(expected, result),
+            None => result,
+        };
+        let result = match deserializer.reader.config.max_fields_per_record {
+            usize::MAX => result,
+            _ => deserializer.check_max_fields(result),
+        };
+        let nread = deserializer.bytes_read();
+        let content_end = deserializer.content_end;
+        let in_record = !deserializer.record_end;
+        let nfields = deserializer.nfields;
+        self.last_nread = nread;
+        self.in_record = in_record;
+        self.fixed_column = if in_record { nfields } else { 0 };
+        self.skip_checksum(checked);
+        Ok((result?, self.last_nread, content_end))
+    }
+
+    /// Reads the record at the start of `input` and verifies its checksum,
+    /// if [`Reader::checksum`] is set, then rewinds to the start of the record.
+    fn verify_checksum(&mut self, input: Input<'_>) -> Result<Option<Checked>> {
+        let Some(checksum) = self.config.checksum else {
+            return Ok(None);
+        };
+        let line = self.inner.line();
+        let mut nread = 0;
+        // Offset of the delimiter before the last field.
+        let mut delimiter = None;
+        let mut field = [0; 8];
+        let mut len = 0;
+        loop {
+            let mut discard = [0; 8];
+            let output = match field.get_mut(len..) {
+                Some(output) if !output.is_empty() => output,
+                _ => &mut discard,
+            };
+            let (result, r, w) = self.inner.read_field(input.rest(nread), output);
+            nread += r;
+            len += w;
+            match result {
+                csv_core::ReadFieldResult::InputEmpty | csv_core::ReadFieldResult::OutputFull => {}
+                csv_core::ReadFieldResult::Field { record_end: false } => {
+                    delimiter = Some(nread - 1);
+                    len = 0;
+                }
+                csv_core::ReadFieldResult::Field { record_end: true } => break,
+                csv_core::ReadFieldResult::End => {
+                    self.inner.reset();
+                    self.inner.set_line(line);
+                    return Ok(None);
+                }
+            }
+        }
+        nread += self.consume_crlf(input, nread);
+        let checked = Checked {
+            content_end: delimiter.unwrap_or(0),
+            nread,
+            line: self.inner.line(),
+        };
+
+        let start = (0..)
+            .find(|&i| !input.get(i).is_some_and(|b| self.is_terminator(b)))
+            .unwrap_or_default();
+        let mut crc = checksum.init();
+        let mut offset = start;
+        while offset < checked.content_end {
+            let rest = input.rest(offset);
+            let n = rest.len().min(checked.content_end - offset);
+            crc = checksum.update(crc, &rest[..n]);
+            offset += n;
+        }
+        let actual = checksum.value(crc);
+        let result = match field.get(..len).and_then(|hex| checksum.parse_hex(hex)) {
+            Some(expected) if expected == actual => Ok(Some(checked)),
+            Some(expected) => Err(Error::ChecksumMismatch { expected, actual }),
+            None => Err(Error::InvalidChecksum(Snippet::new(
+                &field[..len.min(field.len())],
+            ))),
+        };
+        if result.is_err() {
+            // The record is skipped, like after any other error.
+            self.last_nread = checked.nread;
+            self.in_record = false;
+            self.inner.reset();
+            self.inner.set_line(checked.line);
+        } else {
+            self.inner.reset();
+            self.inner.set_line(line);
+        }
+        result
+    }
+
+    /// Skips the checksum field of a record verified by [`Reader::verify_checksum`]
+    /// after the rest of it was read.
+    fn skip_checksum(&mut self, checked: Option<Checked>) {
+        if let Some(checked) = checked {
+            self.inner.reset();
+            self.inner.set_line(checked.line);
+            self.last_nread = checked.nread;
+            self.in_record = false;
+        }
+    }
+
+    /// Deserializes a given CSV byte slice into a value of type `T`,
+    /// also returning the range of `input` taken by the record the value was read from.
+    ///
+    /// The raw record excludes the record terminator and empty lines that precede the record,
+    /// so the start of the range is the offset of the record in `input`.
+    /// It covers all the fields of the record: trailing fields ignored by `T` are skipped,
+    /// so the record is always read up to its terminator.
+    ///
+    /// # Example
+    /// ```
+    /// let csv = b"\r\n1,\"Cracow, Poland\",PL\r\n2,Tokyo,JP\r\n";
+    ///
+    /// let mut reader = serde_csv_core::Reader::<16>::new();
+    /// let (record, raw, nread) = reader.deserialize_with_raw::<(u8, heapless::String<16>)>(&csv[..])?;
+    ///
+    /// assert_eq!(record, (1, "Cracow, Poland".into()));
+    /// assert_eq!(raw, 2..23);
+    /// assert_eq!(&csv[raw], b"1,\"Cracow, Poland\",PL");
+    /// assert_eq!(nread, 25);
+    /// # Ok::<(), serde_csv_core::de::Error>(())
+    /// ```
+    pub fn deserialize_with_raw<'de, T>(
+        &mut self,
+        input: &[u8],
+    ) -> Result<(T, core::ops::Range<usize>, usize)>
+    where
+        T: Deserialize<'de>,
+    {
+        let (value, mut nread, mut end) = self.deserialize_impl(input, None, None, None)?;
+        if self.in_record {
+            nread += self.skip_record(&input[nread..]);
+            self.last_nread = nread;
+            end = input[..nread]
+                .iter()
+                .rposition(|&b| !self.is_terminator(b))
+                .map_or(0, |i| i + 1);
+        }
+        let start = input[..end]
+            .iter()
+            .position(|&b| !self.is_terminator(b))
+            .unwrap_or(end);
+        Ok((value, start..end, nread))
+    }
+
+    /// Deserializes a given CSV byte slice into a value of type `T`.
+    ///
+    /// The second element of the resulting tuple is the unread rest of the input.
+    ///
+    /// # Example
+    /// ```
+    /// use heapless::{String, Vec};
+    /// use serde::Deserialize;
+    ///
+    /// #[derive(Debug, PartialEq, Eq, Deserialize)]
+    /// struct Record {
+    ///     pub country: String<32>,
+    ///     pub city: String<32>,
+    ///     pub population: u32,
+    /// }
+    ///
+    /// let mut reader = serde_csv_core::Reader::<32>::new();
+    /// let mut records: Vec<Record, 2> = Vec::new();
+    /// let mut rest = &b"Poland,Cracow,766683\nJapan,Tokyo,13515271\n"[..];
+    /// while !rest.is_empty() {
+    ///     let (record, tail) = reader.deserialize_rest::<Record>(rest)?;
+    ///     records.push(record).unwrap();
+    ///     rest = tail;
+    /// }
+    ///
+    /// assert_eq!(records.len(), 2);
+    /// assert_eq!(records[1].city, "Tokyo");
+    /// # Ok::<(), serde_csv_core::de::Error>(())
+    /// ```
+    pub fn deserialize_rest<'a, 'de, T>(&mut self, input: &'a [u8]) -> Result<(T, &'a [u8])>
+    where
+        T: Deserialize<'de>,
+    {
+        let (value, nread) = self.deserialize(input)?;
+        Ok((value, &input[nread..]))
     }
-}
 
-impl<const N: usize> Reader<N> {
-    /// Constructs a new reader.
-    pub fn new() -> Self {
-        Self::default()
+    /// Returns the number of bytes read by the last call to [`Reader::deserialize`]
+    /// or [`Reader::skip_record`], including calls that returned an error.
+    ///
+    /// After an error, the reader is left in the middle of a record.
+    /// Pass the input past these bytes to [`Reader::skip_record`] to resynchronize
+    /// to the start of the next record.
+    ///
+    /// # Example
+    /// ```
+    /// let csv = b"1,x,3\n4,5,6\n";
+    ///
+    /// let mut reader = serde_csv_core::Reader::<16>::new();
+    /// assert!(reader.deserialize::<(u8, u8, u8)>(&csv[..]).is_err());
+    ///
+    /// let mut nread = reader.last_bytes_read();
+    /// nread += reader.skip_record(&csv[nread..]);
+    ///
+    /// let (record, _) = reader.deserialize::<(u8, u8, u8)>(&csv[nread..])?;
+    /// assert_eq!(record, (4, 5, 6));
+    /// # Ok::<(), serde_csv_core::de::Error>(())
+    /// ```
+    pub fn last_bytes_read(&self) -> usize {
+        self.last_nread
     }
 
-    /// Constructs a new reader from [`csv_core::ReaderBuilder`].
+    /// Returns `true` if the last call to [`Reader::deserialize`] read its record up to
+    /// the record terminator, or the end of the input.
+    ///
+    /// Returns `false` if fields remain unread, e.g. when a fixed-size array
+    /// doesn't take all the fields of the record, or after an error in the middle of a record.
+    /// Such a record can be finished with [`Reader::skip_record`].
     ///
     /// # Example
     /// ```
-    /// use serde_csv_core::csv_core;
+    /// let csv = b"0,1,2,3\n4,5,6\n";
     ///
-    /// let reader = serde_csv_core::Reader::<16>::from_builder(
-    ///     csv_core::ReaderBuilder::new()
-    ///         .delimiter(b'-')
+    /// let mut reader = serde_csv_core::Reader::<16>::new();
+    /// let (record, mut nread) = reader.deserialize::<[u8; 3]>(&csv[..])?;
+    ///
+    /// assert_eq!(record, [0, 1, 2]);
+    /// assert!(!reader.finished_record());
+    ///
+    /// nread += reader.skip_record(&csv[nread..]);
+    /// let (record, _) = reader.deserialize::<[u8; 3]>(&csv[nread..])?;
+    ///
+    /// assert_eq!(record, [4, 5, 6]);
+    /// assert!(reader.finished_record());
+    /// # Ok::<(), serde_csv_core::de::Error>(())
+    /// ```
+    pub fn finished_record(&self) -> bool {
+        !self.in_record
+    }
+
+    /// Skips the rest of a record left unfinished by the last call to [`Reader::deserialize`],
+    /// returning the number of bytes read.
+    ///
+    /// Fields are discarded regardless of their length.
+    /// Does nothing if the last call finished reading its record.
+    pub fn skip_record(&mut self, input: &[u8]) -> usize {
+        self.skip_rest(input).1
+    }
+
+    /// Skips the rest of a record like [`Reader::skip_record`], returning the number of bytes read.
+    ///
+    /// If `validate` is `true` and [`Reader::with_expected_fields`] is set,
+    /// the fields read by the last call to [`Reader::deserialize`] and the skipped fields
+    /// are counted together, and [`Error::FieldCount`] is returned if the record
+    /// has a different number of fields. The record is skipped either way,
+    /// and [`Reader::last_bytes_read`] returns the number of bytes read.
+    ///
+    /// # Example
+    /// ```
+    /// use serde_csv_core::de::Error;
+    ///
+    /// let csv = b"1,x,3,4\n";
+    ///
+    /// let mut reader = serde_csv_core::Reader::<16>::new();
+    /// reader.with_expected_fields(3);
+    ///
+    /// assert!(reader.deserialize::<(u8, u8, u8)>(&csv[..]).is_err());
+    /// let result = reader.skip_record_checked(&csv[reader.last_bytes_read()..], true);
+    ///
+    /// assert_eq!(result, Err(Error::FieldCount { expected: 3, got: 4 }));
+    /// ```
+    pub fn skip_record_checked(&mut self, input: &[u8], validate: bool) -> Result<usize> {
+        let expected = self
+            .config
+            .expected_fields
+            .filter(|_| validate && self.in_record);
+        let read = self.fixed_column;
+        let (skipped, nread) = self.skip_rest(input);
+        match expected {
+            Some(expected) if read + skipped != expected => Err(Error::FieldCount {
+                expected,
+                got: read + skipped,
+            }),
+            _ => Ok(nread),
+        }
+    }
+
+    /// Skips the rest of an unfinished record, returning the number of fields and bytes read.
+    fn skip_rest(&mut self, input: &[u8]) -> (usize, usize) {
+        if !self.in_record {
+            self.last_nread = 0;
+            return (0, 0);
+        }
+        self.in_record = false;
+        let column = core::mem::take(&mut self.fixed_column);
+        if self.config.fixed_widths.is_some() {
+            let (nfields, nread) = self.skip_fixed_record(Input::single(&input), 0, column);
+            self.last_nread = nread;
+            return (nfields, nread);
+        }
+        // Doesn't use `field_buffer`, which could have no capacity at all.
+        let mut scratch = [0; 16];
+        let mut nfields = 0;
+        let mut nread = 0;
+        loop {
+            let (result, r, _) = self.inner.read_field(&input[nread..], &mut scratch);
+            nread += r;
+            match result {
+                csv_core::ReadFieldResult::InputEmpty | csv_core::ReadFieldResult::OutputFull => {}
+                csv_core::ReadFieldResult::Field { record_end: false } => nfields += 1,
+                csv_core::ReadFieldResult::Field { record_end: true } => {
+                    nfields += 1;
+                    break;
+                }
+                csv_core::ReadFieldResult::End => break,
+            }
+        }
+        nread += self.consume_crlf(Input::single(&input), nread);
+        self.last_nread = nread;
+        (nfields, nread)
+    }
+
+    /// Reads the columns of a fixed-width record from `column` on, starting at `offset`,
+    /// without deserializing them, returning the number of columns and bytes read.
+    ///
+    /// A malformed record is read up to its record terminator.
+    fn skip_fixed_record(
+        &mut self,
+        input: Input<'_>,
+        offset: usize,
+        column: usize,
+    ) -> (usize, usize) {
+        let mut deserializer = Deserializer::new(self, input);
+        deserializer.nread = offset;
+        deserializer.row_start = offset;
+        deserializer.nfields = column;
+        let mut result = Ok(());
+        while result.is_ok() && !deserializer.record_end {
+            result = deserializer.skip_field();
+        }
+        let nfields = deserializer.nfields - column;
+        let mut end = deserializer.bytes_read();
+        if result.is_err() {
+            while input.get(end).is_some_and(|b| !self.is_terminator(b)) {
+                end += 1;
+            }
+            if let Some(b) = input.get(end) {
+                let crlf = b == b'\r' && input.get(end + 1) == Some(b'\n');
+                end += 1 + usize::from(crlf);
+            }
+        }
+        (nfields, end - offset)
+    }
+
+    /// Returns an iterator that deserializes the records of `input` into values of type `T`,
+    /// skipping the records that fail to deserialize instead of stopping at them.
+    ///
+    /// An item is either a value, or an error together with the range of `input`
+    /// taken by the skipped record, including its terminator.
+    /// Fields of a record that aren't taken by `T` are skipped as well.
+    /// The iterator ends at the end of the input.
+    ///
+    /// # Example
+    /// ```
+    /// use serde_csv_core::de::{Error, Snippet};
+    ///
+    /// let csv = b"1,2\n3,x\n5,6\n";
+    ///
+    /// let mut reader = serde_csv_core::Reader::<16>::new();
+    /// let mut records = reader.records_lenient::<(u8, u8)>(&csv[..]);
+    ///
+    /// assert_eq!(records.next(), Some(Ok((1, 2))));
+    /// assert_eq!(
+    ///     records.next(),
+    ///     Some(Err((Error::InvalidInt(Snippet::new(b"x")), 4..8)))
     /// );
+    /// assert_eq!(records.next(), Some(Ok((5, 6))));
+    /// assert_eq!(records.next(), None);
     /// ```
-    pub fn from_builder(builder: impl Borrow<csv_core::ReaderBuilder>) -> Self {
-        Self {
-            inner: builder.borrow().build(),
-            field_buffer: [0; N],
+    pub fn records_lenient<'a, T>(&'a mut self, input: &'a [u8]) -> RecordsLenient<'a, N, T>
+    where
+        T: DeserializeOwned,
+    {
+        RecordsLenient {
+            reader: self,
+            input,
+            nread: 0,
+            _type: core::marker::PhantomData,
         }
     }
 
-    /// Deserializes a given CSV byte slice into a value of type `T`.
+    /// Finds the first record whose first field is equal to `key` and deserializes it
+    /// into a value of type `T`.
     ///
-    /// The second element of the resulting tuple is a number of bytes read.
+    /// The first field is compared after unescaping, so quoted keys are supported.
+    /// Records that don't match are skipped without being deserialized.
+    /// The second element of the resulting tuple is a number of bytes read,
+    /// up to the end of the matching record.
+    /// Returns `None` if no record matches.
+    ///
+    /// # Example
+    /// ```
+    /// use heapless::String;
+    ///
+    /// let csv = b"Poland,Warsaw\nJapan,Tokyo\n\"United Kingdom\",London\n";
+    ///
+    /// let mut reader = serde_csv_core::Reader::<32>::new();
+    /// let found = reader.find_by_key::<(String<32>, String<32>)>(csv, b"Japan")?;
+    ///
+    /// assert_eq!(found, Some((("Japan".into(), "Tokyo".into()), 26)));
+    /// # Ok::<(), serde_csv_core::de::Error>(())
+    /// ```
+    pub fn find_by_key<'de, T>(&mut self, input: &[u8], key: &[u8]) -> Result<Option<(T, usize)>>
+    where
+        T: Deserialize<'de>,
+    {
+        if self.config.fixed_widths.is_some() {
+            let found = self.find_fixed_record(input, 1, |_, field| field == key)?;
+            // The number of bytes read covers the matching record.
+            return Ok(found.map(|(value, _)| (value, self.last_nread)));
+        }
+        // Doesn't use `field_buffer`, so keys of any length can be compared.
+        let mut scratch = [0; 16];
+        let mut nread = 0;
+        loop {
+            let start = nread;
+            let line = self.inner.line();
+            // Number of bytes of the key matched so far.
+            let mut matched = Some(0);
+            let record_end = loop {
+                let (result, r, w) = self.inner.read_field(&input[nread..], &mut scratch);
+                nread += r;
+                matched = matched
+                    .filter(|&m| key[m..].starts_with(&scratch[..w]))
+                    .map(|m| m + w);
+                match result {
+                    csv_core::ReadFieldResult::InputEmpty
+                    | csv_core::ReadFieldResult::OutputFull => {}
+                    csv_core::ReadFieldResult::Field { record_end } => break record_end,
+                    csv_core::ReadFieldResult::End => {
+                        self.last_nread = nread;
+                        return Ok(None);
+                    }
+                }
+            };
+            if matched == Some(key.len()) {
+                // Read the record again from its start.
+                self.inner.reset();
+                self.inner.set_line(line);
+                let value = self.deserialize_at(input, start)?;
+                return Ok(Some((value, self.last_nread)));
+            }
+            self.in_record = !record_end;
+            nread += self.skip_record(&input[nread..]);
+        }
+    }
+
+    /// Finds the first record with a field matching `predicate` and deserializes it
+    /// into a value of type `T`.
+    ///
+    /// `predicate` is called with the index and the unescaped contents of every field,
+    /// until it returns `true`. Then, the whole record is deserialized.
+    /// Records that don't match are not deserialized.
+    /// The second element of the resulting tuple is the offset of the matching record in `input`.
+    /// [`Reader::last_bytes_read`] returns the number of bytes read up to the end of it.
+    /// Returns `None` if no record matches.
+    ///
+    /// [`Error::Overflow`] is returned if a field doesn't fit in the internal buffer.
     ///
     /// # Example
     /// ```
     /// use heapless::String;
+    ///
+    /// let csv = b"1,INFO,started\n2,ERROR,disk full\n3,INFO,stopped\n";
+    ///
+    /// let mut reader = serde_csv_core::Reader::<16>::new();
+    /// let found = reader.find_record::<(u32, String<8>, String<16>), _>(csv, |i, field| {
+    ///     i == 1 && field == b"ERROR"
+    /// })?;
+    ///
+    /// assert_eq!(found, Some(((2, "ERROR".into(), "disk full".into()), 15)));
+    /// # Ok::<(), serde_csv_core::de::Error>(())
+    /// ```
+    pub fn find_record<'de, T, P>(
+        &mut self,
+        input: &[u8],
+        mut predicate: P,
+    ) -> Result<Option<(T, usize)>>
+    where
+        T: Deserialize<'de>,
+        P: FnMut(usize, &[u8]) -> bool,
+    {
+        if self.config.fixed_widths.is_some() {
+            return self.find_fixed_record(input, usize::MAX, predicate);
+        }
+        let mut nread = 0;
+        loop {
+            let start = nread;
+            let line = self.inner.line();
+            let mut index = 0;
+            let mut len = 0;
+            loop {
+                let (result, r, w) = read_field(
+                    &mut self.inner,
+                    &input[nread..],
+                    &mut self.field_buffer[len..],
+                );
+                nread += r;
+                len += w;
+                let record_end = match result {
+                    // The next read finishes the field at the end of input.
+                    csv_core::ReadFieldResult::InputEmpty => continue,
+                    csv_core::ReadFieldResult::OutputFull => {
+                        self.last_nread = nread;
+                        self.in_record = true;
+                        return Err(Error::Overflow);
+                    }
+                    csv_core::ReadFieldResult::Field { record_end } => record_end,
+                    csv_core::ReadFieldResult::End => {
+                        self.last_nread = nread;
+                        return Ok(None);
+                    }
+                };
+                if predicate(index, &self.field_buffer[..len]) {
+                    // Read the record again from its start.
+                    self.inner.reset();
+                    self.inner.set_line(line);
+                    let value = self.deserialize_at(input, start)?;
+                    return Ok(Some((value, start)));
+                }
+                if record_end {
+                    break;
+                }
+                index += 1;
+                len = 0;
+            }
+        }
+    }
+
+    /// Deserializes the record at `start` of `input`, setting [`Reader::last_bytes_read`]
+    /// to the number of bytes read from the start of `input`, even on error.
+    fn deserialize_at<'de, T>(&mut self, input: &[u8], start: usize) -> Result<T>
+    where
+        T: Deserialize<'de>,
+    {
+        let result = self.deserialize(&input[start..]);
+        self.last_nread += start;
+        result.map(|(value, _)| value)
+    }
+
+    /// Finds the first fixed-width record with one of its first `columns` columns
+    /// matching `predicate` and deserializes it, like [`Reader::find_record`].
+    ///
+    /// The rest of the columns of a record are skipped without being compared.
+    fn find_fixed_record<'de, T, P>(
+        &mut self,
+        input: &[u8],
+        columns: usize,
+        mut predicate: P,
+    ) -> Result<Option<(T, usize)>>
+    where
+        T: Deserialize<'de>,
+        P: FnMut(usize, &[u8]) -> bool,
+    {
+        let mut nread = 0;
+        while nread < input.len() {
+            let start = nread;
+            let mut deserializer = Deserializer::new(self, Input::single(&input));
+            deserializer.nread = start;
+            deserializer.row_start = start;
+            let mut result = Ok(false);
+            while result == Ok(false) && !deserializer.record_end {
+                let index = deserializer.nfields;
+                result = match index < columns {
+                    true => deserializer
+                        .read_field_impl(false)
+                        .map(|len| predicate(index, &deserializer.reader.field_buffer[..len])),
+                    false => deserializer.skip_field().map(|_| false),
+                };
+            }
+            nread = deserializer.bytes_read();
+            let (record_end, nfields) = (deserializer.record_end, deserializer.nfields);
+            match result {
+                Ok(true) => {
+                    let value = self.deserialize_at(input, start)?;
+                    return Ok(Some((value, start)));
+                }
+                Ok(false) => {}
+                Err(e) => {
+                    self.last_nread = nread;
+                    self.in_record = !record_end;
+                    self.fixed_column = if record_end { 0 } else { nfields };
+                    return Err(e);
+                }
+            }
+        }
+        self.last_nread = nread;
+        Ok(None)
+    }
+
+    /// Deserializes a given CSV byte vector into a value of type `T`.
+    ///
+    /// The second element of the resulting tuple is a number of bytes read.
+    ///
+    /// # Example
+    /// ```
+    /// use heapless::{String, Vec};
     /// use serde::Deserialize;
     ///
     /// #[derive(Debug, PartialEq, Eq, Deserialize)]
@@ -59,10 +2126,10 @@ impl<const N: usize> Reader<N> {
     ///     pub population: u32,
     /// }
     ///
-    /// let csv = b"Poland,Cracow,766683\n";
+    /// let csv: Vec<u8, 32> = Vec::from_slice(b"Poland,Cracow,766683\n").unwrap();
     ///
     /// let mut reader = serde_csv_core::Reader::<32>::new();
-    /// let (record, nread)  = reader.deserialize::<Record>(&csv[..])?;
+    /// let (record, nread) = reader.deserialize_from_vec::<Record, 32>(&csv)?;
     ///
     /// assert_eq!(record, Record {
     ///     country: "Poland".into(),
@@ -72,13 +2139,15 @@ impl<const N: usize> Reader<N> {
     /// assert_eq!(nread, 21);
     /// # Ok::<(), serde_csv_core::de::Error>(())
     /// ```
-    pub fn deserialize<'de, T>(&mut self, input: &[u8]) -> Result<(T, usize)>
+    #[cfg(feature = "heapless")]
+    pub fn deserialize_from_vec<'de, T, const M: usize>(
+        &mut self,
+        input: &Vec<u8, M>,
+    ) -> Result<(T, usize)>
     where
         T: Deserialize<'de>,
     {
-        let mut deserializer = Deserializer::new(self, input);
-        let value = T::deserialize(&mut deserializer)?;
-        Ok((value, deserializer.bytes_read()))
+        self.deserialize(input)
     }
 }
 
@@ -87,18 +2156,142 @@ impl<const N: usize> Reader<N> {
 pub enum Error {
     /// Buffer overflow.
     Overflow,
+    /// A field transcoded to UTF-8 doesn't fit in the internal buffer, see [`Reader::encoding`].
+    TranscodedTooLong {
+        /// Length of the transcoded field, in bytes.
+        len: usize,
+    },
     /// Expected an empty field.
     ExpectedEmpty,
     /// Invalid boolean value. Expected either `true` or `false`.
-    InvalidBool,
+    InvalidBool(Snippet),
     /// Invalid integer.
-    InvalidInt,
+    InvalidInt(Snippet),
     /// Invalid floating-point number.
-    InvalidFloat,
+    InvalidFloat(Snippet),
+    /// An integer has a leading zero, see [`Reader::reject_leading_zeros`].
+    LeadingZero,
     /// Invalid UTF-8 encoded character.
     InvalidUtf8Char,
     /// Invalid UTF-8 encoded string.
     InvalidUtf8String,
+    /// A field didn't fit in the capacity of the target type, e.g. `heapless::String<N>`.
+    FieldTooLong {
+        /// Length of the unescaped field in bytes.
+        len: usize,
+    },
+    /// A string is longer than allowed by [`Reader::max_str_len`].
+    StrTooLong {
+        /// Length of the string in bytes.
+        len: usize,
+    },
+    /// A record has more fields than allowed by [`Reader::max_fields_per_record`].
+    TooManyFields,
+    /// A sequence has more elements than the capacity of the target type,
+    /// e.g. `heapless::Vec<T, N>`.
+    SeqTooLong {
+        /// Number of elements read until the capacity was exceeded.
+        len: usize,
+    },
+    /// A sequence of unknown length, e.g. `heapless::Vec<T, N>`, consumed the rest of a record,
+    /// but more fields were expected after it.
+    SeqNotLast,
+    /// A map has more entries than the capacity of the target type,
+    /// e.g. `heapless::LinearMap<K, V, N>`.
+    MapTooLong {
+        /// Number of entries read until the capacity was exceeded.
+        len: usize,
+    },
+    /// A record is longer than allowed by [`Reader::max_record_len`].
+    RecordTooLong {
+        /// Length of the record read until the limit was exceeded, in bytes.
+        len: usize,
+    },
+    /// A record ends at the end of the input without a record terminator,
+    /// see [`Reader::require_terminator`], or a fixed-width record isn't followed by one,
+    /// see [`Reader::fixed_widths`].
+    MissingTerminator,
+    /// A fixed-width record ends before its last column, see [`Reader::fixed_widths`].
+    ShortRecord {
+        /// Length of the record in bytes.
+        len: usize,
+    },
+    /// A field isn't encoded correctly for [`Reader::field_decoder`].
+    InvalidEncoding,
+    /// A record has a different number of fields than set by [`Reader::with_expected_fields`].
+    FieldCount {
+        /// Expected number of fields.
+        expected: usize,
+        /// Actual number of fields.
+        got: usize,
+    },
+    /// Two columns of a header record have the same name.
+    DuplicateHeader {
+        /// Index of the first column with the name.
+        index_a: usize,
+        /// Index of the second column with the name.
+        index_b: usize,
+    },
+    /// A column of a header record doesn't match the struct field expected by
+    /// [`Reader::validate_headers`].
+    HeaderMismatch {
+        /// Index of the column.
+        index: usize,
+        /// Snippet of the name of the expected field, empty if there's no such field.
+        expected: Snippet,
+        /// Snippet of the name of the column, empty if there's no such column.
+        found: Snippet,
+    },
+    /// A [`Nested`](crate::Nested) record didn't fit in its buffer.
+    NestedOverflow,
+    /// The checksum of a record doesn't match the one in its last field,
+    /// see [`Reader::checksum`].
+    ChecksumMismatch {
+        /// Checksum read from the record.
+        expected: u32,
+        /// Checksum of the record's contents.
+        actual: u32,
+    },
+    /// The last field of a record isn't a checksum of the configured type,
+    /// see [`Reader::checksum`].
+    ///
+    /// Contains a snippet of the field.
+    InvalidChecksum(Snippet),
+    /// A field has a different type than expected by the target type.
+    ///
+    /// Contains a snippet of the value, or a description of its type.
+    InvalidType(Snippet),
+    /// A field has an unexpected value, e.g. an integer out of the range accepted
+    /// by the target type.
+    ///
+    /// Contains a snippet of the value.
+    InvalidValue(Snippet),
+    /// A value has an unexpected length, e.g. a record with fewer fields than the target type.
+    InvalidLength {
+        /// Length of the value, or the number of fields of a record that's too short.
+        len: usize,
+    },
+    /// An enum variant name isn't one of the variants of the target type.
+    ///
+    /// Contains a snippet of the name.
+    UnknownVariant(Snippet),
+    /// A struct field name isn't one of the fields of the target type.
+    ///
+    /// Contains a snippet of the name.
+    UnknownField(Snippet),
+    /// A field of the target struct is missing.
+    MissingField(&'static str),
+    /// A field of the target struct appears more than once.
+    DuplicateField(&'static str),
+    /// Two field names of the target struct differ only in case, so a column name can't be
+    /// matched to one of them, see [`Reader::case_insensitive_headers`].
+    ///
+    /// Contains the latter of the names.
+    AmbiguousField(&'static str),
+    /// The deserializer was asked to perform an operation it doesn't support.
+    ///
+    /// Contains the name of the operation, e.g. `Deserializer::deserialize_any`.
+    Unsupported(&'static str),
     /// Error with a custom message had to be discarded.
     Custom,
 }
@@ -107,130 +2300,1020 @@ macro_rules! impl_format {
     ($self:ident, $write:ident, $f:ident) => {
         match $self {
             Self::Overflow => $write!($f, "Buffer overflow."),
+            Self::TranscodedTooLong { len } => {
+                $write!(
+                    $f,
+                    "Transcoded field of length {} overflows the buffer.",
+                    len
+                )
+            }
             Self::ExpectedEmpty => $write!($f, "Expected an empty field."),
-            Self::InvalidBool => {
+            Self::InvalidBool(field) => {
                 $write!(
                     $f,
-                    "Invalid boolean value. Expected either `true` or `false`."
+                    "Invalid boolean value `{}`. Expected either `true` or `false`.",
+                    field
                 )
             }
-            Self::InvalidInt => $write!($f, "Invalid integer."),
-            Self::InvalidFloat => $write!($f, "Invalid floating-point number."),
+            Self::InvalidInt(field) => $write!($f, "Invalid integer `{}`.", field),
+            Self::InvalidFloat(field) => {
+                $write!($f, "Invalid floating-point number `{}`.", field)
+            }
+            Self::LeadingZero => $write!($f, "Integer has a leading zero."),
             Self::InvalidUtf8Char => $write!($f, "Invalid UTF-8 encoded character."),
             Self::InvalidUtf8String => $write!($f, "Invalid UTF-8 encoded string."),
+            Self::FieldTooLong { len } => {
+                $write!($f, "Field of length {} exceeds the target's capacity.", len)
+            }
+            Self::StrTooLong { len } => {
+                $write!($f, "String of length {} exceeds the limit.", len)
+            }
+            Self::TooManyFields => $write!($f, "Record has too many fields."),
+            Self::SeqTooLong { len } => {
+                $write!(
+                    $f,
+                    "Sequence of length {} exceeds the target's capacity.",
+                    len
+                )
+            }
+            Self::SeqNotLast => $write!($f, "Sequence of unknown length is not the last field."),
+            Self::MapTooLong { len } => {
+                $write!($f, "Map of length {} exceeds the target's capacity.", len)
+            }
+            Self::RecordTooLong { len } => {
+                $write!($f, "Record of length {} exceeds the limit.", len)
+            }
+            Self::MissingTerminator => $write!($f, "Record has no terminator."),
+            Self::ShortRecord { len } => {
+                $write!($f, "Record of length {} ends before its last column.", len)
+            }
+            Self::InvalidEncoding => $write!($f, "Invalid field encoding."),
+            Self::FieldCount { expected, got } => {
+                $write!($f, "Record has {} fields instead of {}.", got, expected)
+            }
+            Self::DuplicateHeader { index_a, index_b } => {
+                $write!(
+                    $f,
+                    "Header columns {} and {} have the same name.",
+                    index_a,
+                    index_b
+                )
+            }
+            Self::HeaderMismatch {
+                index,
+                expected,
+                found,
+            } => {
+                $write!(
+                    $f,
+                    "Header column {} is `{}` instead of `{}`.",
+                    index,
+                    found,
+                    expected
+                )
+            }
+            Self::NestedOverflow => $write!($f, "Nested record overflow."),
+            Self::ChecksumMismatch { expected, actual } => {
+                $write!(
+                    $f,
+                    "Record checksum {:X} doesn't match the expected {:X}.",
+                    actual,
+                    expected
+                )
+            }
+            Self::InvalidChecksum(field) => $write!($f, "Invalid checksum `{}`.", field),
+            Self::InvalidType(value) => $write!($f, "Invalid type of `{}`.", value),
+            Self::InvalidValue(value) => $write!($f, "Invalid value `{}`.", value),
+            Self::InvalidLength { len } => $write!($f, "Invalid length {}.", len),
+            Self::UnknownVariant(variant) => $write!($f, "Unknown variant `{}`.", variant),
+            Self::UnknownField(field) => $write!($f, "Unknown field `{}`.", field),
+            Self::MissingField(field) => $write!($f, "Missing field `{}`.", field),
+            Self::DuplicateField(field) => $write!($f, "Duplicate field `{}`.", field),
+            Self::AmbiguousField(field) => {
+                $write!($f, "Field `{}` differs from another only in case.", field)
+            }
+            Self::Unsupported(operation) => $write!($f, "`{}` is not supported.", operation),
             Self::Custom => $write!($f, "CSV does not match deserializer's expected format."),
         }
     };
 }
 
-/// Alias for a `core::result::Result` with the error type `serde_csv_core::de::Error`.
-pub type Result<T> = core::result::Result<T, Error>;
+/// Alias for a `core::result::Result` with the error type `serde_csv_core::de::Error`.
+pub type Result<T> = core::result::Result<T, Error>;
+
+impl core::fmt::Display for Error {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        impl_format!(self, write, f)
+    }
+}
+
+impl serde::de::StdError for Error {}
+
+impl serde::de::Error for Error {
+    fn custom<T>(_msg: T) -> Self
+    where
+        T: core::fmt::Display,
+    {
+        Self::Custom
+    }
+
+    fn invalid_type(unexp: serde::de::Unexpected, _exp: &dyn serde::de::Expected) -> Self {
+        Self::InvalidType(Snippet::from_unexpected(unexp))
+    }
+
+    fn invalid_value(unexp: serde::de::Unexpected, _exp: &dyn serde::de::Expected) -> Self {
+        Self::InvalidValue(Snippet::from_unexpected(unexp))
+    }
+
+    fn invalid_length(len: usize, _exp: &dyn serde::de::Expected) -> Self {
+        // Turned into a more specific error by the deserializer if it refers to a field,
+        // a sequence or a map that exceeded the capacity of the target type.
+        Self::InvalidLength { len }
+    }
+
+    fn unknown_variant(variant: &str, _expected: &'static [&'static str]) -> Self {
+        Self::UnknownVariant(Snippet::new(variant.as_bytes()))
+    }
+
+    fn unknown_field(field: &str, _expected: &'static [&'static str]) -> Self {
+        Self::UnknownField(Snippet::new(field.as_bytes()))
+    }
+
+    fn missing_field(field: &'static str) -> Self {
+        Self::MissingField(field)
+    }
+
+    fn duplicate_field(field: &'static str) -> Self {
+        Self::DuplicateField(field)
+    }
+}
+
+#[cfg(feature = "defmt")]
+impl defmt::Format for Error {
+    fn format(&self, f: defmt::Formatter) {
+        use defmt::write;
+        impl_format!(self, write, f)
+    }
+}
+
+/// Deduplicates strings deserialized by [`Reader::deserialize_with_interner`].
+///
+/// Useful for columns with a small set of repeated values, like categories,
+/// that would otherwise be copied into every deserialized record.
+pub trait FieldInterner {
+    /// Returns a string equal to `value` that outlives the reader,
+    /// or `None` if `value` isn't interned.
+    fn intern(&mut self, value: &str) -> Option<&'static str>;
+}
+
+/// Iterator over the records of an input returned by [`Reader::records_lenient`].
+pub struct RecordsLenient<'a, const N: usize, T> {
+    reader: &'a mut Reader<N>,
+    input: &'a [u8],
+    nread: usize,
+    _type: core::marker::PhantomData<fn() -> T>,
+}
+
+impl<const N: usize, T> Iterator for RecordsLenient<'_, N, T>
+where
+    T: DeserializeOwned,
+{
+    type Item = core::result::Result<T, (Error, core::ops::Range<usize>)>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let start = self.nread;
+        let rest = self.input.get(start..).filter(|rest| !rest.is_empty())?;
+        match self.reader.deserialize(rest) {
+            Ok((value, nread)) => {
+                // Fields not taken by `T` would be read as the next record otherwise.
+                let skipped = self.reader.skip_record(&rest[nread..]);
+                self.nread += nread + skipped;
+                Some(Ok(value))
+            }
+            Err(e) => {
+                let nread = self.reader.last_bytes_read();
+                let nread = nread + self.reader.skip_record(&rest[nread..]);
+                // An error that consumed nothing would be returned forever.
+                self.nread = match nread {
+                    0 => self.input.len(),
+                    nread => start + nread,
+                };
+                Some(Err((e, start..self.nread)))
+            }
+        }
+    }
+}
+
+impl<const N: usize, T> core::fmt::Debug for RecordsLenient<'_, N, T> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("RecordsLenient")
+            .field("nread", &self.nread)
+            .finish_non_exhaustive()
+    }
+}
+
+/// A record whose first field was read by [`Reader::deserialize_tagged`].
+pub struct Tagged<'a, 'b, const N: usize> {
+    deserializer: &'a mut Deserializer<'b, 'static, N>,
+    len: usize,
+}
+
+impl<const N: usize> Tagged<'_, '_, N> {
+    /// Returns the first field of the record.
+    pub fn tag(&self) -> &str {
+        // The field was already read as a string.
+        core::str::from_utf8(&self.deserializer.reader.field_buffer[..self.len]).unwrap_or_default()
+    }
 
-impl core::fmt::Display for Error {
+    /// Deserializes the rest of the record into a value of type `T`.
+    pub fn deserialize<'de, T>(self) -> Result<T>
+    where
+        T: Deserialize<'de>,
+    {
+        T::deserialize(self.deserializer)
+    }
+}
+
+impl<const N: usize> core::fmt::Debug for Tagged<'_, '_, N> {
     fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
-        impl_format!(self, write, f)
+        f.debug_struct("Tagged").field("tag", &self.tag()).finish()
     }
 }
 
-impl serde::de::StdError for Error {}
+/// Skips `W` consecutive fields of a record.
+///
+/// [`serde::de::IgnoredAny`] always skips a single field, because CSV doesn't describe
+/// how many fields a value spans. Use `IgnoredFields` in place of an ignored compound value,
+/// like a nested struct or tuple, to keep the following fields aligned.
+/// At the top level, [`serde::de::IgnoredAny`] skips a whole record instead.
+///
+/// # Example
+/// ```
+/// use serde::Deserialize;
+/// use serde_csv_core::de::IgnoredFields;
+///
+/// #[derive(Debug, PartialEq, Eq, Deserialize)]
+/// struct Record {
+///     pub id: u32,
+///     pub position: IgnoredFields<3>,
+///     pub population: u32,
+/// }
+///
+/// let mut reader = serde_csv_core::Reader::<16>::new();
+/// let (record, _) = reader.deserialize::<Record>(b"1,50.06,19.94,219,766683\n")?;
+///
+/// assert_eq!(record.id, 1);
+/// assert_eq!(record.population, 766_683);
+/// # Ok::<(), serde_csv_core::de::Error>(())
+/// ```
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct IgnoredFields<const W: usize>;
 
-impl serde::de::Error for Error {
-    fn custom<T>(_msg: T) -> Self
+impl<'de, const W: usize> Deserialize<'de> for IgnoredFields<W> {
+    fn deserialize<D>(deserializer: D) -> core::result::Result<Self, D::Error>
     where
-        T: core::fmt::Display,
+        D: serde::Deserializer<'de>,
     {
-        Self::Custom
+        struct Visitor<const W: usize>;
+
+        impl<'de, const W: usize> serde::de::Visitor<'de> for Visitor<W> {
+            type Value = IgnoredFields<W>;
+
+            fn expecting(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+                write!(f, "{} fields", W)
+            }
+
+            fn visit_seq<A>(self, mut seq: A) -> core::result::Result<Self::Value, A::Error>
+            where
+                A: serde::de::SeqAccess<'de>,
+            {
+                for i in 0..W {
+                    if seq.next_element::<serde::de::IgnoredAny>()?.is_none() {
+                        return Err(serde::de::Error::invalid_length(i, &self));
+                    }
+                }
+                Ok(IgnoredFields)
+            }
+        }
+
+        deserializer.deserialize_tuple(W, Visitor)
     }
 }
 
-#[cfg(feature = "defmt")]
-impl defmt::Format for Error {
-    fn format(&self, f: defmt::Formatter) {
-        use defmt::write;
-        impl_format!(self, write, f)
+/// A record whose checksum was verified by [`Reader::verify_checksum`].
+#[derive(Clone, Copy)]
+struct Checked {
+    // Offset of the delimiter before the checksum field.
+    content_end: usize,
+    // Number of bytes of the whole record.
+    nread: usize,
+    // Line that follows the record.
+    line: u64,
+}
+
+/// Input of a [`Deserializer`], split into one or more parts that are read in order.
+#[derive(Clone, Copy)]
+struct Input<'a> {
+    parts: &'a [&'a [u8]],
+    len: usize,
+}
+
+impl<'a> Input<'a> {
+    fn new(parts: &'a [&'a [u8]]) -> Self {
+        let len = parts.iter().map(|part| part.len()).sum();
+        Self { parts, len }
+    }
+
+    fn single(input: &'a &'a [u8]) -> Self {
+        Self::new(core::slice::from_ref(input))
+    }
+
+    /// Returns the input that ends before the checksum field of a verified record.
+    fn before_checksum(self, checked: Option<Checked>) -> Self {
+        match checked {
+            Some(checked) => Self {
+                parts: self.parts,
+                len: checked.content_end,
+            },
+            None => self,
+        }
+    }
+
+    /// Returns the rest of the part that contains the byte at `offset`, up to the end of the input.
+    fn rest(&self, offset: usize) -> &'a [u8] {
+        let len = self.len.saturating_sub(offset);
+        let mut part_offset = offset;
+        for part in self.parts {
+            match part.get(part_offset..) {
+                Some(rest) if !rest.is_empty() => return &rest[..rest.len().min(len)],
+                _ => part_offset = part_offset.saturating_sub(part.len()),
+            }
+        }
+        &[]
+    }
+
+    fn get(&self, offset: usize) -> Option<u8> {
+        self.rest(offset).first().copied()
     }
 }
 
-#[derive(Debug)]
-struct Deserializer<'a, const N: usize> {
+/// Reads records for serde, passing strings borrowed from the input as `&'i str`.
+struct Deserializer<'a, 'i, const N: usize> {
     reader: &'a mut Reader<N>,
-    input: &'a [u8],
+    input: Input<'a>,
     nread: usize,
     record_end: bool,
     peeked: Option<usize>,
+    // Offset of the end of the last field read, excluding its delimiter or terminator.
+    content_end: usize,
+    // Offset and line of the last field read.
+    field_start: (usize, u64),
+    // Offset and line of the peeked field, unless it was synthesized.
+    peek_start: Option<(usize, u64)>,
+    depth: usize,
+    nfields: usize,
+    tag_field: Option<usize>,
+    seq_len: usize,
+    seq_ended_record: bool,
+    past_end: bool,
+    headers: Option<HeaderNames<'a>>,
+    interner: Option<&'a mut dyn FieldInterner>,
+    // The input, if it's known to be valid UTF-8.
+    str_input: Option<&'a str>,
+    // Offset of the last field read if it's a verbatim part of `str_input`.
+    verbatim: Option<usize>,
+    // Input that strings can be borrowed from, set by `Reader::deserialize_borrowed`.
+    borrowed: Option<&'i [u8]>,
+    // Whether the peeked field is a quoted empty field deserialized as `Some`.
+    quoted_some: bool,
+    // Offset of the current row, if `Config::row_per_struct` is set.
+    row_start: usize,
+    // Whether a struct read from a row of its own is being deserialized.
+    row_struct: bool,
+    // Records of keys and values a map is read from, set by `Reader::deserialize_keyed`.
+    keyed: Option<KeyedRecords>,
 }
 
-impl<'a, const N: usize> Deserializer<'a, N> {
-    pub fn new(reader: &'a mut Reader<N>, input: &'a [u8]) -> Self {
+impl<'a, 'i, const N: usize> Deserializer<'a, 'i, N> {
+    pub fn new(reader: &'a mut Reader<N>, input: Input<'a>) -> Self {
         Self {
             reader,
             input,
             nread: 0,
             record_end: false,
             peeked: None,
+            content_end: 0,
+            field_start: (0, 0),
+            peek_start: None,
+            depth: 0,
+            nfields: 0,
+            tag_field: None,
+            seq_len: 0,
+            seq_ended_record: false,
+            past_end: false,
+            headers: None,
+            interner: None,
+            str_input: None,
+            verbatim: None,
+            borrowed: None,
+            quoted_some: false,
+            row_start: 0,
+            row_struct: false,
+            keyed: None,
         }
     }
 
+    /// Returns the number of bytes consumed by the inner reader.
+    ///
+    /// A peeked field is already consumed by the inner reader, so its bytes are counted as well.
+    /// Every peek must therefore be followed by a read of the same field.
     pub fn bytes_read(&self) -> usize {
         self.nread
     }
 
+    /// Converts an error returned by a visitor of a field of length `len`
+    /// into [`Error::FieldTooLong`] if it's caused by the field exceeding the visitor's capacity.
+    fn field_error(&self, e: Error, len: usize) -> Error {
+        match e {
+            Error::InvalidLength { len: n } if n == len => Error::FieldTooLong { len },
+            e => e,
+        }
+    }
+
     fn read_bytes_impl(&mut self) -> Result<usize> {
-        let (result, r, w) = self
-            .reader
-            .inner
-            .read_field(&self.input[self.nread..], &mut self.reader.field_buffer);
-        self.nread += r;
+        let len = self.read_field_impl(false)?;
+        match self.reader.config.field_decoder {
+            Some(decoder) => {
+                self.verbatim = None;
+                decoder.decode(&mut self.reader.field_buffer[..len])
+            }
+            None => Ok(len),
+        }
+    }
+
+    /// Reads a field, discarding its contents.
+    ///
+    /// Unlike other reads, the field may exceed the capacity of the internal buffer.
+    fn skip_field(&mut self) -> Result<()> {
+        self.peek_start = None;
+        match self.peeked.take() {
+            Some(_) => Ok(()),
+            None => self.read_field_impl(true).map(|_| ()),
+        }
+    }
+
+    fn read_field_impl(&mut self, discard: bool) -> Result<usize> {
+        if self.past_end {
+            // The record is shorter than the target type.
+            return Err(Error::InvalidLength { len: self.nfields });
+        }
+        self.skip_tag_field()?;
+        if self.nfields == self.reader.config.max_fields_per_record {
+            return Err(Error::TooManyFields);
+        }
+        self.nfields += 1;
+        let start = self.nread;
+        self.field_start = (start, self.reader.inner.line());
+        if let Some(widths) = self.reader.config.fixed_widths {
+            return self.read_fixed_field(widths, discard);
+        }
+        let mut w = 0;
+        let (result, r) = loop {
+            let (result, r, n) = read_field(
+                &mut self.reader.inner,
+                self.input.rest(self.nread),
+                &mut self.reader.field_buffer[w..],
+            );
+            self.nread += r;
+            w += n;
+            match result {
+                csv_core::ReadFieldResult::OutputFull if discard && r > 0 => w = 0,
+                // The field continues in the next part of the input.
+                csv_core::ReadFieldResult::InputEmpty if self.nread < self.input.len => {}
+                result => break (result, r),
+            }
+        };
+        // A field ended by the end of the input isn't followed by a delimiter or a terminator.
+        let delimited = matches!(result, csv_core::ReadFieldResult::Field { .. }) && r > 0;
+        if self.str_input.is_some() {
+            self.verbatim = self.is_verbatim(start, delimited, w).then_some(start);
+        }
+        // The record starts at the beginning of the input, or of the row.
+        let mut record_len = self.nread - self.row_start;
+        self.content_end = self.nread;
         match result {
-            csv_core::ReadFieldResult::InputEmpty => {}
+            csv_core::ReadFieldResult::InputEmpty => {
+                if self.reader.config.require_terminator {
+                    return Err(Error::MissingTerminator);
+                }
+                // The end of the input ends the field and the record as well.
+                self.reader
+                    .inner
+                    .read_field(&[], &mut self.reader.field_buffer[w..]);
+                self.record_end = true;
+            }
             csv_core::ReadFieldResult::OutputFull => return Err(Error::Overflow),
-            csv_core::ReadFieldResult::Field { record_end } => self.record_end = record_end,
-            csv_core::ReadFieldResult::End => {}
+            csv_core::ReadFieldResult::Field { record_end } => {
+                if delimited {
+                    // The field was ended by a delimiter or the first byte of a terminator.
+                    self.content_end -= 1;
+                }
+                self.record_end = record_end;
+                if record_end {
+                    self.consume_crlf();
+                    if self.reader.config.record_len_includes_terminator {
+                        record_len = self.nread - self.row_start;
+                    } else if delimited {
+                        record_len -= 1;
+                    }
+                }
+            }
+            csv_core::ReadFieldResult::End => {
+                // There's no record at the end of the input, so the empty field read
+                // in its place isn't counted.
+                self.nfields -= 1;
+                self.record_end = true;
+            }
+        }
+        if record_len > self.reader.config.max_record_len {
+            return Err(Error::RecordTooLong { len: record_len });
+        }
+        Ok(w)
+    }
+
+    /// Reads the next field of a fixed-width record with the column `widths`,
+    /// like [`Deserializer::read_field_impl`].
+    fn read_fixed_field(&mut self, widths: &[usize], discard: bool) -> Result<usize> {
+        let index = self.nfields - 1;
+        let &width = widths.get(index).ok_or(Error::TooManyFields)?;
+        let start = self.nread;
+        let end = start + width;
+        if index == 0 && self.input.get(start).is_none() {
+            // There's no record at the end of the input.
+            self.nfields -= 1;
+            self.record_end = true;
+            return Err(Error::InvalidLength { len: 0 });
+        }
+        if let Some(len) =
+            (start..end).find(|&i| self.input.get(i).is_none_or(|b| self.is_terminator(b)))
+        {
+            // The column isn't consumed and the record is left unfinished,
+            // so that it can be skipped from this column on.
+            self.nfields -= 1;
+            return Err(Error::ShortRecord {
+                len: len - self.row_start,
+            });
+        }
+        let (mut field_start, mut field_end) = (start, end);
+        if self.reader.config.trim_padding {
+            while field_start < field_end && self.input.get(field_start) == Some(b' ') {
+                field_start += 1;
+            }
+            while field_end > field_start && self.input.get(field_end - 1) == Some(b' ') {
+                field_end -= 1;
+            }
+        }
+        let w = match self.reader.field_buffer.get_mut(..field_end - field_start) {
+            Some(output) => {
+                for (i, b) in (field_start..field_end).zip(output) {
+                    *b = self.input.get(i).unwrap_or_default();
+                }
+                field_end - field_start
+            }
+            None if discard => 0,
+            None => {
+                self.nfields -= 1;
+                return Err(Error::Overflow);
+            }
+        };
+        self.field_start.0 = field_start;
+        if self.str_input.is_some() {
+            self.verbatim = Some(field_start);
+        }
+        self.nread = end;
+        self.content_end = end;
+        let mut record_len = end - self.row_start;
+        if index + 1 == widths.len() {
+            match self.input.get(end) {
+                None if self.reader.config.require_terminator => {
+                    self.record_end = true;
+                    return Err(Error::MissingTerminator);
+                }
+                None => self.record_end = true,
+                Some(b) if self.is_terminator(b) => {
+                    self.record_end = true;
+                    let crlf = b == b'\r' && self.input.get(end + 1) == Some(b'\n');
+                    self.nread += 1 + usize::from(crlf);
+                    if self.reader.config.record_len_includes_terminator {
+                        record_len = self.nread - self.row_start;
+                    }
+                }
+                Some(_) => return Err(Error::MissingTerminator),
+            }
+        }
+        if record_len > self.reader.config.max_record_len {
+            return Err(Error::RecordTooLong { len: record_len });
         }
         Ok(w)
     }
 
+    /// Checks whether `b` is a record terminator, or a part of one.
+    fn is_terminator(&self, b: u8) -> bool {
+        match self.reader.config.terminator {
+            csv_core::Terminator::CRLF => b == b'\r' || b == b'\n',
+            csv_core::Terminator::Any(terminator) => b == terminator,
+            _ => false,
+        }
+    }
+
+    /// Checks the number of fields of a record after it was deserialized with `result`,
+    /// reading the rest of the record if needed.
+    fn check_field_count<T>(&mut self, expected: usize, result: Result<T>) -> Result<T> {
+        let value = match result {
+            Ok(value) => value,
+            // The record was too short for the target type.
+            Err(_) if self.record_end && self.nfields < expected => {
+                return Err(Error::FieldCount {
+                    expected,
+                    got: self.nfields,
+                })
+            }
+            Err(e) => return Err(e),
+        };
+        while !self.record_end {
+            self.skip_field()?;
+        }
+        if self.nfields != expected {
+            return Err(Error::FieldCount {
+                expected,
+                got: self.nfields,
+            });
+        }
+        Ok(value)
+    }
+
+    /// Skips the fields left unread by the target type, counting them
+    /// against [`Reader::max_fields_per_record`].
+    fn check_max_fields<T>(&mut self, result: Result<T>) -> Result<T> {
+        let value = result?;
+        while !self.record_end {
+            self.skip_field()?;
+        }
+        Ok(value)
+    }
+
+    /// Checks whether a field of length `w` read from `self.input[start..self.nread]`,
+    /// `delimited` if its last byte is a delimiter or a terminator, is an unmodified part of the input.
+    fn is_verbatim(&self, start: usize, delimited: bool, w: usize) -> bool {
+        // Quotes, escapes and skipped lines would take up at least one more byte,
+        // so the field must be followed by nothing but its delimiter or terminator.
+        let end = self.nread - usize::from(delimited);
+        end - start == w
+    }
+
+    /// Consumes the `\n` of a `\r\n` record terminator, see [`Reader::consume_crlf`].
+    fn consume_crlf(&mut self) {
+        self.nread += self.reader.consume_crlf(self.input, self.nread);
+    }
+
+    /// Skips the variant name of an enum if it's the next field, since it was already read.
+    fn skip_tag_field(&mut self) -> Result<()> {
+        if self.tag_field == Some(self.nfields) {
+            self.tag_field = None;
+            self.read_bytes_impl()?;
+        }
+        Ok(())
+    }
+
+    /// Reads the field `skip` fields ahead of the current one, without consuming it.
+    fn scout_field(&mut self, skip: usize) -> Result<&[u8]> {
+        let line = self.reader.inner.line();
+        let mut nread = self.nread;
+        let mut remaining = skip;
+        let mut w = 0;
+        let result = loop {
+            let (result, r, n) = read_field(
+                &mut self.reader.inner,
+                self.input.rest(nread),
+                &mut self.reader.field_buffer[w..],
+            );
+            nread += r;
+            w += n;
+            match result {
+                // The field continues in the next part of the input.
+                csv_core::ReadFieldResult::InputEmpty if nread < self.input.len => {}
+                csv_core::ReadFieldResult::InputEmpty if remaining == 0 => break Ok(w),
+                csv_core::ReadFieldResult::Field { .. } if remaining == 0 => break Ok(w),
+                csv_core::ReadFieldResult::Field { record_end: false } => {
+                    remaining -= 1;
+                    w = 0;
+                }
+                csv_core::ReadFieldResult::OutputFull => break Err(Error::Overflow),
+                // The record ends before the scouted field, after the one that was read last.
+                csv_core::ReadFieldResult::Field { record_end: true }
+                | csv_core::ReadFieldResult::InputEmpty => {
+                    let len = self.nfields + skip - remaining + 1;
+                    break Err(Error::InvalidLength { len });
+                }
+                csv_core::ReadFieldResult::End => {
+                    let len = self.nfields + skip - remaining;
+                    break Err(Error::InvalidLength { len });
+                }
+            }
+        };
+        // The field buffer no longer holds the field that was read last.
+        self.verbatim = None;
+        // `csv_core::Reader` can't be cloned reliably, so the scouted fields are read again
+        // from a reset reader. The current field is at the start of a record or just after
+        // a delimiter, and both are parsed the same way.
+        self.reader.inner.reset();
+        self.reader.inner.set_line(line);
+        result.map(|len| &self.reader.field_buffer[..len])
+    }
+
+    /// Returns the number of unread fields of the record, if the number of its fields is known
+    /// from [`Reader::with_expected_fields`] or from the headers.
+    fn remaining_fields(&self) -> Option<usize> {
+        let count = self
+            .reader
+            .config
+            .expected_fields
+            .or_else(|| self.headers.as_ref().map(HeaderNames::len))?;
+        let read = self.nfields - usize::from(self.peeked.is_some());
+        Some(count.saturating_sub(read))
+    }
+
+    /// Moves on to the next row, skipping unread fields of the current one,
+    /// unless no field of the current row was read.
+    fn begin_row(&mut self) -> Result<()> {
+        self.unpeek();
+        if self.nfields == 0 {
+            return Ok(());
+        }
+        self.past_end = false;
+        while !self.record_end {
+            self.skip_field()?;
+        }
+        self.record_end = false;
+        self.nfields = 0;
+        self.row_start = self.nread;
+        Ok(())
+    }
+
+    /// Rewinds the reader to the start of a peeked field, so that it's read again.
+    fn unpeek(&mut self) {
+        if let (Some(_), Some((start, line))) = (self.peeked, self.peek_start) {
+            self.peeked = None;
+            self.peek_start = None;
+            self.nread = start;
+            self.nfields -= 1;
+            self.record_end = false;
+            self.reader.inner.reset();
+            self.reader.inner.set_line(line);
+        }
+    }
+
     fn peek_bytes(&mut self) -> Result<&[u8]> {
         let len = match self.peeked {
             Some(len) => len,
             None => {
                 let len = self.read_bytes_impl()?;
                 self.peeked = Some(len);
+                self.peek_start = Some(self.field_start);
                 len
             }
         };
         Ok(&self.reader.field_buffer[..len])
     }
 
+    /// Returns `true` if the peeked field is a quoted empty field, e.g. `""`.
+    /// Fixed-width fields are never quoted, even if their padding is trimmed.
+    fn peeked_quoted_empty(&self) -> bool {
+        if self.reader.config.fixed_widths.is_some() {
+            return false;
+        }
+        match (self.peeked, self.peek_start) {
+            // An unquoted empty field takes up no input, except for lines skipped before it.
+            (Some(0), Some((start, _))) => {
+                self.content_end > start
+                    && !self
+                        .input
+                        .get(self.content_end - 1)
+                        .is_some_and(|b| self.reader.is_terminator(b))
+            }
+            _ => false,
+        }
+    }
+
+    fn read_len(&mut self) -> Result<usize> {
+        self.peek_start = None;
+        match self.peeked.take() {
+            Some(len) => Ok(len),
+            None => self.read_bytes_impl(),
+        }
+    }
+
     fn read_bytes(&mut self) -> Result<&[u8]> {
-        let len = match self.peeked.take() {
-            Some(len) => len,
-            None => self.read_bytes_impl()?,
-        };
+        let len = self.read_len()?;
         Ok(&self.reader.field_buffer[..len])
     }
 
+    /// Reads a numeric field, replacing the parentheses of an accounting negative
+    /// with a minus sign if enabled. Returns the `invalid` error if the parentheses don't match.
+    fn read_number(&mut self, invalid: fn(Snippet) -> Error) -> Result<&[u8]> {
+        let accounting_negatives = self.reader.config.accounting_negatives;
+        let len = self.read_len()?;
+        let buf = &mut self.reader.field_buffer[..len];
+        if !accounting_negatives || buf.first() != Some(&b'(') {
+            return Ok(buf);
+        }
+        if buf.last() != Some(&b')') {
+            return Err(invalid(Snippet::new(buf)));
+        }
+        buf[0] = b'-';
+        Ok(&buf[..len - 1])
+    }
+
     fn read_int<T: atoi::FromRadix10SignedChecked>(&mut self) -> Result<T> {
-        atoi::atoi(self.read_bytes()?).ok_or(Error::InvalidInt)
+        let reject_leading_zeros = self.reader.config.reject_leading_zeros;
+        let bytes = self.read_number(Error::InvalidInt)?;
+        let n = match T::from_radix_10_signed_checked(bytes) {
+            // A sign alone is parsed as zero.
+            (Some(n), used)
+                if used == bytes.len() && bytes.last().is_some_and(u8::is_ascii_digit) =>
+            {
+                n
+            }
+            _ => return Err(Error::InvalidInt(Snippet::new(bytes))),
+        };
+        let digits = match bytes {
+            [b'-' | b'+', digits @ ..] => digits,
+            digits => digits,
+        };
+        if reject_leading_zeros && digits.len() > 1 && digits[0] == b'0' {
+            return Err(Error::LeadingZero);
+        }
+        Ok(n)
+    }
+
+    fn read_float<T: Float>(&mut self) -> Result<T> {
+        let percent = self.reader.config.percent;
+        let bytes = self.read_number(Error::InvalidFloat)?;
+        let result = match bytes.strip_suffix(b"%") {
+            Some(number) if percent => T::parse(number).map(|v| v / T::from(100)),
+            _ => T::parse(bytes),
+        };
+        result.ok_or_else(|| Error::InvalidFloat(Snippet::new(bytes)))
     }
 
-    fn read_float<T: FromLexical>(&mut self) -> Result<T> {
-        T::from_lexical(self.read_bytes()?).map_err(|_| Error::InvalidFloat)
+    /// Reads a string-like field, removing a formula guard if configured.
+    fn read_text_len(&mut self) -> Result<usize> {
+        let len = self.read_len()?;
+        let Config {
+            unsanitize_formulas,
+            formula_guard,
+            ..
+        } = self.reader.config;
+        let buf = &mut self.reader.field_buffer[..len];
+        if unsanitize_formulas
+            && buf.first() == Some(&formula_guard)
+            && crate::is_formula(&buf[1..], formula_guard)
+        {
+            buf.copy_within(1.., 0);
+            self.verbatim = None;
+            return Ok(len - 1);
+        }
+        Ok(len)
     }
 
     fn read_str(&mut self) -> Result<&str> {
-        core::str::from_utf8(self.read_bytes()?).map_err(|_| Error::InvalidUtf8String)
+        let max = self.reader.config.max_str_len;
+        let str = self.decode_str()?;
+        if str.len() > max {
+            return Err(Error::StrTooLong { len: str.len() });
+        }
+        Ok(str)
+    }
+
+    /// Passes a string field to `visitor` as a string borrowed from the input
+    /// if it's an unmodified part of it.
+    fn deserialize_borrowed_str<'de, V>(&mut self, visitor: V) -> Result<V::Value>
+    where
+        V: serde::de::Visitor<'de>,
+        'i: 'de,
+    {
+        // The string is read into the start of the field buffer.
+        let len = self.read_str()?.len();
+        let value = core::str::from_utf8(&self.reader.field_buffer[..len])
+            .map_err(|_| Error::InvalidUtf8String)?;
+        let start = self.field_start.0;
+        let input = self
+            .borrowed
+            .and_then(|input| input.get(start..)?.get(..len));
+        let result = match input.filter(|input| *input == value.as_bytes()) {
+            Some(input) => visitor.visit_borrowed_str(
+                core::str::from_utf8(input).map_err(|_| Error::InvalidUtf8String)?,
+            ),
+            None => visitor.visit_str(value),
+        };
+        result.map_err(|e| self.field_error(e, len))
+    }
+
+    fn decode_str(&mut self) -> Result<&str> {
+        let mut len = self.read_text_len()?;
+        if let (Some(input), Some(start)) = (self.str_input, self.verbatim) {
+            // The field is a copy of a part of the input, which is a `str` already,
+            // as long as that part starts and ends on char boundaries.
+            let field = input.get(start..start + len);
+            if let Some(field) = field.filter(|_| self.reader.config.encoding == Encoding::Utf8) {
+                return Ok(field);
+            }
+        }
+        match self.reader.config.encoding {
+            Encoding::Utf8 => {
+                if self.reader.config.lossy_utf8 {
+                    len = replace_invalid_utf8(&mut self.reader.field_buffer, len)?;
+                }
+            }
+            encoding => len = transcode_to_utf8(&mut self.reader.field_buffer, len, encoding)?,
+        }
+        core::str::from_utf8(&self.reader.field_buffer[..len]).map_err(|_| Error::InvalidUtf8String)
     }
 }
 
-impl<'de, 'a, 'b, const N: usize> serde::de::Deserializer<'de> for &'a mut Deserializer<'b, N> {
+/// Calls [`csv_core::Reader::read_field`], handling fields that fill `output` exactly.
+///
+/// csv-core reports a full output as soon as it's filled, even if the field ends right after.
+fn read_field(
+    reader: &mut csv_core::Reader,
+    input: &[u8],
+    output: &mut [u8],
+) -> (csv_core::ReadFieldResult, usize, usize) {
+    let (result, r, w) = reader.read_field(input, output);
+    if result == csv_core::ReadFieldResult::OutputFull && r < input.len() {
+        // Empty input would mean the end of data, so it's only checked when there's more.
+        let (next, nr, nw) = reader.read_field(&input[r..], &mut [0]);
+        if nw == 0 {
+            return (next, r + nr, w);
+        }
+        return (csv_core::ReadFieldResult::OutputFull, r + nr, w);
+    }
+    (result, r, w)
+}
+
+/// Transcodes `buf[..len]` from a single-byte `encoding` to UTF-8, returning the new length.
+fn transcode_to_utf8(buf: &mut [u8], len: usize, encoding: Encoding) -> Result<usize> {
+    let new_len: usize = buf[..len]
+        .iter()
+        .map(|&b| encoding.decode_byte(b).len_utf8())
+        .sum();
+    if new_len > buf.len() {
+        return Err(Error::TranscodedTooLong { len: new_len });
+    }
+    // Going backwards, every byte is read before it could be overwritten.
+    let mut end = new_len;
+    for i in (0..len).rev() {
+        let c = encoding.decode_byte(buf[i]);
+        end -= c.len_utf8();
+        c.encode_utf8(&mut buf[end..]);
+    }
+    Ok(new_len)
+}
+
+/// Replaces invalid UTF-8 sequences in `buf[..len]` with `U+FFFD`, returning the new length.
+fn replace_invalid_utf8(buf: &mut [u8], mut len: usize) -> Result<usize> {
+    const REPLACEMENT: &[u8] = "\u{FFFD}".as_bytes();
+    let mut pos = 0;
+    while let Err(e) = core::str::from_utf8(&buf[pos..len]) {
+        let start = pos + e.valid_up_to();
+        let end = match e.error_len() {
+            Some(n) => start + n,
+            None => len,
+        };
+        let new_len = len - (end - start) + REPLACEMENT.len();
+        if new_len > buf.len() {
+            return Err(Error::Overflow);
+        }
+        buf.copy_within(end..len, start + REPLACEMENT.len());
+        buf[start..start + REPLACEMENT.len()].copy_from_slice(REPLACEMENT);
+        len = new_len;
+        pos = start + REPLACEMENT.len();
+    }
+    Ok(len)
+}
+
+impl<'de, 'a, 'b, 'i, const N: usize> serde::de::Deserializer<'de>
+    for &'a mut Deserializer<'b, 'i, N>
+where
+    'i: 'de,
+{
     type Error = Error;
 
     fn deserialize_any<V>(self, _visitor: V) -> Result<V::Value>
     where
         V: serde::de::Visitor<'de>,
     {
-        unimplemented!("`Deserializer::deserialize_any` is not supported");
+        Err(Error::Unsupported("Deserializer::deserialize_any"))
     }
 
     fn deserialize_bool<V>(self, visitor: V) -> Result<V::Value>
@@ -240,7 +3323,7 @@ impl<'de, 'a, 'b, const N: usize> serde::de::Deserializer<'de> for &'a mut Deser
         match self.read_bytes()? {
             b"true" => visitor.visit_bool(true),
             b"false" => visitor.visit_bool(false),
-            _ => Err(Error::InvalidBool),
+            bytes => Err(Error::InvalidBool(Snippet::new(bytes))),
         }
     }
 
@@ -331,40 +3414,69 @@ impl<'de, 'a, 'b, const N: usize> serde::de::Deserializer<'de> for &'a mut Deser
     where
         V: serde::de::Visitor<'de>,
     {
-        visitor.visit_str(self.read_str()?)
+        if self.borrowed.is_some() {
+            return self.deserialize_borrowed_str(visitor);
+        }
+        let mut interner = self.interner.take();
+        let result = match self.read_str() {
+            Ok(value) => match interner.as_mut().and_then(|i| i.intern(value)) {
+                Some(interned) => visitor.visit_borrowed_str(interned),
+                None => {
+                    let len = value.len();
+                    visitor
+                        .visit_str(value)
+                        .map_err(|e| self.field_error(e, len))
+                }
+            },
+            Err(e) => Err(e),
+        };
+        self.interner = interner;
+        result
     }
 
-    fn deserialize_string<V>(self, _visitor: V) -> Result<V::Value>
+    fn deserialize_string<V>(self, visitor: V) -> Result<V::Value>
     where
         V: serde::de::Visitor<'de>,
     {
-        unimplemented!("`Deserializer::deserialize_any` is not supported");
+        self.deserialize_str(visitor)
     }
 
     fn deserialize_bytes<V>(self, visitor: V) -> Result<V::Value>
     where
         V: serde::de::Visitor<'de>,
     {
-        self.read_bytes().and_then(|v| visitor.visit_bytes(v))
+        let len = self.read_text_len()?;
+        visitor
+            .visit_bytes(&self.reader.field_buffer[..len])
+            .map_err(|e| self.field_error(e, len))
     }
 
-    fn deserialize_byte_buf<V>(self, _visitor: V) -> Result<V::Value>
+    fn deserialize_byte_buf<V>(self, visitor: V) -> Result<V::Value>
     where
         V: serde::de::Visitor<'de>,
     {
-        unimplemented!("`Deserializer::deserialize_buf` is not supported");
+        self.deserialize_bytes(visitor)
     }
 
     fn deserialize_option<V>(self, visitor: V) -> Result<V::Value>
     where
         V: serde::de::Visitor<'de>,
     {
-        let bytes = self.peek_bytes()?;
-        if bytes.is_empty() {
-            visitor.visit_none()
-        } else {
-            visitor.visit_some(self)
+        let config = self.reader.config;
+        let is_null = self.peek_bytes()? == config.null_literal;
+        let quoted_empty = self.peeked_quoted_empty();
+        // `Some(None)` of a nested option, or `None` unless a quoted empty field is `Some`.
+        if (quoted_empty && core::mem::take(&mut self.quoted_some))
+            || (is_null && (!quoted_empty || config.quoted_empty_is_none))
+        {
+            // Consume the peeked field.
+            self.read_len()?;
+            return visitor.visit_none();
         }
+        self.quoted_some = quoted_empty;
+        let value = visitor.visit_some(&mut *self);
+        self.quoted_some = false;
+        value
     }
 
     fn deserialize_unit<V>(self, visitor: V) -> Result<V::Value>
@@ -385,56 +3497,140 @@ impl<'de, 'a, 'b, const N: usize> serde::de::Deserializer<'de> for &'a mut Deser
         self.deserialize_unit(visitor)
     }
 
-    fn deserialize_newtype_struct<V>(self, _name: &'static str, _visitor: V) -> Result<V::Value>
+    fn deserialize_newtype_struct<V>(self, name: &'static str, visitor: V) -> Result<V::Value>
     where
         V: serde::de::Visitor<'de>,
     {
-        unimplemented!("`Deserializer::deserialize_newtype_struct` is not supported");
+        if name == crate::nested::NAME {
+            let len = self.read_text_len()?;
+            return visitor
+                .visit_bytes(&self.reader.field_buffer[..len])
+                .map_err(|e| match e {
+                    Error::InvalidLength { .. } => Error::NestedOverflow,
+                    e => e,
+                });
+        }
+        visitor.visit_newtype_struct(self)
     }
 
     fn deserialize_seq<V>(self, visitor: V) -> Result<V::Value>
     where
         V: serde::de::Visitor<'de>,
     {
-        visitor.visit_seq(self)
+        if self.past_end {
+            self.past_end = false;
+            return visitor.visit_seq(Fields {
+                de: self,
+                len: Some(0),
+            });
+        }
+        self.seq_len = 0;
+        visitor
+            .visit_seq(Fields {
+                de: &mut *self,
+                len: None,
+            })
+            .map_err(|e| match e {
+                // Growable collections report running out of capacity as a length error
+                // with the number of elements they were given.
+                Error::InvalidLength { len } if len == self.seq_len => Error::SeqTooLong { len },
+                e => e,
+            })
     }
 
-    fn deserialize_tuple<V>(self, _len: usize, visitor: V) -> Result<V::Value>
+    fn deserialize_tuple<V>(self, len: usize, visitor: V) -> Result<V::Value>
     where
         V: serde::de::Visitor<'de>,
     {
-        visitor.visit_seq(self)
+        visitor.visit_seq(Fields {
+            de: self,
+            len: Some(len),
+        })
     }
 
     fn deserialize_tuple_struct<V>(
         self,
         _name: &'static str,
-        _len: usize,
+        len: usize,
         visitor: V,
     ) -> Result<V::Value>
     where
         V: serde::de::Visitor<'de>,
     {
-        visitor.visit_seq(self)
+        visitor.visit_seq(Fields {
+            de: self,
+            len: Some(len),
+        })
     }
 
     fn deserialize_map<V>(self, visitor: V) -> Result<V::Value>
     where
         V: serde::de::Visitor<'de>,
     {
-        visitor.visit_seq(self)
+        if let Some(headers) = self.headers.take() {
+            return visitor.visit_map(Columns {
+                de: self,
+                headers,
+                fields: &[],
+                index: 0,
+            });
+        }
+        if let Some(records) = self.keyed.take() {
+            let mut entries = Keyed {
+                de: self,
+                records,
+                index: 0,
+                failed: false,
+            };
+            return visitor.visit_map(&mut entries).map_err(|e| match e {
+                // Maps report running out of capacity like growable sequences,
+                // after an entry was read successfully.
+                Error::InvalidLength { .. } if !entries.failed => {
+                    Error::MapTooLong { len: entries.index }
+                }
+                e => e,
+            });
+        }
+        visitor.visit_map(Pairs { de: self })
     }
 
     fn deserialize_struct<V>(
         self,
         _name: &'static str,
-        _fields: &'static [&'static str],
+        fields: &'static [&'static str],
         visitor: V,
     ) -> Result<V::Value>
     where
         V: serde::de::Visitor<'de>,
     {
-        visitor.visit_seq(self)
+        if let Some(headers) = self.headers.take() {
+            if self.reader.config.case_insensitive_headers {
+                headers::check_case_folding(fields)?;
+            }
+            return visitor.visit_map(Columns {
+                de: self,
+                headers,
+                fields,
+                index: 0,
+            });
+        }
+        if self.keyed.is_some() {
+            return self.deserialize_map(visitor);
+        }
+        if self.reader.config.row_per_struct && self.depth > 0 && !self.row_struct {
+            self.begin_row()?;
+            self.row_struct = true;
+            let value = visitor.visit_seq(Fields {
+                de: &mut *self,
+                len: Some(fields.len()),
+            });
+            self.row_struct = false;
+            return value;
+        }
+        visitor.visit_seq(Fields {
+            de: self,
+            len: Some(fields.len()),
+        })
     }
 
     fn deserialize_enum<V>(
@@ -446,54 +3642,75 @@ impl<'de, 'a, 'b, const N: usize> serde::de::Deserializer<'de> for &'a mut Deser
     where
         V: serde::de::Visitor<'de>,
     {
-        visitor.visit_enum(self)
+        let value = visitor.visit_enum(&mut *self)?;
+        // The variant name is left unread if it follows the variant's content.
+        self.skip_tag_field()?;
+        self.tag_field = None;
+        Ok(value)
     }
 
-    fn deserialize_identifier<V>(self, _visitor: V) -> Result<V::Value>
+    fn deserialize_identifier<V>(self, visitor: V) -> Result<V::Value>
     where
         V: serde::de::Visitor<'de>,
     {
-        unimplemented!("`Deserializer::deserialize_identifier` is not supported");
+        self.deserialize_str(visitor)
     }
 
     fn deserialize_ignored_any<V>(self, visitor: V) -> Result<V::Value>
     where
         V: serde::de::Visitor<'de>,
     {
-        let _ = self.read_bytes()?;
+        // The number of fields spanned by an ignored value is unknown, so a single field
+        // is skipped, unless the ignored value is the whole record. See `IgnoredFields`.
+        if self.depth == 0 {
+            while !self.record_end {
+                self.skip_field()?;
+            }
+        } else {
+            self.skip_field()?;
+        }
         visitor.visit_unit()
     }
 }
 
-impl<'de, 'a, 'b, const N: usize> serde::de::VariantAccess<'de> for &'a mut Deserializer<'b, N> {
+impl<'de, 'a, 'b, 'i, const N: usize> serde::de::VariantAccess<'de>
+    for &'a mut Deserializer<'b, 'i, N>
+where
+    'i: 'de,
+{
     type Error = Error;
 
     fn unit_variant(self) -> Result<()> {
         Ok(())
     }
 
-    fn newtype_variant_seed<U: DeserializeSeed<'de>>(self, _seed: U) -> Result<U::Value> {
-        unimplemented!("`VariantAccess::newtype_variant_seed` is not supported");
+    fn newtype_variant_seed<U: DeserializeSeed<'de>>(self, seed: U) -> Result<U::Value> {
+        seed.deserialize(self)
     }
 
-    fn tuple_variant<V: serde::de::Visitor<'de>>(
-        self,
-        _len: usize,
-        _visitor: V,
-    ) -> Result<V::Value> {
-        unimplemented!("`VariantAccess::tuple_variant` is not supported");
+    fn tuple_variant<V: serde::de::Visitor<'de>>(self, len: usize, visitor: V) -> Result<V::Value> {
+        visitor.visit_seq(Fields {
+            de: self,
+            len: Some(len),
+        })
     }
 
     fn struct_variant<V: serde::de::Visitor<'de>>(
         self,
-        _fields: &'static [&'static str],
-        _visitor: V,
+        fields: &'static [&'static str],
+        visitor: V,
     ) -> Result<V::Value> {
-        unimplemented!("`VariantAccess::struct_variant` is not supported");
+        visitor.visit_seq(Fields {
+            de: self,
+            len: Some(fields.len()),
+        })
     }
 }
 
-impl<'de, 'a, 'b, const N: usize> serde::de::EnumAccess<'de> for &'a mut Deserializer<'b, N> {
+impl<'de, 'a, 'b, 'i, const N: usize> serde::de::EnumAccess<'de> for &'a mut Deserializer<'b, 'i, N>
+where
+    'i: 'de,
+{
     type Error = Error;
 
     type Variant = Self;
@@ -503,23 +3720,231 @@ impl<'de, 'a, 'b, const N: usize> serde::de::EnumAccess<'de> for &'a mut Deseria
         V: DeserializeSeed<'de>,
     {
         use serde::de::IntoDeserializer;
-        let variant_name = self.read_bytes()?;
+        let tag_column = self.reader.config.enum_tag_column;
+        let variant_name = if tag_column == 0 {
+            self.read_bytes()?
+        } else {
+            // A peeked field is the first field of the enum and scouting would overwrite it.
+            self.unpeek();
+            let peeked = usize::from(self.peeked.is_some());
+            self.tag_field = Some(self.nfields - peeked + tag_column);
+            self.scout_field(tag_column - peeked)?
+        };
         seed.deserialize(variant_name.into_deserializer())
             .map(|v| (v, self))
     }
 }
 
-impl<'de, 'a, 'b, const N: usize> serde::de::SeqAccess<'de> for &'a mut Deserializer<'b, N> {
+/// Fields of a record deserialized as a sequence.
+///
+/// `len` is the number of elements expected by the visitor, if known.
+struct Fields<'a, 'b, 'i, const N: usize> {
+    de: &'a mut Deserializer<'b, 'i, N>,
+    len: Option<usize>,
+}
+
+impl<'de, 'a, 'b, 'i, const N: usize> serde::de::SeqAccess<'de> for Fields<'a, 'b, 'i, N>
+where
+    'i: 'de,
+{
     type Error = Error;
 
     fn next_element_seed<V>(&mut self, seed: V) -> Result<Option<V::Value>>
     where
         V: DeserializeSeed<'de>,
     {
-        if self.record_end {
-            Ok(None)
-        } else {
-            seed.deserialize(&mut **self).map(Some)
+        self.de.skip_tag_field()?;
+        let pad = match &mut self.len {
+            Some(0) => return Ok(None),
+            Some(len) => {
+                *len -= 1;
+                self.de.reader.config.pad_short_records
+            }
+            None => false,
+        };
+        if self.de.record_end {
+            if self.de.seq_ended_record {
+                // The fields meant for this element were consumed by a preceding sequence.
+                return Err(Error::SeqNotLast);
+            }
+            if self.len.is_none() {
+                self.de.seq_ended_record = self.de.depth > 0;
+                return Ok(None);
+            }
+            if pad {
+                // Synthesize an empty field.
+                self.de.peeked = Some(0);
+            } else {
+                // Only an empty sequence can be deserialized past the end of a record.
+                self.de.past_end = true;
+            }
+        }
+        if self.len.is_none() {
+            self.de.seq_len += 1;
+        }
+        self.de.depth += 1;
+        let value = seed.deserialize(&mut *self.de);
+        self.de.depth -= 1;
+        self.de.past_end = false;
+        value.map(Some)
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        self.len.or_else(|| self.de.remaining_fields())
+    }
+}
+
+/// Fields of a record deserialized as a map, read as alternating keys and values.
+struct Pairs<'a, 'b, 'i, const N: usize> {
+    de: &'a mut Deserializer<'b, 'i, N>,
+}
+
+impl<'de, 'a, 'b, 'i, const N: usize> serde::de::MapAccess<'de> for Pairs<'a, 'b, 'i, N>
+where
+    'i: 'de,
+{
+    type Error = Error;
+
+    fn next_key_seed<K>(&mut self, seed: K) -> Result<Option<K::Value>>
+    where
+        K: DeserializeSeed<'de>,
+    {
+        self.de.skip_tag_field()?;
+        if self.de.record_end {
+            if self.de.seq_ended_record {
+                // The fields meant for this map were consumed by a preceding sequence.
+                return Err(Error::SeqNotLast);
+            }
+            self.de.seq_ended_record = self.de.depth > 0;
+            return Ok(None);
+        }
+        self.de.depth += 1;
+        let key = seed.deserialize(&mut *self.de);
+        self.de.depth -= 1;
+        key.map(Some)
+    }
+
+    fn next_value_seed<V>(&mut self, seed: V) -> Result<V::Value>
+    where
+        V: DeserializeSeed<'de>,
+    {
+        if self.de.record_end {
+            // The last key has no value.
+            return Err(Error::FieldCount {
+                expected: self.de.nfields + 1,
+                got: self.de.nfields,
+            });
+        }
+        self.de.depth += 1;
+        let value = seed.deserialize(&mut *self.de);
+        self.de.depth -= 1;
+        value
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        self.de.remaining_fields().map(|fields| fields / 2)
+    }
+}
+
+/// The records of keys and values read by [`Reader::deserialize_keyed`],
+/// which have `len` fields each.
+struct KeyedRecords {
+    keys: RecordCursor,
+    values: RecordCursor,
+    len: usize,
+}
+
+/// Position of the next unread field of a record.
+#[derive(Clone, Copy)]
+struct RecordCursor {
+    // Offset of the start of the record.
+    start: usize,
+    // Offset and line of the next field.
+    offset: usize,
+    line: u64,
+}
+
+impl RecordCursor {
+    fn new(start: usize, line: u64) -> Self {
+        Self {
+            start,
+            offset: start,
+            line,
+        }
+    }
+}
+
+/// Reads the `i`-th entry of a map from the `i`-th fields of two records.
+///
+/// The reader alternates between the records, so their fields are read once each.
+/// A field starts right after a delimiter, which is parsed like the start of a record,
+/// so the inner reader can be reset to the next field of either record.
+struct Keyed<'a, 'b, 'i, const N: usize> {
+    de: &'a mut Deserializer<'b, 'i, N>,
+    records: KeyedRecords,
+    index: usize,
+    // Whether reading a key or a value failed, as opposed to inserting the entry.
+    failed: bool,
+}
+
+impl<'i, const N: usize> Keyed<'_, '_, 'i, N> {
+    /// Reads the field of the current entry in the record of `cursor` with `seed`,
+    /// moving the cursor past it.
+    fn read<'de, T>(&mut self, cursor: &mut RecordCursor, seed: T) -> Result<T::Value>
+    where
+        T: DeserializeSeed<'de>,
+        'i: 'de,
+    {
+        let de = &mut *self.de;
+        de.reader.inner.reset();
+        de.reader.inner.set_line(cursor.line);
+        de.nread = cursor.offset;
+        de.row_start = cursor.start;
+        de.record_end = false;
+        de.nfields = self.index;
+        de.peeked = None;
+        de.peek_start = None;
+        de.depth += 1;
+        let result = seed.deserialize(&mut *de);
+        de.depth -= 1;
+        cursor.offset = de.nread;
+        cursor.line = de.reader.inner.line();
+        self.failed |= result.is_err();
+        result
+    }
+}
+
+impl<'de, 'i, const N: usize> serde::de::MapAccess<'de> for &mut Keyed<'_, '_, 'i, N>
+where
+    'i: 'de,
+{
+    type Error = Error;
+
+    fn next_key_seed<K>(&mut self, seed: K) -> Result<Option<K::Value>>
+    where
+        K: DeserializeSeed<'de>,
+    {
+        if self.index == self.records.len {
+            return Ok(None);
         }
+        let mut keys = self.records.keys;
+        let key = self.read(&mut keys, seed);
+        self.records.keys = keys;
+        key.map(Some)
+    }
+
+    fn next_value_seed<V>(&mut self, seed: V) -> Result<V::Value>
+    where
+        V: DeserializeSeed<'de>,
+    {
+        let mut values = self.records.values;
+        let value = self.read(&mut values, seed);
+        self.records.values = values;
+        self.index += 1;
+        value
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.records.len - self.index)
     }
 }
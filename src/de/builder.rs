@@ -0,0 +1,247 @@
+use super::{Config, DuplicateHeaders, FieldDecoder, Reader};
+use crate::{Checksum, Encoding};
+
+/// Builds a [`Reader`] configured with both the CSV format and the serde-level options.
+///
+/// The format is set by the methods passed through to [`csv_core::ReaderBuilder`].
+/// Other methods are equivalent to the methods of [`Reader`] with the same names.
+///
+/// # Example
+/// ```
+/// use heapless::String;
+/// use serde_csv_core::de::ReaderBuilder;
+///
+/// let mut reader = ReaderBuilder::new()
+///     .delimiter(b';')
+///     .null_literal(Some(b"NULL"))
+///     .build::<16>();
+///
+/// let (record, _) = reader.deserialize::<(String<8>, Option<u8>)>(b"a,b;NULL\n")?;
+///
+/// assert_eq!(record, ("a,b".into(), None));
+/// # Ok::<(), serde_csv_core::de::Error>(())
+/// ```
+#[derive(Debug, Default)]
+pub struct ReaderBuilder {
+    inner: csv_core::ReaderBuilder,
+    config: Config,
+}
+
+impl ReaderBuilder {
+    /// Constructs a builder with the default configuration.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Builds a reader with an `N`-byte field buffer.
+    pub fn build<const N: usize>(&self) -> Reader<N> {
+        let mut reader = Reader::from_builder(&self.inner);
+        reader.config = self.config;
+        reader
+    }
+
+    /// Builds a reader with an `N`-byte field buffer on the heap.
+    ///
+    /// See [`Reader::new_boxed`].
+    #[cfg(feature = "alloc")]
+    pub fn build_boxed<const N: usize>(&self) -> alloc::boxed::Box<Reader<N>> {
+        let mut reader = Reader::from_builder_boxed(&self.inner);
+        reader.config = self.config;
+        reader
+    }
+
+    /// Sets the field delimiter. Defaults to `,`.
+    pub fn delimiter(&mut self, delimiter: u8) -> &mut Self {
+        self.inner.delimiter(delimiter);
+        self
+    }
+
+    /// Sets the record terminator. Defaults to [`csv_core::Terminator::CRLF`],
+    /// which accepts `\r`, `\n` and `\r\n`.
+    pub fn terminator(&mut self, terminator: csv_core::Terminator) -> &mut Self {
+        self.inner.terminator(terminator);
+        self.config.terminator = terminator;
+        self
+    }
+
+    /// Sets the quote character. Defaults to `"`.
+    pub fn quote(&mut self, quote: u8) -> &mut Self {
+        self.inner.quote(quote);
+        self
+    }
+
+    /// Sets the escape character used instead of doubled quotes. Disabled by default.
+    pub fn escape(&mut self, escape: Option<u8>) -> &mut Self {
+        self.inner.escape(escape);
+        self
+    }
+
+    /// Sets whether two adjacent quotes in a quoted field are read as a single quote.
+    /// Enabled by default.
+    pub fn double_quote(&mut self, yes: bool) -> &mut Self {
+        self.inner.double_quote(yes);
+        self
+    }
+
+    /// Sets whether quotes are recognized. Enabled by default.
+    pub fn quoting(&mut self, yes: bool) -> &mut Self {
+        self.inner.quoting(yes);
+        self
+    }
+
+    /// Sets the character that starts a comment line. Disabled by default.
+    pub fn comment(&mut self, comment: Option<u8>) -> &mut Self {
+        self.inner.comment(comment);
+        self
+    }
+
+    /// See [`Reader::lossy_utf8`].
+    pub fn lossy_utf8(&mut self, yes: bool) -> &mut Self {
+        self.config.lossy_utf8 = yes;
+        self
+    }
+
+    /// See [`Reader::encoding`].
+    pub fn encoding(&mut self, encoding: Encoding) -> &mut Self {
+        self.config.encoding = encoding;
+        self
+    }
+
+    /// See [`Reader::unsanitize_formulas`].
+    pub fn unsanitize_formulas(&mut self, yes: bool) -> &mut Self {
+        self.config.unsanitize_formulas = yes;
+        self
+    }
+
+    /// See [`Reader::formula_guard`].
+    pub fn formula_guard(&mut self, guard: u8) -> &mut Self {
+        self.config.formula_guard = guard;
+        self
+    }
+
+    /// See [`Reader::field_decoder`].
+    pub fn field_decoder(&mut self, decoder: Option<&'static dyn FieldDecoder>) -> &mut Self {
+        self.config.field_decoder = decoder;
+        self
+    }
+
+    /// See [`Reader::null_literal`].
+    pub fn null_literal(&mut self, literal: Option<&'static [u8]>) -> &mut Self {
+        self.config.null_literal = literal.unwrap_or_default();
+        self
+    }
+
+    /// See [`Reader::quoted_empty_is_none`].
+    pub fn quoted_empty_is_none(&mut self, yes: bool) -> &mut Self {
+        self.config.quoted_empty_is_none = yes;
+        self
+    }
+
+    /// See [`Reader::percent`].
+    pub fn percent(&mut self, yes: bool) -> &mut Self {
+        self.config.percent = yes;
+        self
+    }
+
+    /// See [`Reader::accounting_negatives`].
+    pub fn accounting_negatives(&mut self, yes: bool) -> &mut Self {
+        self.config.accounting_negatives = yes;
+        self
+    }
+
+    /// See [`Reader::reject_leading_zeros`].
+    pub fn reject_leading_zeros(&mut self, yes: bool) -> &mut Self {
+        self.config.reject_leading_zeros = yes;
+        self
+    }
+
+    /// See [`Reader::max_fields_per_record`].
+    pub fn max_fields_per_record(&mut self, max: usize) -> &mut Self {
+        self.config.max_fields_per_record = max;
+        self
+    }
+
+    /// See [`Reader::pad_short_records`].
+    pub fn pad_short_records(&mut self, yes: bool) -> &mut Self {
+        self.config.pad_short_records = yes;
+        self
+    }
+
+    /// See [`Reader::row_per_struct`].
+    pub fn row_per_struct(&mut self, yes: bool) -> &mut Self {
+        self.config.row_per_struct = yes;
+        self
+    }
+
+    /// See [`Reader::unordered_headers`].
+    pub fn unordered_headers(&mut self, yes: bool) -> &mut Self {
+        self.config.unordered_headers = yes;
+        self
+    }
+
+    /// See [`Reader::duplicate_headers`].
+    pub fn duplicate_headers(&mut self, policy: DuplicateHeaders) -> &mut Self {
+        self.config.duplicate_headers = policy;
+        self
+    }
+
+    /// See [`Reader::case_insensitive_headers`].
+    pub fn case_insensitive_headers(&mut self, yes: bool) -> &mut Self {
+        self.config.case_insensitive_headers = yes;
+        self
+    }
+
+    /// See [`Reader::max_record_len`].
+    pub fn max_record_len(&mut self, max: usize) -> &mut Self {
+        self.config.max_record_len = max;
+        self
+    }
+
+    /// See [`Reader::record_len_includes_terminator`].
+    pub fn record_len_includes_terminator(&mut self, yes: bool) -> &mut Self {
+        self.config.record_len_includes_terminator = yes;
+        self
+    }
+
+    /// See [`Reader::require_terminator`].
+    pub fn require_terminator(&mut self, yes: bool) -> &mut Self {
+        self.config.require_terminator = yes;
+        self
+    }
+
+    /// See [`Reader::max_str_len`].
+    pub fn max_str_len(&mut self, max: usize) -> &mut Self {
+        self.config.max_str_len = max;
+        self
+    }
+
+    /// See [`Reader::fixed_widths`].
+    pub fn fixed_widths(&mut self, widths: Option<&'static [usize]>) -> &mut Self {
+        self.config.fixed_widths = widths;
+        self
+    }
+
+    /// See [`Reader::trim_padding`].
+    pub fn trim_padding(&mut self, yes: bool) -> &mut Self {
+        self.config.trim_padding = yes;
+        self
+    }
+
+    /// See [`Reader::with_expected_fields`].
+    pub fn with_expected_fields(&mut self, count: usize) -> &mut Self {
+        self.config.expected_fields = Some(count);
+        self
+    }
+
+    /// See [`Reader::enum_tag_column`].
+    pub fn enum_tag_column(&mut self, column: usize) -> &mut Self {
+        self.config.enum_tag_column = column;
+        self
+    }
+
+    /// See [`Reader::checksum`].
+    pub fn checksum(&mut self, checksum: Option<Checksum>) -> &mut Self {
+        self.config.checksum = checksum;
+        self
+    }
+}
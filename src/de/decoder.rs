@@ -0,0 +1,58 @@
+#[cfg(feature = "percent-encoding")]
+use super::Error;
+use super::Result;
+
+/// Transformation reversing the encoding of every field after it's unescaped,
+/// set by [`Reader::field_decoder`](super::Reader::field_decoder).
+///
+/// Decoders are shared by reference, so they must be `Sync` to keep readers `Send` and `Sync`.
+pub trait FieldDecoder: core::fmt::Debug + Sync {
+    /// Decodes `field` in place, returning the length of the decoded field.
+    fn decode(&self, field: &mut [u8]) -> Result<usize>;
+}
+
+/// Decoding of percent-encoded bytes, written as `%` followed by two hexadecimal digits
+/// of either case, e.g. by [`ser::PercentEncoder`](crate::ser::PercentEncoder).
+///
+/// Other bytes are left as they are. [`Error::InvalidEncoding`] is returned
+/// for a `%` that isn't followed by two hexadecimal digits.
+///
+/// # Example
+/// ```
+/// use heapless::String;
+/// use serde_csv_core::de::PercentDecoder;
+///
+/// let mut reader = serde_csv_core::Reader::<16>::new();
+/// reader.field_decoder(Some(&PercentDecoder));
+///
+/// let (record, _) = reader.deserialize::<(String<8>, u8)>(b"a%20b%2Cc,%31\n")?;
+///
+/// assert_eq!(record, ("a b,c".into(), 1));
+/// # Ok::<(), serde_csv_core::de::Error>(())
+/// ```
+#[cfg(feature = "percent-encoding")]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct PercentDecoder;
+
+#[cfg(feature = "percent-encoding")]
+impl FieldDecoder for PercentDecoder {
+    fn decode(&self, field: &mut [u8]) -> Result<usize> {
+        let digit = |b: u8| char::from(b).to_digit(16).ok_or(Error::InvalidEncoding);
+        let mut r = 0;
+        let mut w = 0;
+        while r < field.len() {
+            field[w] = match field[r] {
+                b'%' => {
+                    let hex = field.get(r + 1..r + 3).ok_or(Error::InvalidEncoding)?;
+                    let byte = (digit(hex[0])? << 4 | digit(hex[1])?) as u8;
+                    r += 2;
+                    byte
+                }
+                b => b,
+            };
+            r += 1;
+            w += 1;
+        }
+        Ok(w)
+    }
+}
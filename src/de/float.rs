@@ -0,0 +1,208 @@
+//! Parsing of floating-point fields.
+//!
+//! Floats are parsed by `lexical-parse-float`, unless the `minimal-float` feature is enabled
+//! or the `lexical-parse-float` feature is disabled. The compact parser used then accepts
+//! an optional sign, digits with an optional fractional part and an optional exponent,
+//! like `-12.5e-3`. Unlike `lexical-parse-float`, it rejects `NaN`, infinities and numbers
+//! too large to represent, like `1e309`, or `1e39` as `f32`. Its result is occasionally one unit in the last place
+//! away from the nearest float, since digits past the 19th significant one are dropped
+//! and powers of 10 are approximated with 64 significant bits.
+
+/// A floating-point type that can be parsed from a field.
+pub(super) trait Float: Sized + From<u8> + core::ops::Div<Output = Self> {
+    /// Parses a whole field as a number.
+    fn parse(bytes: &[u8]) -> Option<Self>;
+}
+
+#[cfg(all(feature = "lexical-parse-float", not(feature = "minimal-float")))]
+impl Float for f32 {
+    fn parse(bytes: &[u8]) -> Option<Self> {
+        lexical_parse_float::FromLexical::from_lexical(bytes).ok()
+    }
+}
+
+#[cfg(all(feature = "lexical-parse-float", not(feature = "minimal-float")))]
+impl Float for f64 {
+    fn parse(bytes: &[u8]) -> Option<Self> {
+        lexical_parse_float::FromLexical::from_lexical(bytes).ok()
+    }
+}
+
+#[cfg(any(not(feature = "lexical-parse-float"), feature = "minimal-float"))]
+impl Float for f32 {
+    fn parse(bytes: &[u8]) -> Option<Self> {
+        // Numbers beyond the range of `f32` become infinite when narrowed, so they're rejected too.
+        minimal::parse(bytes)
+            .map(|v| v as f32)
+            .filter(|v| !v.is_infinite())
+    }
+}
+
+#[cfg(any(not(feature = "lexical-parse-float"), feature = "minimal-float"))]
+impl Float for f64 {
+    fn parse(bytes: &[u8]) -> Option<Self> {
+        minimal::parse(bytes)
+    }
+}
+
+#[cfg(any(not(feature = "lexical-parse-float"), feature = "minimal-float"))]
+mod minimal {
+    /// Significant digits that fit in a `u64`.
+    const MAX_DIGITS: u32 = 19;
+
+    /// Exponents are clamped to it, which is far beyond the range of `f64`.
+    const MAX_EXPONENT: i32 = 10_000;
+
+    /// Parses a decimal number with an optional fractional part and exponent.
+    pub fn parse(bytes: &[u8]) -> Option<f64> {
+        let (negative, mut rest) = split_sign(bytes);
+        let mut mantissa = 0u64;
+        let mut ndigits = 0;
+        let mut exponent = 0i32;
+        let mut any_digits = false;
+        let mut fraction = false;
+        while let Some((&b, tail)) = rest.split_first() {
+            match b {
+                b'0'..=b'9' => {
+                    any_digits = true;
+                    if ndigits < MAX_DIGITS {
+                        mantissa = mantissa * 10 + u64::from(b - b'0');
+                        // Leading zeros aren't significant.
+                        if mantissa > 0 {
+                            ndigits += 1;
+                        }
+                        if fraction {
+                            exponent -= 1;
+                        }
+                    } else if !fraction {
+                        // A dropped digit of the integer part still scales the number.
+                        exponent += 1;
+                    }
+                }
+                b'.' if !fraction => fraction = true,
+                _ => break,
+            }
+            rest = tail;
+        }
+        if !any_digits {
+            return None;
+        }
+        if let Some((b'e' | b'E', tail)) = rest.split_first() {
+            let (negative, digits) = split_sign(tail);
+            if digits.is_empty() || !digits.iter().all(u8::is_ascii_digit) {
+                return None;
+            }
+            let value = digits.iter().fold(0i32, |value, &b| {
+                (value * 10 + i32::from(b - b'0')).min(MAX_EXPONENT)
+            });
+            exponent = exponent.saturating_add(if negative { -value } else { value });
+        } else if !rest.is_empty() {
+            return None;
+        }
+        if mantissa == 0 {
+            return Some(if negative { -0.0 } else { 0.0 });
+        }
+        let value = scale(mantissa, exponent);
+        // Numbers out of range are rejected rather than made infinite.
+        if value.is_infinite() {
+            return None;
+        }
+        Some(if negative { -value } else { value })
+    }
+
+    fn split_sign(bytes: &[u8]) -> (bool, &[u8]) {
+        match bytes.split_first() {
+            Some((b'-', rest)) => (true, rest),
+            Some((b'+', rest)) => (false, rest),
+            _ => (false, bytes),
+        }
+    }
+
+    /// Returns `mantissa * 10^exponent`.
+    fn scale(mantissa: u64, exponent: i32) -> f64 {
+        // 10^n = 5^n * 2^n, where the power of 5 is approximated with 64 significant bits.
+        let value = Extended::new(mantissa);
+        let pow5 = Extended::pow5(exponent.unsigned_abs());
+        let value = if exponent >= 0 {
+            value.mul(pow5)
+        } else {
+            value.div(pow5)
+        };
+        value.to_f64(exponent)
+    }
+
+    /// A number `m * 2^e`, where the most significant bit of `m` is set.
+    #[derive(Clone, Copy)]
+    struct Extended {
+        m: u64,
+        e: i32,
+    }
+
+    impl Extended {
+        fn new(value: u64) -> Self {
+            let shift = value.leading_zeros();
+            Self {
+                m: value << shift,
+                e: -(shift as i32),
+            }
+        }
+
+        fn pow5(mut n: u32) -> Self {
+            let mut result = Self::new(1);
+            let mut base = Self::new(5);
+            while n > 0 {
+                if n & 1 == 1 {
+                    result = result.mul(base);
+                }
+                base = base.mul(base);
+                n >>= 1;
+            }
+            result
+        }
+
+        fn mul(self, other: Self) -> Self {
+            let product = u128::from(self.m) * u128::from(other.m);
+            Self::round(product, self.e + other.e)
+        }
+
+        fn div(self, other: Self) -> Self {
+            let quotient = (u128::from(self.m) << 64) / u128::from(other.m);
+            Self::round(quotient, self.e - other.e - 64)
+        }
+
+        /// Rounds `value * 2^e` to 64 significant bits.
+        fn round(value: u128, e: i32) -> Self {
+            let shift = 64 - value.leading_zeros() as i32;
+            if shift <= 0 {
+                return Self::new(value as u64).with_exponent(e);
+            }
+            let half = 1u128 << (shift - 1);
+            let rounded = (value + half) >> shift;
+            match u64::try_from(rounded) {
+                Ok(m) => Self::new(m).with_exponent(e + shift),
+                // Rounded up to the next power of 2.
+                Err(_) => Self::new(1 << 63).with_exponent(e + shift + 1),
+            }
+        }
+
+        fn with_exponent(self, e: i32) -> Self {
+            Self {
+                m: self.m,
+                e: self.e + e,
+            }
+        }
+
+        /// Returns the number multiplied by `2^exponent`.
+        fn to_f64(self, exponent: i32) -> f64 {
+            let mut value = self.m as f64;
+            let mut e = self.e + exponent;
+            // Powers of 2 are applied in steps that are normal floats.
+            while e != 0 {
+                let step = e.clamp(-1000, 1000);
+                value *= f64::from_bits(((1023 + step) as u64) << 52);
+                e -= step;
+            }
+            value
+        }
+    }
+}
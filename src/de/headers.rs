@@ -0,0 +1,623 @@
+use super::{Deserializer, Error, Result};
+use core::ops::Range;
+use serde::de::{DeserializeSeed, IntoDeserializer};
+
+/// Handling of columns with the same name, set by [`Reader::duplicate_headers`].
+///
+/// [`Reader::duplicate_headers`]: super::Reader::duplicate_headers
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum DuplicateHeaders {
+    /// [`Error::DuplicateHeader`] is returned.
+    #[default]
+    Error,
+    /// The first of the columns is used and the later ones are skipped.
+    FirstWins,
+    /// The last of the columns is used and the earlier ones are skipped.
+    LastWins,
+}
+
+/// Column names read from a header record by [`Reader::read_headers`](super::Reader::read_headers).
+///
+/// `C` is the maximum number of columns and `B` is a capacity of a buffer
+/// that stores all the names, in bytes.
+#[derive(Debug, Clone)]
+pub struct Headers<const C: usize, const B: usize> {
+    names: [u8; B],
+    ends: [usize; C],
+    skipped: [bool; C],
+    len: usize,
+}
+
+impl<const C: usize, const B: usize> Default for Headers<C, B> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<const C: usize, const B: usize> Headers<C, B> {
+    /// Constructs an empty set of headers.
+    pub const fn new() -> Self {
+        Self {
+            names: [0; B],
+            ends: [0; C],
+            skipped: [false; C],
+            len: 0,
+        }
+    }
+
+    /// Returns the number of columns.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if there are no columns.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns the name of the column at `index`.
+    pub fn get(&self, index: usize) -> Option<&str> {
+        self.names().get(index)
+    }
+
+    /// Returns an iterator over the column names.
+    pub fn iter(&self) -> impl Iterator<Item = &str> {
+        let names = self.names();
+        (0..self.len).filter_map(move |i| names.get(i))
+    }
+
+    /// Returns `true` if the column at `index` is skipped in favor of another column
+    /// with the same name, as set by [`Reader::duplicate_headers`].
+    ///
+    /// [`Reader::duplicate_headers`]: super::Reader::duplicate_headers
+    pub fn is_skipped(&self, index: usize) -> bool {
+        self.names().is_skipped(index)
+    }
+
+    pub(super) fn clear(&mut self) {
+        self.len = 0;
+    }
+
+    /// Appends a column name, resolving a duplicate of a preceding column by `policy`.
+    pub(super) fn push(
+        &mut self,
+        name: &str,
+        policy: DuplicateHeaders,
+        case_insensitive: bool,
+    ) -> Result<()> {
+        let start = self.names().end();
+        let end = start + name.len();
+        if self.len == C || end > B {
+            return Err(Error::Overflow);
+        }
+        let names = self.names();
+        let duplicate = (0..self.len).find(|&i| {
+            !names.is_skipped(i)
+                && names
+                    .get(i)
+                    .is_some_and(|n| same_name(n, name, case_insensitive))
+        });
+        let mut skipped = false;
+        match (duplicate, policy) {
+            (None, _) => {}
+            (Some(index_a), DuplicateHeaders::Error) => {
+                return Err(Error::DuplicateHeader {
+                    index_a,
+                    index_b: self.len,
+                })
+            }
+            (Some(_), DuplicateHeaders::FirstWins) => skipped = true,
+            (Some(index_a), DuplicateHeaders::LastWins) => self.skipped[index_a] = true,
+        }
+        self.names[start..end].copy_from_slice(name.as_bytes());
+        self.ends[self.len] = end;
+        self.skipped[self.len] = skipped;
+        self.len += 1;
+        Ok(())
+    }
+
+    pub(super) fn names(&self) -> HeaderNames<'_> {
+        HeaderNames {
+            names: &self.names,
+            ends: &self.ends[..self.len],
+            skipped: &self.skipped[..self.len],
+        }
+    }
+}
+
+/// A view of [`Headers`] that doesn't depend on their capacity.
+#[derive(Debug, Clone, Copy)]
+pub(super) struct HeaderNames<'a> {
+    names: &'a [u8],
+    ends: &'a [usize],
+    skipped: &'a [bool],
+}
+
+impl<'a> HeaderNames<'a> {
+    pub fn len(&self) -> usize {
+        self.ends.len()
+    }
+
+    pub fn get(&self, index: usize) -> Option<&'a str> {
+        let end = *self.ends.get(index)?;
+        let start = match index {
+            0 => 0,
+            i => self.ends[i - 1],
+        };
+        // Only valid UTF-8 is ever pushed.
+        core::str::from_utf8(&self.names[start..end]).ok()
+    }
+
+    pub fn is_skipped(&self, index: usize) -> bool {
+        self.skipped.get(index).copied().unwrap_or(false)
+    }
+
+    fn end(&self) -> usize {
+        self.ends.last().copied().unwrap_or(0)
+    }
+}
+
+/// Returns `true` if the column names are the same, ignoring ASCII case if `case_insensitive`.
+pub(super) fn same_name(a: &str, b: &str, case_insensitive: bool) -> bool {
+    match case_insensitive {
+        true => a.eq_ignore_ascii_case(b),
+        false => a == b,
+    }
+}
+
+/// Checks that no two of `fields` differ only in ASCII case, so that a column name
+/// matched ignoring case belongs to at most one of them.
+pub(super) fn check_case_folding(fields: &'static [&'static str]) -> Result<()> {
+    for (i, field) in fields.iter().enumerate() {
+        if fields[..i].iter().any(|f| f.eq_ignore_ascii_case(field)) {
+            return Err(Error::AmbiguousField(field));
+        }
+    }
+    Ok(())
+}
+
+/// Fields of a record deserialized as a map, keyed by the column names.
+///
+/// If the names are matched case-insensitively, a name is replaced by
+/// the matching one of `fields`.
+pub(super) struct Columns<'a, 'b, 'i, const N: usize> {
+    pub de: &'a mut Deserializer<'b, 'i, N>,
+    pub headers: HeaderNames<'b>,
+    pub fields: &'static [&'static str],
+    pub index: usize,
+}
+
+impl<'de, 'a, 'b, 'i, const N: usize> serde::de::MapAccess<'de> for Columns<'a, 'b, 'i, N>
+where
+    'i: 'de,
+{
+    type Error = Error;
+
+    fn next_key_seed<K>(&mut self, seed: K) -> Result<Option<K::Value>>
+    where
+        K: DeserializeSeed<'de>,
+    {
+        loop {
+            if self.index > 0 && self.de.record_end {
+                return Ok(None);
+            }
+            let Some(name) = self.headers.get(self.index) else {
+                return Ok(None);
+            };
+            self.index += 1;
+            if self.headers.is_skipped(self.index - 1) {
+                self.de.skip_field()?;
+                continue;
+            }
+            let name = match self.de.reader.config.case_insensitive_headers {
+                true => self.fields.iter().find(|f| f.eq_ignore_ascii_case(name)),
+                false => None,
+            }
+            .map_or(name, |f| *f);
+            return seed.deserialize(name.into_deserializer()).map(Some);
+        }
+    }
+
+    fn next_value_seed<V>(&mut self, seed: V) -> Result<V::Value>
+    where
+        V: DeserializeSeed<'de>,
+    {
+        self.de.depth += 1;
+        let value = seed.deserialize(&mut *self.de);
+        self.de.depth -= 1;
+        value
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.headers.len() - self.index)
+    }
+}
+
+/// Returns the field names of the struct `T`, as passed to `Deserializer::deserialize_struct`,
+/// or `None` if `T` isn't a struct.
+pub(super) fn struct_fields<'de, T>() -> Option<&'static [&'static str]>
+where
+    T: serde::Deserialize<'de>,
+{
+    let mut fields = None;
+    // The probe always fails, since it has no values to give.
+    let _ = T::deserialize(FieldNames(&mut fields));
+    fields
+}
+
+/// A deserializer that records the field names of a struct instead of deserializing it.
+struct FieldNames<'a>(&'a mut Option<&'static [&'static str]>);
+
+impl<'de> serde::Deserializer<'de> for FieldNames<'_> {
+    type Error = Error;
+
+    fn deserialize_any<V>(self, _visitor: V) -> Result<V::Value>
+    where
+        V: serde::de::Visitor<'de>,
+    {
+        Err(Error::Custom)
+    }
+
+    fn deserialize_struct<V>(
+        self,
+        _name: &'static str,
+        fields: &'static [&'static str],
+        _visitor: V,
+    ) -> Result<V::Value>
+    where
+        V: serde::de::Visitor<'de>,
+    {
+        *self.0 = Some(fields);
+        Err(Error::Custom)
+    }
+
+    serde::forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf option unit unit_struct newtype_struct seq tuple
+        tuple_struct map enum identifier ignored_any
+    }
+}
+
+/// Returns the ranges of `fields` that belong to the same field of the struct `T`, in order.
+///
+/// serde lists every name of a field, including its aliases, next to each other,
+/// but doesn't tell where the names of one field end and the next field's begin.
+pub(super) fn field_groups<'de, T>(
+    fields: &'static [&'static str],
+) -> impl Iterator<Item = Range<usize>>
+where
+    T: serde::Deserialize<'de>,
+{
+    let mut start = 0;
+    core::iter::from_fn(move || {
+        if start == fields.len() {
+            return None;
+        }
+        let mut end = start + 1;
+        while end < fields.len() && same_field::<T>(fields[end - 1], fields[end]) {
+            end += 1;
+        }
+        let group = start..end;
+        start = end;
+        Some(group)
+    })
+}
+
+/// Returns `true` if `a` and `b` are names of the same field of the struct `T`.
+///
+/// The struct is given a map with both names as keys, which it rejects as a duplicate field
+/// only if they're names of the same field. The value of the first key is a placeholder,
+/// so fields of types that reject it, e.g. `NonZeroU8`, are assumed to have a single name.
+fn same_field<'de, T>(a: &'static str, b: &'static str) -> bool
+where
+    T: serde::Deserialize<'de>,
+{
+    let result = T::deserialize(AliasProbe { names: [a, b] });
+    matches!(result, Err(Error::DuplicateField(_)))
+}
+
+/// A deserializer of a struct from a map with the keys `names`,
+/// that fails as soon as the value of the second key is requested.
+struct AliasProbe {
+    names: [&'static str; 2],
+}
+
+impl<'de> serde::Deserializer<'de> for AliasProbe {
+    type Error = Error;
+
+    fn deserialize_any<V>(self, _visitor: V) -> Result<V::Value>
+    where
+        V: serde::de::Visitor<'de>,
+    {
+        Err(Error::Custom)
+    }
+
+    fn deserialize_struct<V>(
+        self,
+        _name: &'static str,
+        _fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value>
+    where
+        V: serde::de::Visitor<'de>,
+    {
+        visitor.visit_map(AliasProbeKeys {
+            names: self.names,
+            index: 0,
+        })
+    }
+
+    serde::forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf option unit unit_struct newtype_struct seq tuple
+        tuple_struct map enum identifier ignored_any
+    }
+}
+
+struct AliasProbeKeys {
+    names: [&'static str; 2],
+    index: usize,
+}
+
+impl<'de> serde::de::MapAccess<'de> for AliasProbeKeys {
+    type Error = Error;
+
+    fn next_key_seed<K>(&mut self, seed: K) -> Result<Option<K::Value>>
+    where
+        K: DeserializeSeed<'de>,
+    {
+        let Some(&name) = self.names.get(self.index) else {
+            return Ok(None);
+        };
+        self.index += 1;
+        seed.deserialize(name.into_deserializer()).map(Some)
+    }
+
+    fn next_value_seed<V>(&mut self, seed: V) -> Result<V::Value>
+    where
+        V: DeserializeSeed<'de>,
+    {
+        match self.index {
+            1 => seed.deserialize(Placeholder),
+            // The second name belongs to another field.
+            _ => Err(Error::Custom),
+        }
+    }
+}
+
+/// A deserializer of a placeholder value, like `0` or an empty string, of any type.
+struct Placeholder;
+
+impl<'de> serde::Deserializer<'de> for Placeholder {
+    type Error = Error;
+
+    fn deserialize_any<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: serde::de::Visitor<'de>,
+    {
+        visitor.visit_u8(0)
+    }
+
+    fn deserialize_unit<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: serde::de::Visitor<'de>,
+    {
+        visitor.visit_unit()
+    }
+
+    fn deserialize_unit_struct<V>(self, _name: &'static str, visitor: V) -> Result<V::Value>
+    where
+        V: serde::de::Visitor<'de>,
+    {
+        visitor.visit_unit()
+    }
+
+    fn deserialize_ignored_any<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: serde::de::Visitor<'de>,
+    {
+        visitor.visit_unit()
+    }
+
+    fn deserialize_bool<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: serde::de::Visitor<'de>,
+    {
+        visitor.visit_bool(false)
+    }
+
+    fn deserialize_char<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: serde::de::Visitor<'de>,
+    {
+        visitor.visit_char('\0')
+    }
+
+    fn deserialize_str<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: serde::de::Visitor<'de>,
+    {
+        visitor.visit_borrowed_str("")
+    }
+
+    fn deserialize_string<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: serde::de::Visitor<'de>,
+    {
+        visitor.visit_borrowed_str("")
+    }
+
+    fn deserialize_identifier<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: serde::de::Visitor<'de>,
+    {
+        visitor.visit_borrowed_str("")
+    }
+
+    fn deserialize_bytes<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: serde::de::Visitor<'de>,
+    {
+        visitor.visit_borrowed_bytes(&[])
+    }
+
+    fn deserialize_byte_buf<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: serde::de::Visitor<'de>,
+    {
+        visitor.visit_borrowed_bytes(&[])
+    }
+
+    fn deserialize_option<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: serde::de::Visitor<'de>,
+    {
+        visitor.visit_none()
+    }
+
+    fn deserialize_newtype_struct<V>(self, _name: &'static str, visitor: V) -> Result<V::Value>
+    where
+        V: serde::de::Visitor<'de>,
+    {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_seq<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: serde::de::Visitor<'de>,
+    {
+        visitor.visit_seq(Placeholders(0))
+    }
+
+    fn deserialize_tuple<V>(self, len: usize, visitor: V) -> Result<V::Value>
+    where
+        V: serde::de::Visitor<'de>,
+    {
+        visitor.visit_seq(Placeholders(len))
+    }
+
+    fn deserialize_tuple_struct<V>(
+        self,
+        _name: &'static str,
+        len: usize,
+        visitor: V,
+    ) -> Result<V::Value>
+    where
+        V: serde::de::Visitor<'de>,
+    {
+        visitor.visit_seq(Placeholders(len))
+    }
+
+    fn deserialize_map<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: serde::de::Visitor<'de>,
+    {
+        visitor.visit_map(Placeholders(0))
+    }
+
+    fn deserialize_struct<V>(
+        self,
+        _name: &'static str,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value>
+    where
+        V: serde::de::Visitor<'de>,
+    {
+        visitor.visit_seq(Placeholders(fields.len()))
+    }
+
+    fn deserialize_enum<V>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value>
+    where
+        V: serde::de::Visitor<'de>,
+    {
+        visitor.visit_enum(self)
+    }
+
+    serde::forward_to_deserialize_any! {
+        i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64
+    }
+}
+
+impl<'de> serde::de::EnumAccess<'de> for Placeholder {
+    type Error = Error;
+    type Variant = Self;
+
+    fn variant_seed<V>(self, seed: V) -> Result<(V::Value, Self)>
+    where
+        V: DeserializeSeed<'de>,
+    {
+        seed.deserialize(0u32.into_deserializer())
+            .map(|v| (v, self))
+    }
+}
+
+impl<'de> serde::de::VariantAccess<'de> for Placeholder {
+    type Error = Error;
+
+    fn unit_variant(self) -> Result<()> {
+        Ok(())
+    }
+
+    fn newtype_variant_seed<T>(self, seed: T) -> Result<T::Value>
+    where
+        T: DeserializeSeed<'de>,
+    {
+        seed.deserialize(self)
+    }
+
+    fn tuple_variant<V>(self, len: usize, visitor: V) -> Result<V::Value>
+    where
+        V: serde::de::Visitor<'de>,
+    {
+        visitor.visit_seq(Placeholders(len))
+    }
+
+    fn struct_variant<V>(self, fields: &'static [&'static str], visitor: V) -> Result<V::Value>
+    where
+        V: serde::de::Visitor<'de>,
+    {
+        visitor.visit_seq(Placeholders(fields.len()))
+    }
+}
+
+/// A sequence of a number of placeholders, or a map without entries.
+struct Placeholders(usize);
+
+impl<'de> serde::de::SeqAccess<'de> for Placeholders {
+    type Error = Error;
+
+    fn next_element_seed<T>(&mut self, seed: T) -> Result<Option<T::Value>>
+    where
+        T: DeserializeSeed<'de>,
+    {
+        if self.0 == 0 {
+            return Ok(None);
+        }
+        self.0 -= 1;
+        seed.deserialize(Placeholder).map(Some)
+    }
+}
+
+impl<'de> serde::de::MapAccess<'de> for Placeholders {
+    type Error = Error;
+
+    fn next_key_seed<K>(&mut self, _seed: K) -> Result<Option<K::Value>>
+    where
+        K: DeserializeSeed<'de>,
+    {
+        Ok(None)
+    }
+
+    fn next_value_seed<V>(&mut self, _seed: V) -> Result<V::Value>
+    where
+        V: DeserializeSeed<'de>,
+    {
+        Err(Error::Custom)
+    }
+}
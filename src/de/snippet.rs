@@ -0,0 +1,142 @@
+/// The first bytes of a field that failed to deserialize, kept in an [`Error`](super::Error)
+/// to show the offending value in its message.
+///
+/// Up to [`Snippet::CAPACITY`] bytes are kept. When displayed, printable ASCII characters
+/// are written as they are and other bytes as `\xNN` escapes, followed by `...`
+/// if the field was truncated.
+///
+/// # Example
+/// ```
+/// use serde_csv_core::de::{Error, Snippet};
+///
+/// let mut reader = serde_csv_core::Reader::<16>::new();
+/// let result = reader.deserialize::<u32>(b"12O34\n");
+///
+/// assert_eq!(result, Err(Error::InvalidInt(Snippet::new(b"12O34"))));
+/// assert_eq!(result.unwrap_err().to_string(), "Invalid integer `12O34`.");
+/// ```
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub struct Snippet {
+    bytes: [u8; Snippet::CAPACITY],
+    len: u8,
+    truncated: bool,
+}
+
+impl Snippet {
+    /// Maximum number of bytes kept.
+    pub const CAPACITY: usize = 16;
+
+    /// Constructs a snippet of the first bytes of `field`.
+    pub fn new(field: &[u8]) -> Self {
+        let len = field.len().min(Self::CAPACITY);
+        let mut bytes = [0; Self::CAPACITY];
+        bytes[..len].copy_from_slice(&field[..len]);
+        Self {
+            bytes,
+            len: len as u8,
+            truncated: field.len() > len,
+        }
+    }
+
+    /// Constructs a snippet of the first bytes of the text of `value`.
+    pub(crate) fn from_display(value: &dyn core::fmt::Display) -> Self {
+        struct Buffer(Snippet);
+
+        impl core::fmt::Write for Buffer {
+            fn write_str(&mut self, s: &str) -> core::fmt::Result {
+                let snippet = &mut self.0;
+                let start = usize::from(snippet.len);
+                let len = s.len().min(Snippet::CAPACITY - start);
+                snippet.bytes[start..start + len].copy_from_slice(&s.as_bytes()[..len]);
+                snippet.len += len as u8;
+                snippet.truncated |= s.len() > len;
+                Ok(())
+            }
+        }
+
+        let mut buffer = Buffer(Self::new(&[]));
+        // The buffer never fails, so an error can only be reported by `value` itself.
+        let _ = core::fmt::write(&mut buffer, format_args!("{}", value));
+        buffer.0
+    }
+
+    /// Constructs a snippet of an unexpected value reported by a `Deserialize` implementation,
+    /// or of a description of its type if it has no value, e.g. `unit value`.
+    pub(crate) fn from_unexpected(unexp: serde::de::Unexpected) -> Self {
+        use serde::de::Unexpected;
+        match unexp {
+            Unexpected::Bool(v) => Self::from_display(&v),
+            Unexpected::Unsigned(v) => Self::from_display(&v),
+            Unexpected::Signed(v) => Self::from_display(&v),
+            Unexpected::Float(v) => Self::from_display(&v),
+            Unexpected::Char(v) => Self::from_display(&v),
+            Unexpected::Str(v) => Self::new(v.as_bytes()),
+            Unexpected::Bytes(v) => Self::new(v),
+            unexp => Self::from_display(&unexp),
+        }
+    }
+
+    /// Returns the bytes kept.
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.bytes[..usize::from(self.len)]
+    }
+
+    /// Returns `true` if the field was longer than [`Snippet::CAPACITY`].
+    pub fn is_truncated(&self) -> bool {
+        self.truncated
+    }
+
+    /// Splits the snippet into runs of printable ASCII characters and single other bytes.
+    fn parts(&self) -> impl Iterator<Item = Result<&str, u8>> {
+        let printable = |b: &u8| (b' '..=b'~').contains(b);
+        self.as_bytes()
+            .split_inclusive(move |b| !printable(b))
+            .flat_map(move |run| {
+                let (text, other) = match run.split_last() {
+                    Some((&b, text)) if !printable(&b) => (text, Some(b)),
+                    _ => (run, None),
+                };
+                // The run is printable ASCII, so it's always valid UTF-8.
+                let text = core::str::from_utf8(text).unwrap_or_default();
+                core::iter::once(Ok(text))
+                    .filter(|text| text != &Ok(""))
+                    .chain(other.map(Err))
+            })
+    }
+}
+
+impl core::fmt::Debug for Snippet {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "Snippet(\"{}\")", self)
+    }
+}
+
+impl core::fmt::Display for Snippet {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        for part in self.parts() {
+            match part {
+                Ok(text) => f.write_str(text)?,
+                Err(b) => write!(f, "\\x{:02X}", b)?,
+            }
+        }
+        if self.truncated {
+            f.write_str("...")?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(feature = "defmt")]
+impl defmt::Format for Snippet {
+    fn format(&self, f: defmt::Formatter) {
+        for part in self.parts() {
+            match part {
+                Ok(text) => defmt::write!(f, "{=str}", text),
+                Err(b) => defmt::write!(f, "\\x{=u8:02X}", b),
+            }
+        }
+        if self.truncated {
+            defmt::write!(f, "...");
+        }
+    }
+}
@@ -0,0 +1,51 @@
+//! Integers read and written digit by digit.
+
+/// An integer written as decimal digits by [`Scaled`](crate::Scaled) and [`Padded`](crate::Padded).
+pub trait DecimalInt: Copy + Default {
+    /// Appends a decimal digit to the number, subtracting it if the number is `negative`.
+    ///
+    /// Returns `None` on overflow.
+    fn push_digit(self, digit: u8, negative: bool) -> Option<Self>;
+
+    /// Returns whether the number is negative and its absolute value.
+    fn magnitude(self) -> (bool, u64);
+}
+
+macro_rules! impl_decimal_int {
+    ($($int:ty),*; $($uint:ty),*) => {
+        $(
+            impl DecimalInt for $int {
+                fn push_digit(self, digit: u8, negative: bool) -> Option<Self> {
+                    let shifted = self.checked_mul(10)?;
+                    if negative {
+                        shifted.checked_sub(digit as $int)
+                    } else {
+                        shifted.checked_add(digit as $int)
+                    }
+                }
+
+                fn magnitude(self) -> (bool, u64) {
+                    (self < 0, u64::from(self.unsigned_abs()))
+                }
+            }
+        )*
+        $(
+            impl DecimalInt for $uint {
+                fn push_digit(self, digit: u8, negative: bool) -> Option<Self> {
+                    let shifted = self.checked_mul(10)?;
+                    if negative {
+                        shifted.checked_sub(<$uint>::from(digit))
+                    } else {
+                        shifted.checked_add(<$uint>::from(digit))
+                    }
+                }
+
+                fn magnitude(self) -> (bool, u64) {
+                    (false, u64::from(self))
+                }
+            }
+        )*
+    };
+}
+
+impl_decimal_int!(i8, i16, i32, i64; u8, u16, u32, u64);
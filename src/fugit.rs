@@ -0,0 +1,145 @@
+//! Durations of the [`fugit`](https://crates.io/crates/fugit) crate stored in a single field
+//! as an integer, enabled by the `fugit` feature.
+//!
+//! Use [`ticks`] with `#[serde(with = "serde_csv_core::fugit::ticks")]` to store the number
+//! of ticks of a duration, or [`millis`] to store it in milliseconds, independently of
+//! its tick rate. Milliseconds are rounded down when converted from and to ticks.
+//! A number of milliseconds out of the range of the duration is an invalid value.
+//!
+//! # Example
+//! ```
+//! use fugit::{MicrosDurationU32, MillisDurationU64};
+//! use serde::{Deserialize, Serialize};
+//!
+//! #[derive(Debug, PartialEq, Serialize, Deserialize)]
+//! struct Record {
+//!     #[serde(with = "serde_csv_core::fugit::ticks")]
+//!     pub uptime: MillisDurationU64,
+//!     #[serde(with = "serde_csv_core::fugit::millis")]
+//!     pub latency: MicrosDurationU32,
+//! }
+//!
+//! let record = Record {
+//!     uptime: MillisDurationU64::secs(90),
+//!     latency: MicrosDurationU32::millis(15),
+//! };
+//!
+//! let mut csv = [0; 16];
+//! let nwritten = serde_csv_core::Writer::new().serialize(&record, &mut csv)?;
+//! assert_eq!(&csv[..nwritten], b"90000,15\n");
+//!
+//! let (read, _) = serde_csv_core::Reader::<8>::new().deserialize::<Record>(&csv[..nwritten])?;
+//! assert_eq!(read, record);
+//! # Ok::<(), serde_csv_core::Error>(())
+//! ```
+
+use serde::{de::DeserializeOwned, Serialize};
+
+/// A duration stored as an integer.
+pub trait Duration: Sized {
+    /// Integer type of the ticks, either `u32` or `u64`.
+    type Int: Copy + Serialize + DeserializeOwned + Into<u64>;
+
+    /// Returns the number of ticks.
+    fn to_ticks(&self) -> Self::Int;
+
+    /// Creates a duration from a number of ticks.
+    fn from_ticks(ticks: Self::Int) -> Self;
+
+    /// Returns the duration in whole milliseconds,
+    /// or `None` if they don't fit in [`Duration::Int`].
+    fn to_millis(&self) -> Option<Self::Int>;
+
+    /// Creates a duration from a number of milliseconds,
+    /// or returns `None` if its ticks don't fit in [`Duration::Int`].
+    fn from_millis(millis: Self::Int) -> Option<Self>;
+}
+
+macro_rules! impl_duration {
+    ($int:ty) => {
+        impl<const NOM: u32, const DENOM: u32> Duration for fugit::Duration<$int, NOM, DENOM> {
+            type Int = $int;
+
+            fn to_ticks(&self) -> $int {
+                self.ticks()
+            }
+
+            fn from_ticks(ticks: $int) -> Self {
+                Self::from_ticks(ticks)
+            }
+
+            fn to_millis(&self) -> Option<$int> {
+                fugit::Duration::<$int, 1, 1_000>::const_try_from(*self).map(|d| d.ticks())
+            }
+
+            fn from_millis(millis: $int) -> Option<Self> {
+                Self::const_try_from(fugit::Duration::<$int, 1, 1_000>::from_ticks(millis))
+            }
+        }
+    };
+}
+
+impl_duration!(u32);
+impl_duration!(u64);
+
+/// A duration stored as its number of ticks.
+pub mod ticks {
+    use super::Duration;
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    /// Serializes a duration as its number of ticks.
+    pub fn serialize<T, S>(value: &T, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        T: Duration,
+        S: Serializer,
+    {
+        value.to_ticks().serialize(serializer)
+    }
+
+    /// Deserializes a duration from its number of ticks.
+    pub fn deserialize<'de, T, D>(deserializer: D) -> Result<T, D::Error>
+    where
+        T: Duration,
+        D: Deserializer<'de>,
+    {
+        T::Int::deserialize(deserializer).map(T::from_ticks)
+    }
+}
+
+/// A duration stored in milliseconds.
+pub mod millis {
+    use super::Duration;
+    use serde::{de, ser, Deserialize, Deserializer, Serialize, Serializer};
+
+    /// Serializes a duration in milliseconds.
+    ///
+    /// Returns an error if the milliseconds don't fit in the integer type of the duration.
+    pub fn serialize<T, S>(value: &T, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        T: Duration,
+        S: Serializer,
+    {
+        use ser::Error;
+
+        match value.to_millis() {
+            Some(millis) => millis.serialize(serializer),
+            None => Err(S::Error::custom("duration in milliseconds out of range")),
+        }
+    }
+
+    /// Deserializes a duration from milliseconds.
+    ///
+    /// Returns an invalid value error if the duration can't represent the milliseconds.
+    pub fn deserialize<'de, T, D>(deserializer: D) -> Result<T, D::Error>
+    where
+        T: Duration,
+        D: Deserializer<'de>,
+    {
+        use de::Error;
+
+        let millis = T::Int::deserialize(deserializer)?;
+        let unexpected = de::Unexpected::Unsigned(millis.into());
+        T::from_millis(millis)
+            .ok_or_else(|| D::Error::invalid_value(unexpected, &"milliseconds in range"))
+    }
+}
@@ -59,11 +59,11 @@
 //!
 //! let mut reader = serde_csv_core::Reader::<32>::new();
 //! let mut records: Vec<Record, 2> = Vec::new();
-//! let mut nread = 0;
-//! while nread < csv.len() {
-//!     let (record, n)  = reader.deserialize::<Record>(&csv[nread..])?;
-//!     nread += n;
+//! let mut rest = &csv[..];
+//! while !rest.is_empty() {
+//!     let (record, tail) = reader.deserialize_rest::<Record>(rest)?;
 //!     records.push(record);
+//!     rest = tail;
 //! }
 //!
 //! assert_eq!(records, &[
@@ -82,21 +82,34 @@
 //! ```
 //!
 //! # Configuration
-//! Both [`Writer`] and [`Reader`] are wrappers for [`csv_core::Writer`]
-//! and [`csv_core::Reader`], respectively. You can use [`csv_core::WriterBuilder`]
-//! and [`csv_core::ReaderBuilder`] in combination with `from_builder` constructors
-//! to tweak things like field delimiters etc.
+//! [`ser::WriterBuilder`] and [`de::ReaderBuilder`] set both the CSV format,
+//! like field delimiters, and the serde-level options of [`Writer`] and [`Reader`].
 //! ```
-//! use serde_csv_core::csv_core;
+//! let writer = serde_csv_core::ser::WriterBuilder::new()
+//!     .delimiter(b'-')
+//!     .sanitize_formulas(true)
+//!     .build();
 //!
-//! let writer = serde_csv_core::Writer::from_builder(
-//!     csv_core::WriterBuilder::new()
-//!         .delimiter(b'-')
-//! );
+//! let reader = serde_csv_core::de::ReaderBuilder::new()
+//!     .delimiter(b'-')
+//!     .pad_short_records(true)
+//!     .build::<16>();
 //! ```
 #![no_std]
 
+#[cfg(feature = "alloc")]
+extern crate alloc;
+
+pub mod bits8;
+mod checksum;
 pub mod de;
+mod decimal;
+#[cfg(feature = "fugit")]
+pub mod fugit;
+mod nested;
+pub mod opt_flagged;
+mod padded;
+mod scaled;
 pub mod ser;
 
 #[doc(inline)]
@@ -104,6 +117,151 @@ pub use de::Reader;
 #[doc(inline)]
 pub use ser::Writer;
 
+pub use checksum::Checksum;
+pub use decimal::DecimalInt;
+pub use nested::Nested;
+pub use padded::Padded;
+pub use scaled::Scaled;
+
 pub use csv_core;
 #[cfg(feature = "heapless")]
 pub use heapless;
+
+/// Asserts at compile time that a field buffer of capacity `N` fits fields of up to `MAX` bytes.
+///
+/// Expands to a `const` item that fails to compile when `N < MAX`,
+/// so a maximum field length can be enforced for a [`Reader<N>`] at build time
+/// instead of through [`de::Error::Overflow`] at runtime.
+/// Both arguments must be constant expressions of type `usize`.
+///
+/// # Example
+/// ```
+/// const FIELD_CAPACITY: usize = 32;
+/// const MAX_CITY_LEN: usize = 24;
+///
+/// serde_csv_core::assert_field_capacity!(FIELD_CAPACITY, MAX_CITY_LEN);
+///
+/// let reader = serde_csv_core::Reader::<FIELD_CAPACITY>::new();
+/// ```
+///
+/// A buffer that's too small doesn't compile:
+/// ```compile_fail
+/// serde_csv_core::assert_field_capacity!(16, 24);
+/// ```
+#[macro_export]
+macro_rules! assert_field_capacity {
+    ($n:expr, $max:expr $(,)?) => {
+        const _: () = ::core::assert!(
+            $n >= $max,
+            "field buffer capacity `N` is smaller than the maximum field length"
+        );
+    };
+}
+
+/// Error of either serialization or deserialization.
+///
+/// Both [`ser::Error`] and [`de::Error`] convert into it, so it can be used with `?`
+/// in code that does both.
+#[derive(Debug, PartialEq, Eq)]
+pub enum Error {
+    /// Serialization error.
+    Ser(ser::Error),
+    /// Deserialization error.
+    De(de::Error),
+}
+
+impl From<ser::Error> for Error {
+    fn from(e: ser::Error) -> Self {
+        Self::Ser(e)
+    }
+}
+
+impl From<de::Error> for Error {
+    fn from(e: de::Error) -> Self {
+        Self::De(e)
+    }
+}
+
+impl core::fmt::Display for Error {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::Ser(e) => e.fmt(f),
+            Self::De(e) => e.fmt(f),
+        }
+    }
+}
+
+impl serde::ser::StdError for Error {}
+
+#[cfg(feature = "defmt")]
+impl defmt::Format for Error {
+    fn format(&self, f: defmt::Formatter) {
+        match self {
+            Self::Ser(e) => e.format(f),
+            Self::De(e) => e.format(f),
+        }
+    }
+}
+
+/// Character encoding of CSV text fields.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Encoding {
+    /// UTF-8.
+    #[default]
+    Utf8,
+    /// ISO 8859-1, every byte maps to the Unicode code point of the same value.
+    Latin1,
+    /// Windows-1252, a superset of ISO 8859-1 that maps bytes `0x80..=0x9F`
+    /// to printable characters such as `€`.
+    ///
+    /// Bytes left undefined by Windows-1252 map to the C1 control characters of the same value.
+    Windows1252,
+}
+
+impl Encoding {
+    /// Characters of Windows-1252 bytes `0x80..=0x9F`.
+    const WINDOWS_1252: [char; 32] = [
+        '\u{20AC}', '\u{0081}', '\u{201A}', '\u{0192}', '\u{201E}', '\u{2026}', '\u{2020}',
+        '\u{2021}', '\u{02C6}', '\u{2030}', '\u{0160}', '\u{2039}', '\u{0152}', '\u{008D}',
+        '\u{017D}', '\u{008F}', '\u{0090}', '\u{2018}', '\u{2019}', '\u{201C}', '\u{201D}',
+        '\u{2022}', '\u{2013}', '\u{2014}', '\u{02DC}', '\u{2122}', '\u{0161}', '\u{203A}',
+        '\u{0153}', '\u{009D}', '\u{017E}', '\u{0178}',
+    ];
+
+    /// Decodes a single byte of a single-byte encoding.
+    ///
+    /// For UTF-8, bytes are decoded as if the encoding was Latin-1.
+    pub(crate) fn decode_byte(self, b: u8) -> char {
+        match (self, b) {
+            (Self::Windows1252, 0x80..=0x9f) => Self::WINDOWS_1252[usize::from(b - 0x80)],
+            _ => char::from(b),
+        }
+    }
+
+    /// Encodes a character in a single-byte encoding,
+    /// or returns `None` if the encoding can't represent it.
+    ///
+    /// For UTF-8, characters are encoded as if the encoding was Latin-1.
+    pub(crate) fn encode_char(self, c: char) -> Option<u8> {
+        match (self, u8::try_from(c)) {
+            (Self::Windows1252, Ok(0x80..=0x9f) | Err(_)) => Self::WINDOWS_1252
+                .iter()
+                .position(|&w| w == c)
+                .map(|i| 0x80 + i as u8),
+            (_, b) => b.ok(),
+        }
+    }
+}
+
+/// Uppercase hexadecimal digits, indexed by their value.
+pub(crate) const HEX_DIGITS: &[u8; 16] = b"0123456789ABCDEF";
+
+/// Returns `true` if a spreadsheet application could interpret `input` as a formula,
+/// ignoring any leading `guard` bytes.
+pub(crate) fn is_formula(input: &[u8], guard: u8) -> bool {
+    let start = input.iter().take_while(|&&b| b == guard).count();
+    matches!(
+        input.get(start),
+        Some(b'=' | b'+' | b'-' | b'@' | b'\t' | b'\r')
+    )
+}
@@ -0,0 +1,143 @@
+//! CSV records embedded in a single field of another record.
+
+use serde::{de, ser, Deserialize, Serialize};
+
+/// Name of a newtype struct that [`Nested`] serializes to report that it didn't fit in its buffer.
+///
+/// [`Serializer`](crate::Serializer) returns
+/// [`ser::Error::NestedOverflow`](crate::ser::Error::NestedOverflow) for it,
+/// other serializers return the custom error of [`Overflow`].
+pub(crate) const OVERFLOW: &str = "$serde_csv_core::NestedOverflow";
+
+/// Name of the newtype struct that [`Nested`] is deserialized as.
+///
+/// The deserializer of [`Reader`](crate::Reader) reports a length error of its field as
+/// [`de::Error::NestedOverflow`](crate::de::Error::NestedOverflow), since it's returned only when
+/// the record doesn't fit in its buffer.
+pub(crate) const NAME: &str = "$serde_csv_core::Nested";
+
+/// A value serialized as a CSV record of its own, stored in a single field of the outer record.
+///
+/// The inner record is written with `DELIMITER` and without a terminator,
+/// then it's written to the outer record like a string, so the outer quoting applies to it.
+/// `N` is a capacity of a buffer that temporarily stores the inner record, in bytes;
+/// [`ser::Error::NestedOverflow`](crate::ser::Error::NestedOverflow) is returned
+/// when it's exceeded.
+///
+/// When deserialized, the unescaped field is read as a record by a separate
+/// [`Reader<N>`](crate::Reader) with `DELIMITER`, so `N` is a capacity of its field buffer;
+/// [`de::Error::NestedOverflow`](crate::de::Error::NestedOverflow) is returned
+/// when it's exceeded.
+///
+/// # Example
+/// ```
+/// use serde_csv_core::Nested;
+///
+/// let mut writer = serde_csv_core::Writer::new();
+/// let mut csv = [0; 32];
+///
+/// let nwritten = writer.serialize(&(7, Nested::<_, b';', 16>(("E1", "a;b"))), &mut csv)?;
+///
+/// assert_eq!(&csv[..nwritten], b"7,\"E1;\"\"a;b\"\"\"\n");
+/// # Ok::<(), serde_csv_core::ser::Error>(())
+/// ```
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Nested<T, const DELIMITER: u8 = b';', const N: usize = 128>(pub T);
+
+impl<T, const DELIMITER: u8, const N: usize> Serialize for Nested<T, DELIMITER, N>
+where
+    T: Serialize,
+{
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: ser::Serializer,
+    {
+        use ser::Error;
+
+        let mut writer =
+            crate::Writer::from_builder(csv_core::WriterBuilder::new().delimiter(DELIMITER));
+        let mut buf = [0; N];
+        let len = match writer.serialize_field_only(&self.0, &mut buf) {
+            Ok(len) => len,
+            Err(crate::ser::Error::Overflow) => {
+                return serializer.serialize_newtype_struct(OVERFLOW, &Overflow)
+            }
+            Err(e) => return Err(S::Error::custom(e)),
+        };
+        match core::str::from_utf8(&buf[..len]) {
+            Ok(record) => serializer.serialize_str(record),
+            Err(_) => serializer.serialize_bytes(&buf[..len]),
+        }
+    }
+}
+
+impl<'de, T, const DELIMITER: u8, const N: usize> Deserialize<'de> for Nested<T, DELIMITER, N>
+where
+    T: de::DeserializeOwned,
+{
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: de::Deserializer<'de>,
+    {
+        deserializer.deserialize_newtype_struct(
+            NAME,
+            NestedVisitor::<T, DELIMITER, N>(core::marker::PhantomData),
+        )
+    }
+}
+
+struct NestedVisitor<T, const DELIMITER: u8, const N: usize>(core::marker::PhantomData<T>);
+
+impl<'de, T, const DELIMITER: u8, const N: usize> de::Visitor<'de>
+    for NestedVisitor<T, DELIMITER, N>
+where
+    T: de::DeserializeOwned,
+{
+    type Value = Nested<T, DELIMITER, N>;
+
+    fn expecting(&self, formatter: &mut core::fmt::Formatter) -> core::fmt::Result {
+        formatter.write_str("a nested CSV record")
+    }
+
+    fn visit_bytes<E>(self, v: &[u8]) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        let mut reader: crate::Reader<N> =
+            crate::Reader::from_builder(csv_core::ReaderBuilder::new().delimiter(DELIMITER));
+        match reader.deserialize(v) {
+            Ok((value, nread)) if nread == v.len() => Ok(Nested(value)),
+            Ok(_) => Err(E::custom("trailing data after a nested record")),
+            Err(crate::de::Error::Overflow | crate::de::Error::FieldTooLong { .. }) => {
+                Err(E::invalid_length(v.len(), &self))
+            }
+            Err(e) => Err(E::custom(e)),
+        }
+    }
+
+    fn visit_str<E>(self, v: &str) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        self.visit_bytes(v.as_bytes())
+    }
+
+    fn visit_newtype_struct<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: de::Deserializer<'de>,
+    {
+        deserializer.deserialize_bytes(self)
+    }
+}
+
+/// Overflow of the buffer of [`Nested`], see [`OVERFLOW`].
+struct Overflow;
+
+impl Serialize for Overflow {
+    fn serialize<S>(&self, _serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: ser::Serializer,
+    {
+        Err(ser::Error::custom("nested record overflow"))
+    }
+}
@@ -0,0 +1,265 @@
+//! An optional value stored in two fields: a presence flag and the value.
+//!
+//! Use it with `#[serde(with = "serde_csv_core::opt_flagged")]` on a field of type `Option<T>`.
+//! `Some(value)` is written as `1` followed by the value, and `None` as `0` followed by
+//! an empty field, so that a present value that's written as an empty field,
+//! like an empty string, isn't read back as `None`.
+//! The value must be empty when the flag is `0`.
+//!
+//! `None` takes a single field, so the value must be written as a single field as well.
+//! Compound values, like tuples and structs, are rejected with a custom error.
+//!
+//! # Example
+//! ```
+//! use serde::{Deserialize, Serialize};
+//!
+//! #[derive(Debug, PartialEq, Serialize, Deserialize)]
+//! struct Record {
+//!     pub id: u8,
+//!     #[serde(with = "serde_csv_core::opt_flagged")]
+//!     pub reading: Option<u16>,
+//! }
+//!
+//! let records = [Record { id: 1, reading: Some(5) }, Record { id: 2, reading: None }];
+//!
+//! let mut writer = serde_csv_core::Writer::new();
+//! let mut csv = [0; 16];
+//! let mut nwritten = 0;
+//! for record in &records {
+//!     nwritten += writer.serialize(record, &mut csv[nwritten..])?;
+//! }
+//! assert_eq!(&csv[..nwritten], b"1,1,5\n2,0,\n");
+//!
+//! let mut reader = serde_csv_core::Reader::<8>::new();
+//! let (first, nread) = reader.deserialize::<Record>(&csv[..nwritten])?;
+//! let (second, _) = reader.deserialize::<Record>(&csv[nread..nwritten])?;
+//! assert_eq!([first, second], records);
+//! # Ok::<(), serde_csv_core::Error>(())
+//! ```
+
+use serde::ser::{self, Impossible, SerializeTuple};
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+
+/// Serializes an optional value as a presence flag followed by the value.
+pub fn serialize<T, S>(value: &Option<T>, serializer: S) -> Result<S::Ok, S::Error>
+where
+    T: Serialize,
+    S: Serializer,
+{
+    let mut tuple = serializer.serialize_tuple(2)?;
+    match value {
+        Some(value) => {
+            tuple.serialize_element(&1u8)?;
+            tuple.serialize_element(&Scalar(value))?;
+        }
+        None => {
+            tuple.serialize_element(&0u8)?;
+            tuple.serialize_element(&())?;
+        }
+    }
+    tuple.end()
+}
+
+/// A value that must be written as a single field.
+struct Scalar<'a, T>(&'a T);
+
+impl<T: Serialize> Serialize for Scalar<'_, T> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        self.0.serialize(ScalarSerializer(serializer))
+    }
+}
+
+/// Passes values written as a single field through to `S`, rejecting compound values.
+struct ScalarSerializer<S>(S);
+
+/// Returns the error for a compound value.
+fn compound<T, E: ser::Error>() -> Result<T, E> {
+    Err(E::custom("a flagged value must be a single field"))
+}
+
+macro_rules! forward {
+    ($($method:ident($ty:ty)),* $(,)?) => {
+        $(
+            fn $method(self, v: $ty) -> Result<S::Ok, S::Error> {
+                self.0.$method(v)
+            }
+        )*
+    };
+}
+
+impl<S: Serializer> Serializer for ScalarSerializer<S> {
+    type Ok = S::Ok;
+    type Error = S::Error;
+    type SerializeSeq = Impossible<S::Ok, S::Error>;
+    type SerializeTuple = Impossible<S::Ok, S::Error>;
+    type SerializeTupleStruct = Impossible<S::Ok, S::Error>;
+    type SerializeTupleVariant = Impossible<S::Ok, S::Error>;
+    type SerializeMap = Impossible<S::Ok, S::Error>;
+    type SerializeStruct = Impossible<S::Ok, S::Error>;
+    type SerializeStructVariant = Impossible<S::Ok, S::Error>;
+
+    forward!(
+        serialize_bool(bool),
+        serialize_i8(i8),
+        serialize_i16(i16),
+        serialize_i32(i32),
+        serialize_i64(i64),
+        serialize_i128(i128),
+        serialize_u8(u8),
+        serialize_u16(u16),
+        serialize_u32(u32),
+        serialize_u64(u64),
+        serialize_u128(u128),
+        serialize_f32(f32),
+        serialize_f64(f64),
+        serialize_char(char),
+        serialize_str(&str),
+        serialize_bytes(&[u8]),
+        serialize_unit_struct(&'static str),
+    );
+
+    fn serialize_none(self) -> Result<S::Ok, S::Error> {
+        self.0.serialize_none()
+    }
+
+    fn serialize_some<T>(self, value: &T) -> Result<S::Ok, S::Error>
+    where
+        T: Serialize + ?Sized,
+    {
+        self.0.serialize_some(&Scalar(&value))
+    }
+
+    fn serialize_unit(self) -> Result<S::Ok, S::Error> {
+        self.0.serialize_unit()
+    }
+
+    fn serialize_unit_variant(
+        self,
+        name: &'static str,
+        variant_index: u32,
+        variant: &'static str,
+    ) -> Result<S::Ok, S::Error> {
+        self.0.serialize_unit_variant(name, variant_index, variant)
+    }
+
+    fn serialize_newtype_struct<T>(self, name: &'static str, value: &T) -> Result<S::Ok, S::Error>
+    where
+        T: Serialize + ?Sized,
+    {
+        self.0.serialize_newtype_struct(name, &Scalar(&value))
+    }
+
+    fn serialize_newtype_variant<T>(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _value: &T,
+    ) -> Result<S::Ok, S::Error>
+    where
+        T: Serialize + ?Sized,
+    {
+        compound()
+    }
+
+    fn serialize_seq(self, _len: Option<usize>) -> Result<Self::SerializeSeq, S::Error> {
+        compound()
+    }
+
+    fn serialize_tuple(self, _len: usize) -> Result<Self::SerializeTuple, S::Error> {
+        compound()
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        _name: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeTupleStruct, S::Error> {
+        compound()
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeTupleVariant, S::Error> {
+        compound()
+    }
+
+    fn serialize_map(self, _len: Option<usize>) -> Result<Self::SerializeMap, S::Error> {
+        compound()
+    }
+
+    fn serialize_struct(
+        self,
+        _name: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeStruct, S::Error> {
+        compound()
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeStructVariant, S::Error> {
+        compound()
+    }
+
+    fn collect_str<T>(self, value: &T) -> Result<S::Ok, S::Error>
+    where
+        T: core::fmt::Display + ?Sized,
+    {
+        self.0.collect_str(value)
+    }
+
+    fn is_human_readable(&self) -> bool {
+        self.0.is_human_readable()
+    }
+}
+
+/// Deserializes an optional value from a presence flag followed by the value.
+pub fn deserialize<'de, T, D>(deserializer: D) -> Result<Option<T>, D::Error>
+where
+    T: Deserialize<'de>,
+    D: Deserializer<'de>,
+{
+    deserializer.deserialize_tuple(2, OptFlaggedVisitor(core::marker::PhantomData))
+}
+
+struct OptFlaggedVisitor<T>(core::marker::PhantomData<T>);
+
+impl<'de, T: Deserialize<'de>> de::Visitor<'de> for OptFlaggedVisitor<T> {
+    type Value = Option<T>;
+
+    fn expecting(&self, formatter: &mut core::fmt::Formatter) -> core::fmt::Result {
+        formatter.write_str("a presence flag of 0 or 1 followed by a value")
+    }
+
+    fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+    where
+        A: de::SeqAccess<'de>,
+    {
+        let flag = seq
+            .next_element::<u8>()?
+            .ok_or_else(|| de::Error::invalid_length(0, &self))?;
+        let value = match flag {
+            0 => seq.next_element::<()>()?.map(|()| None),
+            1 => seq.next_element::<T>()?.map(Some),
+            flag => {
+                return Err(de::Error::invalid_value(
+                    de::Unexpected::Unsigned(u64::from(flag)),
+                    &self,
+                ))
+            }
+        };
+        value.ok_or_else(|| de::Error::invalid_length(1, &self))
+    }
+}
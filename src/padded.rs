@@ -0,0 +1,134 @@
+//! Integers padded to a fixed width.
+
+use crate::DecimalInt;
+use core::fmt::Write;
+use serde::{de, Deserialize, Serialize};
+
+/// An integer padded with `FILL` bytes to at least `WIDTH` bytes, e.g. `42` as `00000042`
+/// with a width of 8.
+///
+/// The width includes the sign. Zeros are written after the sign, like `-0042`,
+/// and other fill bytes before it, like `  -42`. A value wider than `WIDTH` is written
+/// without padding instead of being truncated. `FILL` must be an ASCII character,
+/// or else writing or reading the value fails to compile.
+///
+/// It's written like a string, so a fill byte that needs quoting, e.g. a space delimiter,
+/// gets the field quoted. When read, leading fill bytes are skipped, so it can be read
+/// regardless of the width of a field.
+///
+/// # Example
+/// ```
+/// use serde_csv_core::Padded;
+///
+/// let record = (Padded::<u32, 8>(42), Padded::<i16, 5>(-42), Padded::<i16, 5, b' '>(-42));
+///
+/// let mut csv = [0; 32];
+/// let nwritten = serde_csv_core::Writer::new().serialize(&record, &mut csv)?;
+/// assert_eq!(&csv[..nwritten], b"00000042,-0042,  -42\n");
+///
+/// let mut reader = serde_csv_core::Reader::<16>::new();
+/// let (read, _) = reader.deserialize::<(Padded<u32, 8>, Padded<i16, 5>, Padded<i16, 5, b' '>)>(
+///     &csv[..nwritten],
+/// )?;
+/// assert_eq!(read, record);
+/// # Ok::<(), serde_csv_core::Error>(())
+/// ```
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Padded<T, const WIDTH: usize, const FILL: u8 = b'0'>(pub T);
+
+impl<T, const WIDTH: usize, const FILL: u8> Padded<T, WIDTH, FILL> {
+    // A non-ASCII fill byte would be written as a two-byte character.
+    const ASCII_FILL: () = assert!(FILL.is_ascii(), "`FILL` of `Padded` must be ASCII");
+}
+
+impl<T: DecimalInt, const WIDTH: usize, const FILL: u8> core::fmt::Display
+    for Padded<T, WIDTH, FILL>
+{
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        let () = Self::ASCII_FILL;
+        let (negative, magnitude) = self.0.magnitude();
+        let mut buffer = itoa::Buffer::new();
+        let digits = buffer.format(magnitude);
+        let padding = WIDTH.saturating_sub(digits.len() + usize::from(negative));
+        let fill = char::from(FILL);
+        if negative && FILL == b'0' {
+            f.write_char('-')?;
+        }
+        for _ in 0..padding {
+            f.write_char(fill)?;
+        }
+        if negative && FILL != b'0' {
+            f.write_char('-')?;
+        }
+        f.write_str(digits)
+    }
+}
+
+impl<T: DecimalInt, const WIDTH: usize, const FILL: u8> Serialize for Padded<T, WIDTH, FILL> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de, T: DecimalInt, const WIDTH: usize, const FILL: u8> Deserialize<'de>
+    for Padded<T, WIDTH, FILL>
+{
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: de::Deserializer<'de>,
+    {
+        let () = Self::ASCII_FILL;
+        deserializer.deserialize_bytes(PaddedVisitor(core::marker::PhantomData))
+    }
+}
+
+/// Parses an integer preceded by `fill` bytes.
+fn parse<T: DecimalInt>(v: &[u8], fill: u8) -> Option<T> {
+    // Zeros are parsed as digits, after the sign.
+    let start = match fill {
+        b'0' => 0,
+        _ => v.iter().take_while(|&&b| b == fill).count(),
+    };
+    let (negative, digits) = match &v[start..] {
+        [b'-', digits @ ..] => (true, digits),
+        [b'+', digits @ ..] => (false, digits),
+        digits => (false, digits),
+    };
+    if digits.is_empty() || !digits.iter().all(u8::is_ascii_digit) {
+        return None;
+    }
+    digits.iter().try_fold(T::default(), |value, &b| {
+        value.push_digit(b - b'0', negative)
+    })
+}
+
+struct PaddedVisitor<T, const WIDTH: usize, const FILL: u8>(core::marker::PhantomData<T>);
+
+impl<T: DecimalInt, const WIDTH: usize, const FILL: u8> de::Visitor<'_>
+    for PaddedVisitor<T, WIDTH, FILL>
+{
+    type Value = Padded<T, WIDTH, FILL>;
+
+    fn expecting(&self, formatter: &mut core::fmt::Formatter) -> core::fmt::Result {
+        formatter.write_str("a padded integer")
+    }
+
+    fn visit_bytes<E>(self, v: &[u8]) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        parse(v, FILL)
+            .map(Padded)
+            .ok_or_else(|| E::invalid_value(de::Unexpected::Bytes(v), &self))
+    }
+
+    fn visit_str<E>(self, v: &str) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        self.visit_bytes(v.as_bytes())
+    }
+}
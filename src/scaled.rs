@@ -0,0 +1,130 @@
+//! Decimal numbers stored as scaled integers.
+
+use crate::DecimalInt;
+use serde::{de, Deserialize, Serialize};
+
+/// A decimal number stored as an integer `T` scaled by `10^DECIMALS`,
+/// e.g. `23.45` as `2345` with 2 decimals.
+///
+/// It's written and read as a decimal number using integer arithmetic only,
+/// so it's suitable for targets without a floating-point unit.
+/// A number is written with exactly `DECIMALS` fractional digits, like `-0.001` for `-1`
+/// with 3 decimals, or as a plain integer if `DECIMALS` is zero.
+/// A number is read from an optional sign, integer digits and optionally a `.` followed by
+/// fractional digits, with at least one digit in total. Missing fractional digits are zeros,
+/// and digits beyond `DECIMALS` are truncated toward zero, so `23.459` with 2 decimals is `2345`.
+/// A number that doesn't fit in `T` is an error.
+///
+/// # Example
+/// ```
+/// use serde_csv_core::Scaled;
+///
+/// let csv = b"23.45,-0.05,7\n";
+///
+/// let mut reader = serde_csv_core::Reader::<16>::new();
+/// let (record, _) = reader.deserialize::<[Scaled<i16, 2>; 3]>(csv)?;
+/// assert_eq!(record, [Scaled(2345), Scaled(-5), Scaled(700)]);
+///
+/// let mut buf = [0; 32];
+/// let nwritten = serde_csv_core::Writer::new().serialize(&record, &mut buf)?;
+/// assert_eq!(&buf[..nwritten], b"23.45,-0.05,7.00\n");
+/// # Ok::<(), serde_csv_core::Error>(())
+/// ```
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Scaled<T, const DECIMALS: u8>(pub T);
+
+impl<T: DecimalInt, const DECIMALS: u8> core::fmt::Display for Scaled<T, DECIMALS> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        let (negative, magnitude) = self.0.magnitude();
+        let mut buffer = itoa::Buffer::new();
+        let digits = buffer.format(magnitude);
+        let decimals = usize::from(DECIMALS);
+        if negative {
+            f.write_str("-")?;
+        }
+        if digits.len() > decimals {
+            let (int, frac) = digits.split_at(digits.len() - decimals);
+            f.write_str(int)?;
+            if !frac.is_empty() {
+                f.write_str(".")?;
+            }
+            f.write_str(frac)
+        } else {
+            f.write_str("0.")?;
+            for _ in digits.len()..decimals {
+                f.write_str("0")?;
+            }
+            f.write_str(digits)
+        }
+    }
+}
+
+impl<T: DecimalInt, const DECIMALS: u8> Serialize for Scaled<T, DECIMALS> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de, T: DecimalInt, const DECIMALS: u8> Deserialize<'de> for Scaled<T, DECIMALS> {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: de::Deserializer<'de>,
+    {
+        deserializer.deserialize_bytes(ScaledVisitor(core::marker::PhantomData))
+    }
+}
+
+/// Parses a decimal number into an integer scaled by `10^decimals`.
+fn parse<T: DecimalInt>(v: &[u8], decimals: u8) -> Option<T> {
+    let (negative, rest) = match v {
+        [b'-', rest @ ..] => (true, rest),
+        [b'+', rest @ ..] => (false, rest),
+        _ => (false, v),
+    };
+    let (int, frac) = match rest.iter().position(|&b| b == b'.') {
+        Some(i) => (&rest[..i], &rest[i + 1..]),
+        None => (rest, &[][..]),
+    };
+    if int.is_empty() && frac.is_empty() || !int.iter().chain(frac).all(u8::is_ascii_digit) {
+        return None;
+    }
+    let padding = core::iter::repeat(&b'0');
+    int.iter()
+        .chain(frac.iter().chain(padding).take(usize::from(decimals)))
+        .try_fold(T::default(), |value, &b| {
+            value.push_digit(b - b'0', negative)
+        })
+}
+
+struct ScaledVisitor<T, const DECIMALS: u8>(core::marker::PhantomData<T>);
+
+impl<T: DecimalInt, const DECIMALS: u8> de::Visitor<'_> for ScaledVisitor<T, DECIMALS> {
+    type Value = Scaled<T, DECIMALS>;
+
+    fn expecting(&self, formatter: &mut core::fmt::Formatter) -> core::fmt::Result {
+        write!(
+            formatter,
+            "a decimal number with up to {} fractional digits",
+            DECIMALS
+        )
+    }
+
+    fn visit_bytes<E>(self, v: &[u8]) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        parse(v, DECIMALS)
+            .map(Scaled)
+            .ok_or_else(|| E::invalid_value(de::Unexpected::Bytes(v), &self))
+    }
+
+    fn visit_str<E>(self, v: &str) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        self.visit_bytes(v.as_bytes())
+    }
+}
@@ -1,14 +1,128 @@
 //! Serialize a Rust data structure into CSV data.
 
+use crate::{Checksum, Encoding};
 use core::borrow::Borrow;
 #[cfg(feature = "heapless")]
 use heapless::Vec;
 use serde::{ser, Serialize};
 
+mod builder;
+mod encoder;
+mod keys;
+
+pub use builder::WriterBuilder;
+pub use encoder::FieldEncoder;
+#[cfg(feature = "percent-encoding")]
+pub use encoder::PercentEncoder;
+use keys::KeyCursor;
+pub use keys::Keys;
+
 /// Wrapper for [`csv_core::Writer`] that provides methods for serialization using [`serde`].
+///
+/// Use [`WriterBuilder`] to configure it.
+///
+/// A writer is `Send` and `Sync`, so it can be moved to another thread or task,
+/// or shared between them, e.g. in a `static` guarded by a mutex.
+/// All the state it holds is owned, apart from the `'static` configuration,
+/// like a [`FieldEncoder`], which is required to be `Sync`.
 #[derive(Debug)]
 pub struct Writer {
     inner: csv_core::Writer,
+    config: Config,
+    partial: Partial,
+    expected_fields: Option<usize>,
+    sequence: u32,
+    pending_terminator: bool,
+}
+
+/// Progress of a record written with [`Writer::serialize_value`].
+#[derive(Debug, Default, Clone, Copy)]
+struct Partial {
+    started: bool,
+    inner_wrote: bool,
+    manual_wrote: bool,
+    nfields: usize,
+    crc: Option<u32>,
+}
+
+/// Serde-level configuration shared by [`Writer`] and [`Serializer`].
+#[derive(Debug, Clone, Copy)]
+struct Config {
+    encoding: Encoding,
+    escape_control_chars: bool,
+    sanitize_formulas: bool,
+    formula_guard: u8,
+    quote_policy: QuotePolicy,
+    bytes_mode: BytesMode,
+    field_encoder: Option<&'static dyn FieldEncoder>,
+    check_field_count: bool,
+    null_literal: &'static [u8],
+    checksum: Option<Checksum>,
+    record_prefix: Option<&'static [u8]>,
+    sequence_start: Option<u32>,
+    sequence_name: &'static str,
+    defer_terminator: bool,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            encoding: Encoding::Utf8,
+            escape_control_chars: false,
+            sanitize_formulas: false,
+            formula_guard: b'\'',
+            quote_policy: QuotePolicy::Minimal,
+            bytes_mode: BytesMode::SingleField,
+            field_encoder: None,
+            check_field_count: false,
+            null_literal: b"",
+            checksum: None,
+            record_prefix: None,
+            sequence_start: None,
+            sequence_name: "seq",
+            defer_terminator: false,
+        }
+    }
+}
+
+/// Quoting of string fields, set by [`Writer::quote_policy`].
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum QuotePolicy {
+    /// Strings are quoted only if they contain a delimiter, a quote or a terminator.
+    #[default]
+    Minimal,
+    /// Strings, chars and byte slices are always quoted.
+    AlwaysStrings,
+    /// Strings are never quoted. [`Error::QuotingRequired`] is returned for a string
+    /// that would have to be quoted.
+    Never,
+}
+
+/// Representation of byte slices, set by [`Writer::bytes_mode`].
+///
+/// Applies only to values serialized with `serialize_bytes`, like `serde_bytes::Bytes`.
+/// A plain `&[u8]` or `[u8; N]` is serialized by `serde` as a sequence,
+/// so every byte is always written as a separate numeric field.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum BytesMode {
+    /// Bytes are written as they are, in a single field.
+    #[default]
+    SingleField,
+    /// Every byte is written as a separate numeric field.
+    PerByte,
+    /// Bytes are written in a single field as uppercase hexadecimal digits.
+    Hex,
+    /// Bytes are written in a single field in padded Base64 with the standard alphabet.
+    Base64,
+}
+
+/// Outcome of [`Writer::serialize_status`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SerializeStatus {
+    /// Number of bytes written.
+    pub bytes_written: usize,
+    /// Whether the record filled the whole output buffer.
+    pub filled_exactly: bool,
 }
 
 impl Default for Writer {
@@ -18,6 +132,9 @@ impl Default for Writer {
 }
 
 impl Writer {
+    /// Capacity of the buffer that [`Writer::write_fmt_field`] formats its text into.
+    pub const FMT_CAPACITY: usize = 64;
+
     /// Constructs a new writer.
     pub fn new() -> Self {
         Self::default()
@@ -34,58 +151,1087 @@ impl Writer {
     ///         .delimiter(b'-')
     /// );
     /// ```
-    pub fn from_builder(builder: impl Borrow<csv_core::WriterBuilder>) -> Self {
-        Self {
-            inner: builder.borrow().build(),
+    pub fn from_builder(builder: impl Borrow<csv_core::WriterBuilder>) -> Self {
+        Self {
+            inner: builder.borrow().build(),
+            config: Config::default(),
+            partial: Partial::default(),
+            expected_fields: None,
+            sequence: 0,
+            pending_terminator: false,
+        }
+    }
+
+    /// Sets the character encoding of text fields.
+    ///
+    /// Strings and chars are transcoded from UTF-8 while being written.
+    /// [`Error::Unrepresentable`] is returned for a character that the encoding can't represent.
+    /// Other fields, like numbers and booleans, are ASCII and not affected,
+    /// and neither are byte slices.
+    ///
+    /// Defaults to [`Encoding::Utf8`].
+    ///
+    /// # Example
+    /// ```
+    /// use serde_csv_core::{ser::Error, Encoding};
+    ///
+    /// let mut writer = serde_csv_core::Writer::new();
+    /// writer.encoding(Encoding::Latin1);
+    ///
+    /// let mut csv = [0; 16];
+    /// let nwritten = writer.serialize(&("café", 1), &mut csv)?;
+    /// assert_eq!(&csv[..nwritten], b"caf\xe9,1\n");
+    ///
+    /// let result = writer.serialize("€", &mut csv);
+    /// assert_eq!(result, Err(Error::Unrepresentable('€')));
+    /// # Ok::<(), serde_csv_core::ser::Error>(())
+    /// ```
+    pub fn encoding(&mut self, encoding: Encoding) -> &mut Self {
+        self.config.encoding = encoding;
+        self
+    }
+
+    /// Enables escaping of control characters in string fields.
+    ///
+    /// When enabled, C0 control characters (`0x00..=0x1F`) and DEL (`0x7F`) found in
+    /// strings, chars and byte slices are written as `\xNN`, where `NN` is the uppercase
+    /// hexadecimal value of the byte. This includes tabs and line breaks.
+    /// Delimiters and record terminators emitted by the writer itself are not affected.
+    ///
+    /// Disabled by default.
+    ///
+    /// # Example
+    /// ```
+    /// let mut writer = serde_csv_core::Writer::new();
+    /// writer.escape_control_chars(true);
+    ///
+    /// let mut csv = [0; 32];
+    /// let nwritten = writer.serialize("\x1b[31mred", &mut csv)?;
+    ///
+    /// assert_eq!(&csv[..nwritten], b"\\x1B[31mred\n");
+    /// # Ok::<(), serde_csv_core::ser::Error>(())
+    /// ```
+    pub fn escape_control_chars(&mut self, yes: bool) -> &mut Self {
+        self.config.escape_control_chars = yes;
+        self
+    }
+
+    /// Enables protection against CSV injection.
+    ///
+    /// When enabled, strings, chars and byte slices starting with a character that
+    /// spreadsheet applications interpret as the beginning of a formula
+    /// (`=`, `+`, `-`, `@`, tab or carriage return) are prefixed with a guard byte,
+    /// see [`Writer::formula_guard`]. Fields that start with that character preceded by guard
+    /// bytes are prefixed as well, so that [`Reader::unsanitize_formulas`](crate::Reader::unsanitize_formulas)
+    /// can restore every field exactly. Numeric fields are never prefixed.
+    ///
+    /// Disabled by default.
+    ///
+    /// # Example
+    /// ```
+    /// let mut writer = serde_csv_core::Writer::new();
+    /// writer.sanitize_formulas(true);
+    ///
+    /// let mut csv = [0; 32];
+    /// let nwritten = writer.serialize(&("=1+2", -3), &mut csv)?;
+    ///
+    /// assert_eq!(&csv[..nwritten], b"'=1+2,-3\n");
+    /// # Ok::<(), serde_csv_core::ser::Error>(())
+    /// ```
+    pub fn sanitize_formulas(&mut self, yes: bool) -> &mut Self {
+        self.config.sanitize_formulas = yes;
+        self
+    }
+
+    /// Sets the byte used by [`Writer::sanitize_formulas`] to guard potential formulas.
+    ///
+    /// The guard must not be one of the formula trigger characters.
+    /// Defaults to `'`.
+    pub fn formula_guard(&mut self, guard: u8) -> &mut Self {
+        self.config.formula_guard = guard;
+        self
+    }
+
+    /// Sets the literal written for `None`.
+    ///
+    /// `None` restores the default, where `None` is written as an empty field.
+    /// Use the same literal with [`Reader::null_literal`](crate::Reader::null_literal)
+    /// to tell `None` and empty strings apart.
+    ///
+    /// # Example
+    /// ```
+    /// let mut writer = serde_csv_core::Writer::new();
+    /// writer.null_literal(Some(b"\\N"));
+    /// let mut csv = [0; 16];
+    ///
+    /// let nwritten = writer.serialize(&(None::<&str>, Some("")), &mut csv)?;
+    ///
+    /// assert_eq!(&csv[..nwritten], b"\\N,\n");
+    /// # Ok::<(), serde_csv_core::ser::Error>(())
+    /// ```
+    pub fn null_literal(&mut self, literal: Option<&'static [u8]>) -> &mut Self {
+        self.config.null_literal = literal.unwrap_or_default();
+        self
+    }
+
+    /// Sets the quoting of string fields.
+    ///
+    /// Applies to strings, chars, byte slices and field names written by
+    /// [`Writer::serialize_header`]. Other fields, like numbers, are quoted only if necessary.
+    ///
+    /// Defaults to [`QuotePolicy::Minimal`].
+    ///
+    /// # Example
+    /// ```
+    /// use serde_csv_core::ser::QuotePolicy;
+    ///
+    /// let mut writer = serde_csv_core::Writer::new();
+    /// writer.quote_policy(QuotePolicy::AlwaysStrings);
+    ///
+    /// let mut csv = [0; 32];
+    /// let nwritten = writer.serialize(&("Cracow", 766_683), &mut csv)?;
+    ///
+    /// assert_eq!(&csv[..nwritten], b"\"Cracow\",766683\n");
+    /// # Ok::<(), serde_csv_core::ser::Error>(())
+    /// ```
+    pub fn quote_policy(&mut self, policy: QuotePolicy) -> &mut Self {
+        self.config.quote_policy = policy;
+        self
+    }
+
+    /// Sets the representation of byte slices.
+    ///
+    /// Defaults to [`BytesMode::SingleField`].
+    ///
+    /// # Example
+    /// ```
+    /// use serde_csv_core::ser::BytesMode;
+    ///
+    /// struct Bytes<'a>(&'a [u8]);
+    ///
+    /// impl serde::Serialize for Bytes<'_> {
+    ///     fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+    ///         serializer.serialize_bytes(self.0)
+    ///     }
+    /// }
+    ///
+    /// let mut writer = serde_csv_core::Writer::new();
+    /// writer.bytes_mode(BytesMode::Hex);
+    ///
+    /// let mut csv = [0; 32];
+    /// let nwritten = writer.serialize(&(1, Bytes(&[0xca, 0xfe])), &mut csv)?;
+    ///
+    /// assert_eq!(&csv[..nwritten], b"1,CAFE\n");
+    /// # Ok::<(), serde_csv_core::ser::Error>(())
+    /// ```
+    pub fn bytes_mode(&mut self, mode: BytesMode) -> &mut Self {
+        self.config.bytes_mode = mode;
+        self
+    }
+
+    /// Sets the encoder that transforms the contents of every field before it's quoted.
+    ///
+    /// The encoder applies to fields of all types, including field names
+    /// written by [`Writer::serialize_header`], but not to the fields
+    /// added by the writer itself, like [`Writer::record_prefix`] or [`Writer::checksum`].
+    /// A `PercentEncoder` is available with the `percent-encoding` feature.
+    ///
+    /// Fields are written as they are by default.
+    ///
+    /// # Example
+    /// ```
+    /// use serde_csv_core::ser::{FieldEncoder, Result};
+    ///
+    /// #[derive(Debug)]
+    /// struct Ampersand;
+    ///
+    /// impl FieldEncoder for Ampersand {
+    ///     fn encode(&self, input: &[u8], f: &mut dyn FnMut(&[u8]) -> Result<()>) -> Result<()> {
+    ///         for (i, part) in input.split(|&b| b == b'&').enumerate() {
+    ///             if i > 0 {
+    ///                 f(b"&amp;")?;
+    ///             }
+    ///             f(part)?;
+    ///         }
+    ///         Ok(())
+    ///     }
+    /// }
+    ///
+    /// let mut writer = serde_csv_core::Writer::new();
+    /// writer.field_encoder(Some(&Ampersand));
+    ///
+    /// let mut csv = [0; 32];
+    /// let nwritten = writer.serialize(&("a&b", "c,&"), &mut csv)?;
+    ///
+    /// assert_eq!(&csv[..nwritten], b"a&amp;b,\"c,&amp;\"\n");
+    /// # Ok::<(), serde_csv_core::ser::Error>(())
+    /// ```
+    pub fn field_encoder(&mut self, encoder: Option<&'static dyn FieldEncoder>) -> &mut Self {
+        self.config.field_encoder = encoder;
+        self
+    }
+
+    /// Enables checking that every record has the same number of fields.
+    ///
+    /// The number of fields of the first record written after enabling the check,
+    /// or after [`Writer::reset`], is remembered. [`Error::InconsistentFieldCount`] is returned
+    /// for a later record with a different number of fields, and the record isn't written.
+    /// Fields of compound types are counted one by one, as they are flattened.
+    ///
+    /// Disabled by default.
+    ///
+    /// # Example
+    /// ```
+    /// use serde_csv_core::ser::Error;
+    ///
+    /// let mut writer = serde_csv_core::Writer::new();
+    /// writer.check_field_count(true);
+    ///
+    /// let mut csv = [0; 32];
+    /// let nwritten = writer.serialize(&(1, (2, 3)), &mut csv)?;
+    /// let result = writer.serialize(&(1, 2), &mut csv[nwritten..]);
+    ///
+    /// assert_eq!(result, Err(Error::InconsistentFieldCount { expected: 3, got: 2 }));
+    /// # Ok::<(), serde_csv_core::ser::Error>(())
+    /// ```
+    pub fn check_field_count(&mut self, yes: bool) -> &mut Self {
+        self.config.check_field_count = yes;
+        self
+    }
+
+    /// Sets the checksum appended to every record as its last field.
+    ///
+    /// The checksum covers the bytes of the record as they are written, including quotes,
+    /// but excluding the delimiter before the checksum field and the record terminator.
+    /// It applies to every record written, including headers, except for values written
+    /// by [`Writer::serialize_field_only`]. [`Error::Overflow`] is returned if the checksum
+    /// doesn't fit in the output, like for any other field.
+    ///
+    /// Disabled by default.
+    ///
+    /// # Example
+    /// ```
+    /// use serde_csv_core::Checksum;
+    ///
+    /// let mut writer = serde_csv_core::Writer::new();
+    /// writer.checksum(Some(Checksum::Crc16Ccitt));
+    ///
+    /// let mut csv = [0; 32];
+    /// let nwritten = writer.serialize(&("123456789",), &mut csv)?;
+    ///
+    /// assert_eq!(&csv[..nwritten], b"123456789,29B1\n");
+    /// # Ok::<(), serde_csv_core::ser::Error>(())
+    /// ```
+    pub fn checksum(&mut self, checksum: Option<Checksum>) -> &mut Self {
+        self.config.checksum = checksum;
+        self
+    }
+
+    /// Sets a field written at the start of every record, before the serialized value,
+    /// e.g. a tag of the source of the record.
+    ///
+    /// The prefix is quoted like any other field if necessary and followed by a delimiter,
+    /// unless the record has no other fields. It counts as a field of the record
+    /// for [`Writer::check_field_count`] and it's covered by [`Writer::checksum`].
+    /// It applies to every record written, including headers, except for values written
+    /// by [`Writer::serialize_field_only`].
+    ///
+    /// `None` restores the default, where records have no prefix.
+    ///
+    /// # Example
+    /// ```
+    /// let mut writer = serde_csv_core::Writer::new();
+    /// writer.record_prefix(Some(b"GPS"));
+    ///
+    /// let mut csv = [0; 32];
+    /// let nwritten = writer.serialize_rows(&[(50.06, 19.94), (52.23, 21.01)], &mut csv)?;
+    ///
+    /// assert_eq!(&csv[..nwritten], b"GPS,50.06,19.94\nGPS,52.23,21.01\n");
+    /// # Ok::<(), serde_csv_core::ser::Error>(())
+    /// ```
+    pub fn record_prefix(&mut self, prefix: Option<&'static [u8]>) -> &mut Self {
+        self.config.record_prefix = prefix;
+        self
+    }
+
+    /// Enables a column of sequence numbers, starting from `start`, to detect dropped records.
+    ///
+    /// Every record begins with its sequence number, which is incremented after each record.
+    /// A call that returns an error doesn't advance the sequence, so a record can be retried
+    /// with the same number. [`Writer::reset`] restarts the sequence.
+    /// After `u32::MAX`, the sequence wraps around to 0.
+    /// The number follows the field set by [`Writer::record_prefix`], if any.
+    /// Header records contain the column name set by [`Writer::sequence_column_name`] instead
+    /// and don't advance the sequence.
+    ///
+    /// `None` restores the default, where records have no sequence number.
+    ///
+    /// # Example
+    /// ```
+    /// let mut writer = serde_csv_core::Writer::new();
+    /// writer.sequence_column(Some(0));
+    ///
+    /// let mut csv = [0; 32];
+    /// let mut nwritten = writer.serialize(&("a", 1), &mut csv)?;
+    /// nwritten += writer.serialize(&("b", 2), &mut csv[nwritten..])?;
+    ///
+    /// assert_eq!(&csv[..nwritten], b"0,a,1\n1,b,2\n");
+    /// # Ok::<(), serde_csv_core::ser::Error>(())
+    /// ```
+    pub fn sequence_column(&mut self, start: Option<u32>) -> &mut Self {
+        self.config.sequence_start = start;
+        self.sequence = start.unwrap_or_default();
+        self
+    }
+
+    /// Sets the name of the column enabled by [`Writer::sequence_column`] in header records.
+    ///
+    /// Defaults to `seq`.
+    pub fn sequence_column_name(&mut self, name: &'static str) -> &mut Self {
+        self.config.sequence_name = name;
+        self
+    }
+
+    /// Sets whether the record terminator is written at the start of the next record
+    /// instead of the end of its own, so that the last record isn't terminated.
+    ///
+    /// The terminator of the last record is left out until [`Writer::reset`] is called
+    /// to start a new file.
+    ///
+    /// Disabled by default.
+    ///
+    /// # Example
+    /// ```
+    /// let mut writer = serde_csv_core::Writer::new();
+    /// writer.defer_terminator(true);
+    ///
+    /// let mut csv = [0; 16];
+    /// let mut nwritten = writer.serialize(&("a", "b"), &mut csv)?;
+    /// nwritten += writer.serialize(&("c", "d"), &mut csv[nwritten..])?;
+    ///
+    /// assert_eq!(&csv[..nwritten], b"a,b\nc,d");
+    /// # Ok::<(), serde_csv_core::ser::Error>(())
+    /// ```
+    pub fn defer_terminator(&mut self, yes: bool) -> &mut Self {
+        self.config.defer_terminator = yes;
+        self
+    }
+
+    /// Forgets the number of fields remembered by [`Writer::check_field_count`],
+    /// restarts the sequence of [`Writer::sequence_column`] and drops the terminator deferred
+    /// by [`Writer::defer_terminator`], e.g. before writing a new file.
+    pub fn reset(&mut self) {
+        self.expected_fields = None;
+        self.sequence = self.config.sequence_start.unwrap_or_default();
+        self.pending_terminator = false;
+    }
+
+    /// Serializes the given value as a CSV byte slice.
+    ///
+    /// Inserts record terminator after the serialized value.
+    /// Flattens compound types (e.g. nested structs, tuples, vectors).
+    /// On success, it returns the number of bytes written.
+    ///
+    /// If an error occurs, the writer is restored to its state from before the call,
+    /// including the number of fields checked by [`Writer::check_field_count`],
+    /// the sequence number of [`Writer::sequence_column`] and the terminator deferred by
+    /// [`Writer::defer_terminator`], so the same record can be written again to a larger
+    /// or emptier buffer. Bytes already written to `output` aren't part of any record and can be
+    /// overwritten. Restoring the state costs a copy of the inner [`csv_core::Writer`],
+    /// a few dozen bytes, on every call; all other methods writing records behave the same way.
+    ///
+    /// `None` is written as an empty field, or as the null literal if one is set.
+    /// Nested options are flattened as well, so `Some(None)` and `None` of an
+    /// `Option<Option<T>>` are both written as `None` and read back as `None`.
+    ///
+    /// Maps, including structs with `#[serde(flatten)]` fields, are flattened as well:
+    /// their keys are dropped and only their values become fields.
+    /// [`Writer::serialize_header`] writes the keys instead.
+    ///
+    /// # Example
+    /// ```
+    /// use heapless::String;
+    ///
+    /// #[derive(serde::Serialize)]
+    /// struct Record {
+    ///     pub country: String<32>,
+    ///     pub city: String<32>,
+    ///     pub population: u32,
+    /// }
+    ///
+    /// let record = Record {
+    ///     country: "Poland".into(),
+    ///     city: "Cracow".into(),
+    ///     population: 766_683,
+    /// };
+    ///
+    /// let mut writer = serde_csv_core::Writer::new();
+    /// let mut csv = [0; 32];
+    /// let nwritten = writer.serialize(&record, &mut csv)?;
+    ///
+    /// assert_eq!(&csv[..nwritten], b"Poland,Cracow,766683\n");
+    /// # Ok::<(), serde_csv_core::ser::Error>(())
+    /// ```
+    pub fn serialize<T>(&mut self, value: &T, output: &mut [u8]) -> Result<usize>
+    where
+        T: Serialize + ?Sized,
+    {
+        let nwritten = self.write_with(output, |serializer| {
+            value.serialize(&mut *serializer)?;
+            serializer.terminator()
+        })?;
+        self.partial = Partial::default();
+        Ok(nwritten)
+    }
+
+    /// Serializes the given value as a CSV record followed by a field with its `checksum`.
+    ///
+    /// Equivalent to [`Writer::serialize`] with [`Writer::checksum`] set for this record only.
+    ///
+    /// # Example
+    /// ```
+    /// use serde_csv_core::Checksum;
+    ///
+    /// let mut writer = serde_csv_core::Writer::new();
+    ///
+    /// let mut csv = [0; 32];
+    /// let nwritten = writer.serialize_with_crc(&("123456789",), Checksum::Crc8, &mut csv)?;
+    ///
+    /// assert_eq!(&csv[..nwritten], b"123456789,F4\n");
+    /// # Ok::<(), serde_csv_core::ser::Error>(())
+    /// ```
+    pub fn serialize_with_crc<T>(
+        &mut self,
+        value: &T,
+        checksum: Checksum,
+        output: &mut [u8],
+    ) -> Result<usize>
+    where
+        T: Serialize + ?Sized,
+    {
+        let config = self.config;
+        self.config.checksum = Some(checksum);
+        let result = self.serialize(value, output);
+        self.config = config;
+        result
+    }
+
+    /// Serializes the given value as a CSV record, like [`Writer::serialize`],
+    /// and reports whether the record filled `output` exactly.
+    ///
+    /// A record that doesn't fit returns [`Error::Overflow`], like with [`Writer::serialize`].
+    ///
+    /// # Example
+    /// ```
+    /// let mut writer = serde_csv_core::Writer::new();
+    /// let mut csv = [0; 8];
+    ///
+    /// let status = writer.serialize_status(&("abc", 12), &mut csv)?;
+    ///
+    /// assert_eq!(status.bytes_written, 7);
+    /// assert!(!status.filled_exactly);
+    ///
+    /// let status = writer.serialize_status(&("abc", 123), &mut csv)?;
+    ///
+    /// assert_eq!(status.bytes_written, 8);
+    /// assert!(status.filled_exactly);
+    /// # Ok::<(), serde_csv_core::ser::Error>(())
+    /// ```
+    pub fn serialize_status<T>(&mut self, value: &T, output: &mut [u8]) -> Result<SerializeStatus>
+    where
+        T: Serialize + ?Sized,
+    {
+        let bytes_written = self.serialize(value, output)?;
+        Ok(SerializeStatus {
+            bytes_written,
+            filled_exactly: bytes_written == output.len(),
+        })
+    }
+
+    /// Serializes the given value as a CSV record written across a sequence of buffers.
+    ///
+    /// Works like [`Writer::serialize`], except that the record continues in the next buffer
+    /// when the current one is full, even in the middle of a field.
+    /// [`Error::Overflow`] is returned only if all buffers are full.
+    /// On success, it returns the total number of bytes written; the buffers are filled
+    /// in order, so only the last one written to may be filled partially.
+    ///
+    /// # Example
+    /// ```
+    /// let mut writer = serde_csv_core::Writer::new();
+    /// let mut first = [0; 8];
+    /// let mut second = [0; 16];
+    /// let nwritten = writer.serialize_to_slices(
+    ///     &("Poland", "Cracow", 766_683),
+    ///     &mut [&mut first, &mut second],
+    /// )?;
+    ///
+    /// assert_eq!(nwritten, 21);
+    /// assert_eq!(&first, b"Poland,C");
+    /// assert_eq!(&second[..13], b"racow,766683\n");
+    /// # Ok::<(), serde_csv_core::ser::Error>(())
+    /// ```
+    pub fn serialize_to_slices<T>(&mut self, value: &T, parts: &mut [&mut [u8]]) -> Result<usize>
+    where
+        T: Serialize + ?Sized,
+    {
+        let mut parts = parts;
+        let nwritten = self.write_to(Output::Parts(&mut parts), |serializer| {
+            value.serialize(&mut *serializer)?;
+            serializer.terminator()
+        })?;
+        self.partial = Partial::default();
+        Ok(nwritten)
+    }
+
+    /// Serializes the given value as CSV records, one per element of a top-level sequence.
+    ///
+    /// Works like [`Writer::serialize`], except that a sequence, a tuple or an array is written
+    /// with a record terminator after each element instead of a delimiter between them.
+    /// Sequences nested in the elements are flattened as usual.
+    /// A value that isn't a sequence is written as a single record, and an empty sequence
+    /// writes nothing. On success, it returns the number of bytes written.
+    ///
+    /// If an error occurs, all records written by the call should be discarded.
+    ///
+    /// # Example
+    /// ```
+    /// #[derive(serde::Serialize)]
+    /// struct Record {
+    ///     pub city: &'static str,
+    ///     pub population: u32,
+    /// }
+    ///
+    /// let records = [
+    ///     Record { city: "Cracow", population: 766_683 },
+    ///     Record { city: "Warsaw", population: 1_860_281 },
+    /// ];
+    ///
+    /// let mut writer = serde_csv_core::Writer::new();
+    /// let mut csv = [0; 64];
+    /// let nwritten = writer.serialize_rows(&records, &mut csv)?;
+    ///
+    /// assert_eq!(&csv[..nwritten], b"Cracow,766683\nWarsaw,1860281\n");
+    /// # Ok::<(), serde_csv_core::ser::Error>(())
+    /// ```
+    pub fn serialize_rows<T>(&mut self, value: &T, output: &mut [u8]) -> Result<usize>
+    where
+        T: Serialize + ?Sized,
+    {
+        let nwritten = self.write_with(output, |serializer| {
+            serializer.rows = true;
+            value.serialize(&mut *serializer)?;
+            serializer.rows = false;
+            // The value wasn't a sequence.
+            if serializer.started {
+                serializer.terminator()?;
+            }
+            Ok(())
+        })?;
+        self.partial = Partial::default();
+        Ok(nwritten)
+    }
+
+    /// Serializes the given value as fields of a record that isn't terminated yet.
+    ///
+    /// Flattens compound types (e.g. nested structs, tuples, vectors).
+    /// A delimiter is inserted before the value unless it's the first one in the record.
+    /// The record is finished by [`Writer::terminate`] or by a call to [`Writer::serialize`],
+    /// which appends its value to the record.
+    /// On success, it returns the number of bytes written.
+    ///
+    /// # Example
+    /// ```
+    /// let mut writer = serde_csv_core::Writer::new();
+    /// let mut csv = [0; 32];
+    /// let mut nwritten = writer.serialize_value(&"Poland", &mut csv)?;
+    /// nwritten += writer.serialize_value(&("Cracow", 766_683), &mut csv[nwritten..])?;
+    /// nwritten += writer.terminate(&mut csv[nwritten..])?;
+    ///
+    /// assert_eq!(&csv[..nwritten], b"Poland,Cracow,766683\n");
+    /// # Ok::<(), serde_csv_core::ser::Error>(())
+    /// ```
+    pub fn serialize_value<T>(&mut self, value: &T, output: &mut [u8]) -> Result<usize>
+    where
+        T: Serialize + ?Sized,
+    {
+        let mut partial = self.partial;
+        let nwritten = self.write_with(output, |serializer| {
+            value.serialize(&mut *serializer)?;
+            serializer.update_checksum();
+            partial = Partial {
+                started: serializer.started,
+                inner_wrote: serializer.inner_wrote,
+                manual_wrote: serializer.manual_wrote,
+                nfields: serializer.nfields,
+                crc: serializer.crc,
+            };
+            Ok(())
+        })?;
+        self.partial = partial;
+        Ok(nwritten)
+    }
+
+    /// Writes formatted text as a single string field of a record that isn't terminated yet.
+    ///
+    /// The text is formatted once into a buffer on the stack of [`Writer::FMT_CAPACITY`] bytes,
+    /// then quoted and escaped like a string. [`Error::FmtOverflow`] is returned
+    /// if it doesn't fit, and nothing is written.
+    /// Otherwise it behaves like [`Writer::serialize_value`].
+    /// On success, it returns the number of bytes written.
+    ///
+    /// # Example
+    /// ```
+    /// let mut writer = serde_csv_core::Writer::new();
+    /// let mut csv = [0; 32];
+    /// let mut nwritten = writer.serialize_value(&"T1", &mut csv)?;
+    /// nwritten += writer.write_fmt_field(format_args!("{:.2}", 21.456), &mut csv[nwritten..])?;
+    /// nwritten += writer.write_fmt_field(format_args!("{},{}", 1, 2), &mut csv[nwritten..])?;
+    /// nwritten += writer.terminate(&mut csv[nwritten..])?;
+    ///
+    /// assert_eq!(&csv[..nwritten], b"T1,21.46,\"1,2\"\n");
+    /// # Ok::<(), serde_csv_core::ser::Error>(())
+    /// ```
+    pub fn write_fmt_field(
+        &mut self,
+        args: core::fmt::Arguments<'_>,
+        output: &mut [u8],
+    ) -> Result<usize> {
+        let mut buffer = FmtBuffer {
+            bytes: [0; Self::FMT_CAPACITY],
+            len: 0,
+        };
+        core::fmt::write(&mut buffer, args).map_err(|_| Error::FmtOverflow)?;
+        let text = core::str::from_utf8(&buffer.bytes[..buffer.len]).map_err(|_| Error::Custom)?;
+        self.serialize_value(text, output)
+    }
+
+    /// Serializes the given value like [`Writer::serialize`], but without the record terminator.
+    ///
+    /// It's meant for scalars that are embedded in another format as a single field.
+    /// Compound types are still flattened into multiple fields separated by delimiters.
+    /// The record is finished anyway, so the next value starts a new record.
+    /// The value isn't a data record, so it isn't checked by [`Writer::check_field_count`].
+    /// On success, it returns the number of bytes written.
+    ///
+    /// # Example
+    /// ```
+    /// let mut writer = serde_csv_core::Writer::new();
+    /// let mut csv = [0; 32];
+    ///
+    /// let nwritten = writer.serialize_field_only(&"Cracow, Poland", &mut csv)?;
+    ///
+    /// assert_eq!(&csv[..nwritten], b"\"Cracow, Poland\"");
+    /// # Ok::<(), serde_csv_core::ser::Error>(())
+    /// ```
+    pub fn serialize_field_only<T>(&mut self, value: &T, output: &mut [u8]) -> Result<usize>
+    where
+        T: Serialize + ?Sized,
+    {
+        let snapshot = (self.inner.clone(), self.partial);
+        // The value isn't a record, so it's written without a checksum or leading fields,
+        // and its number of fields isn't remembered by the scratch terminator.
+        let config = self.config;
+        self.config.checksum = None;
+        self.config.record_prefix = None;
+        self.config.sequence_start = None;
+        self.config.defer_terminator = false;
+        self.config.check_field_count = false;
+        let result = self.serialize_value(value, output).and_then(|nwritten| {
+            // Writes the closing quote of the last field.
+            let (r, n) = self.inner.finish(&mut output[nwritten..]);
+            if r == csv_core::WriteResult::OutputFull {
+                return Err(Error::Overflow);
+            }
+            // csv-core can't be reset to the start of a record,
+            // so the terminator is written to a scratch buffer instead.
+            self.terminate(&mut [0; 4])?;
+            Ok(nwritten + n)
+        });
+        self.config = config;
+        if result.is_err() {
+            (self.inner, self.partial) = snapshot;
+        }
+        result
+    }
+
+    /// Writes a record with a single field containing the number of records that follow,
+    /// to be read by [`Reader::read_count`](crate::Reader::read_count).
+    ///
+    /// The count isn't a data record, so it's written without the fields set by
+    /// [`Writer::record_prefix`] and [`Writer::sequence_column`], and it isn't checked
+    /// by [`Writer::check_field_count`].
+    /// On success, it returns the number of bytes written.
+    ///
+    /// # Example
+    /// ```
+    /// let mut writer = serde_csv_core::Writer::new();
+    /// let mut csv = [0; 32];
+    ///
+    /// let mut nwritten = writer.write_count(2, &mut csv)?;
+    /// nwritten += writer.serialize(&("a", 1), &mut csv[nwritten..])?;
+    /// nwritten += writer.serialize(&("b", 2), &mut csv[nwritten..])?;
+    ///
+    /// assert_eq!(&csv[..nwritten], b"2\na,1\nb,2\n");
+    /// # Ok::<(), serde_csv_core::ser::Error>(())
+    /// ```
+    pub fn write_count(&mut self, count: u32, output: &mut [u8]) -> Result<usize> {
+        let config = self.config;
+        self.config.record_prefix = None;
+        self.config.sequence_start = None;
+        self.config.check_field_count = false;
+        let result = self.serialize(&count, output);
+        self.config = config;
+        result
+    }
+
+    /// Finishes a record started by [`Writer::serialize_value`] by writing the record terminator.
+    ///
+    /// On success, it returns the number of bytes written.
+    pub fn terminate(&mut self, output: &mut [u8]) -> Result<usize> {
+        let nwritten = self.write_with(output, |serializer| serializer.terminator())?;
+        self.partial = Partial::default();
+        Ok(nwritten)
+    }
+
+    /// Runs `f` on a serializer writing to `output`, restoring the writer's state on error.
+    fn write_with<F>(&mut self, output: &mut [u8], f: F) -> Result<usize>
+    where
+        F: FnOnce(&mut Serializer) -> Result<()>,
+    {
+        self.write_to(Output::Slice(output), f)
+    }
+
+    /// Runs `f` on a serializer writing to `output`, restoring the writer's state on error.
+    fn write_to<F>(&mut self, output: Output<'_>, f: F) -> Result<usize>
+    where
+        F: FnOnce(&mut Serializer) -> Result<()>,
+    {
+        self.write_keyed(output, None, f)
+    }
+
+    /// Runs `f` like [`Writer::write_to`], passing the keys of maps to `keys`.
+    fn write_keyed<F>(
+        &mut self,
+        mut output: Output<'_>,
+        keys: Option<KeyCursor<'_>>,
+        f: F,
+    ) -> Result<usize>
+    where
+        F: FnOnce(&mut Serializer) -> Result<()>,
+    {
+        // Restored on error, so that the writer isn't left in the middle of a field.
+        let snapshot = self.inner.clone();
+        let mut serializer =
+            Serializer::with_config(&mut self.inner, self.config, output.reborrow());
+        serializer.keys = keys;
+        serializer.started = self.partial.started;
+        serializer.inner_wrote = self.partial.inner_wrote;
+        serializer.manual_wrote = self.partial.manual_wrote;
+        serializer.nfields = self.partial.nfields;
+        serializer.crc = self.partial.crc;
+        serializer.expected_fields = self.expected_fields;
+        serializer.sequence = self.sequence;
+        serializer.pending_terminator = self.pending_terminator;
+        match f(&mut serializer) {
+            Ok(()) => {
+                self.expected_fields = serializer.expected_fields;
+                self.sequence = serializer.sequence;
+                self.pending_terminator = serializer.pending_terminator;
+                Ok(serializer.bytes_written())
+            }
+            Err(e) => {
+                self.inner = snapshot;
+                Err(e)
+            }
+        }
+    }
+
+    /// Serializes a header record with the field names of the given value.
+    ///
+    /// Struct fields are named after their keys, including fields of nested structs.
+    /// Every field of a compound value, like a tuple or an array, is named after the struct field
+    /// or the map key that contains it. A map written by [`serde::ser::SerializeMap::serialize_key`]
+    /// and [`serde::ser::SerializeMap::serialize_value`] separately, rather than by
+    /// [`serde::ser::SerializeMap::serialize_entry`], must have a single field per value.
+    /// The values themselves are not written, but one is needed to find out
+    /// the names, since `serde` doesn't describe types without a value.
+    /// [`Error::Unsupported`] is returned for a field that doesn't belong to any struct.
+    /// On success, it returns the number of bytes written.
+    ///
+    /// # Example
+    /// ```
+    /// #[derive(serde::Serialize)]
+    /// struct Record {
+    ///     pub city: &'static str,
+    ///     pub population: u32,
+    /// }
+    ///
+    /// let record = Record { city: "Cracow", population: 766_683 };
+    ///
+    /// let mut writer = serde_csv_core::Writer::new();
+    /// let mut csv = [0; 32];
+    /// let nwritten = writer.serialize_header(&record, &mut csv)?;
+    ///
+    /// assert_eq!(&csv[..nwritten], b"city,population\n");
+    /// # Ok::<(), serde_csv_core::ser::Error>(())
+    /// ```
+    pub fn serialize_header<T>(&mut self, value: &T, output: &mut [u8]) -> Result<usize>
+    where
+        T: Serialize + ?Sized,
+    {
+        let nwritten = self.write_with(output, |serializer| {
+            serializer.header = true;
+            value.serialize(&mut *serializer)?;
+            serializer.terminator()
+        })?;
+        self.partial = Partial::default();
+        Ok(nwritten)
+    }
+
+    /// Serializes a map, or a struct with `#[serde(flatten)]` fields, as a CSV record,
+    /// checking that it has the same keys as the first one.
+    ///
+    /// If `keys` is empty, the keys of the map are stored in them and a header record
+    /// of the keys is written before the record, as with [`Writer::serialize_header`].
+    /// Otherwise [`Error::KeyMismatch`] is returned unless the map has the stored keys
+    /// in the same order, and nothing is written.
+    /// [`Error::KeysOverflow`] is returned if the keys don't fit in `keys`.
+    /// On success, it returns the number of bytes written.
+    ///
+    /// # Example
+    /// ```
+    /// use serde_csv_core::ser::{Error, Keys};
+    /// use std::collections::BTreeMap;
+    ///
+    /// let mut writer = serde_csv_core::Writer::new();
+    /// let mut keys = Keys::<8, 64>::new();
+    /// let mut csv = [0; 32];
+    ///
+    /// let first = BTreeMap::from([("x", 1), ("y", 2)]);
+    /// let nwritten = writer.serialize_keyed(&first, &mut keys, &mut csv)?;
+    /// assert_eq!(&csv[..nwritten], b"x,y\n1,2\n");
+    ///
+    /// let second = BTreeMap::from([("x", 3), ("y", 4)]);
+    /// let nwritten = writer.serialize_keyed(&second, &mut keys, &mut csv)?;
+    /// assert_eq!(&csv[..nwritten], b"3,4\n");
+    ///
+    /// let third = BTreeMap::from([("x", 5), ("z", 6)]);
+    /// let result = writer.serialize_keyed(&third, &mut keys, &mut csv);
+    /// assert_eq!(result, Err(Error::KeyMismatch { index: 1 }));
+    /// # Ok::<(), serde_csv_core::ser::Error>(())
+    /// ```
+    pub fn serialize_keyed<T, const C: usize, const B: usize>(
+        &mut self,
+        value: &T,
+        keys: &mut Keys<C, B>,
+        output: &mut [u8],
+    ) -> Result<usize>
+    where
+        T: Serialize + ?Sized,
+    {
+        let header = keys.is_empty();
+        let mut nwritten = 0;
+        if header {
+            let result = self.write_keyed(
+                Output::Slice(output),
+                Some(keys.cursor(true)),
+                |serializer| {
+                    serializer.header = true;
+                    value.serialize(&mut *serializer)?;
+                    serializer.terminator()
+                },
+            );
+            nwritten = result.inspect_err(|_| keys.clear())?;
+            self.partial = Partial::default();
+        }
+        let result = self.write_keyed(
+            Output::Slice(&mut output[nwritten..]),
+            Some(keys.cursor(false)),
+            |serializer| {
+                value.serialize(&mut *serializer)?;
+                serializer.finish_keys()?;
+                serializer.terminator()
+            },
+        );
+        if result.is_err() && header {
+            // The header has to be written again with the next record.
+            keys.clear();
+        }
+        let nwritten = nwritten + result?;
+        self.partial = Partial::default();
+        Ok(nwritten)
+    }
+
+    /// Serializes a header record followed by all the given records.
+    ///
+    /// The header is derived from the first record as in [`Writer::serialize_header`].
+    /// Nothing is written if `records` is empty.
+    /// On success, it returns the number of bytes written.
+    ///
+    /// If `output` is too small, [`Error::TableOverflow`] is returned with the number of records
+    /// and bytes that were written completely. After flushing those bytes, the remaining records
+    /// can be written with [`Writer::serialize`]. If even the header didn't fit, no records are
+    /// reported and the whole table has to be written again.
+    ///
+    /// # Example
+    /// ```
+    /// #[derive(serde::Serialize)]
+    /// struct Record {
+    ///     pub city: &'static str,
+    ///     pub population: u32,
+    /// }
+    ///
+    /// let records = [
+    ///     Record { city: "Cracow", population: 766_683 },
+    ///     Record { city: "Warsaw", population: 1_860_281 },
+    /// ];
+    ///
+    /// let mut writer = serde_csv_core::Writer::new();
+    /// let mut csv = [0; 64];
+    /// let nwritten = writer.serialize_table(&records, &mut csv)?;
+    ///
+    /// assert_eq!(&csv[..nwritten], b"city,population\nCracow,766683\nWarsaw,1860281\n");
+    /// # Ok::<(), serde_csv_core::ser::Error>(())
+    /// ```
+    pub fn serialize_table<T>(&mut self, records: &[T], output: &mut [u8]) -> Result<usize>
+    where
+        T: Serialize,
+    {
+        let Some(first) = records.first() else {
+            return Ok(0);
+        };
+        let overflow = |e, records, nwritten| match e {
+            Error::Overflow => Error::TableOverflow { records, nwritten },
+            e => e,
+        };
+        let mut nwritten = self
+            .serialize_header(first, output)
+            .map_err(|e| overflow(e, 0, 0))?;
+        for (i, record) in records.iter().enumerate() {
+            nwritten += self
+                .serialize(record, &mut output[nwritten..])
+                .map_err(|e| overflow(e, i, nwritten))?;
         }
+        Ok(nwritten)
     }
 
-    /// Serializes the given value as a CSV byte slice.
+    /// Serializes the given value as a CSV byte slice, with fields in a different order.
+    ///
+    /// `permutation[i]` is the output column of the `i`-th field of the value,
+    /// counting fields of compound types one by one, as they are flattened.
+    /// The fields are collected in `scratch` before being written, so it has to fit all of them
+    /// plus two `usize`s and a byte for every field. [`Error::Overflow`] is returned otherwise.
+    /// [`Error::InvalidPermutation`] is returned if `permutation` doesn't assign a distinct
+    /// column to every field.
     ///
     /// Inserts record terminator after the serialized value.
-    /// Flattens compound types (e.g. nested structs, tuples, vectors).
     /// On success, it returns the number of bytes written.
     ///
     /// # Example
     /// ```
-    /// use heapless::String;
-    ///
     /// #[derive(serde::Serialize)]
     /// struct Record {
-    ///     pub country: String<32>,
-    ///     pub city: String<32>,
+    ///     pub country: &'static str,
+    ///     pub city: &'static str,
     ///     pub population: u32,
     /// }
     ///
-    /// let record = Record {
-    ///     country: "Poland".into(),
-    ///     city: "Cracow".into(),
-    ///     population: 766_683,
-    /// };
+    /// let record = Record { country: "Poland", city: "Cracow", population: 766_683 };
     ///
     /// let mut writer = serde_csv_core::Writer::new();
+    /// let mut scratch = [0; 128];
     /// let mut csv = [0; 32];
-    /// let nwritten = writer.serialize(&record, &mut csv)?;
+    /// let nwritten = writer.serialize_permuted(&record, &[1, 2, 0], &mut scratch, &mut csv)?;
     ///
-    /// assert_eq!(&csv[..nwritten], b"Poland,Cracow,766683\n");
+    /// assert_eq!(&csv[..nwritten], b"766683,Poland,Cracow\n");
     /// # Ok::<(), serde_csv_core::ser::Error>(())
     /// ```
-    pub fn serialize<T>(&mut self, value: &T, output: &mut [u8]) -> Result<usize>
+    pub fn serialize_permuted<T>(
+        &mut self,
+        value: &T,
+        permutation: &[usize],
+        scratch: &mut [u8],
+        output: &mut [u8],
+    ) -> Result<usize>
     where
         T: Serialize + ?Sized,
     {
-        let mut nwritten = 0;
+        self.permuted(value, false, permutation, scratch, output)
+    }
 
-        let mut serializer = Serializer::new(&mut self.inner, output);
-        value.serialize(&mut serializer)?;
-        nwritten += serializer.bytes_written();
+    /// Serializes a header record as in [`Writer::serialize_header`],
+    /// with columns in the order of [`Writer::serialize_permuted`].
+    pub fn serialize_header_permuted<T>(
+        &mut self,
+        value: &T,
+        permutation: &[usize],
+        scratch: &mut [u8],
+        output: &mut [u8],
+    ) -> Result<usize>
+    where
+        T: Serialize + ?Sized,
+    {
+        self.permuted(value, true, permutation, scratch, output)
+    }
 
-        let (result, n) = self.inner.terminator(&mut output[nwritten..]);
-        if result == csv_core::WriteResult::OutputFull {
-            return Err(Error::Overflow);
+    fn permuted<T>(
+        &mut self,
+        value: &T,
+        header: bool,
+        permutation: &[usize],
+        scratch: &mut [u8],
+        output: &mut [u8],
+    ) -> Result<usize>
+    where
+        T: Serialize + ?Sized,
+    {
+        // Nothing is written through the inner writer while collecting fields.
+        let mut serializer =
+            Serializer::with_config(&mut self.inner, self.config, Output::Slice(scratch));
+        serializer.header = header;
+        serializer.collect = true;
+        value.serialize(&mut serializer)?;
+        let len = serializer.bytes_written();
+
+        // The rest of `scratch` holds the offset of the field written in every column.
+        const LEN: usize = core::mem::size_of::<usize>();
+        let (collected, offsets) = scratch.split_at_mut(len);
+        let nfields = collected_fields(collected).count();
+        if nfields != permutation.len() {
+            return Err(Error::InvalidPermutation);
         }
-        nwritten += n;
-
+        let offsets = offsets.get_mut(..nfields * LEN).ok_or(Error::Overflow)?;
+        offsets.fill(u8::MAX);
+        let mut offset = 0usize;
+        for ((field, _), &column) in collected_fields(collected).zip(permutation) {
+            let slot = match offsets.chunks_exact_mut(LEN).nth(column) {
+                Some(slot) if slot.iter().all(|&b| b == u8::MAX) => slot,
+                _ => return Err(Error::InvalidPermutation),
+            };
+            slot.copy_from_slice(&offset.to_ne_bytes());
+            offset += LEN + 1 + field.len();
+        }
+        let (collected, offsets) = (&*collected, &*offsets);
+
+        let nwritten = self.write_with(output, |serializer| {
+            // Only leading fields depend on it, since the names were already collected.
+            serializer.header = header;
+            for slot in offsets.chunks_exact(LEN) {
+                let offset = usize::from_ne_bytes(slot.try_into().unwrap_or_default());
+                let (field, quoting) =
+                    collected_fields(collected.get(offset..).unwrap_or_default())
+                        .next()
+                        .unwrap_or_default();
+                if quoting {
+                    serializer.chunked_field(QuotePolicy::AlwaysStrings, |f| f(field))?;
+                } else {
+                    serializer.plain_field(field)?;
+                }
+            }
+            serializer.terminator()
+        })?;
+        self.partial = Partial::default();
         Ok(nwritten)
     }
 
@@ -135,6 +1281,44 @@ impl Writer {
         buf.truncate(len);
         Ok(buf)
     }
+
+    /// Serializes the given value as a CSV string.
+    ///
+    /// Inserts record terminator after the serialized value.
+    /// Flattens compound types (e.g. nested structs, tuples, vectors).
+    ///
+    /// The output is valid UTF-8 unless a byte slice field isn't,
+    /// in which case [`Error::InvalidUtf8`] is returned.
+    /// [`Error::NonUtf8Encoding`] is returned if [`Writer::encoding`] isn't [`Encoding::Utf8`],
+    /// since the output of other encodings isn't UTF-8 text.
+    ///
+    /// # Example
+    /// ```
+    /// use heapless::String;
+    ///
+    /// let mut writer = serde_csv_core::Writer::new();
+    /// let csv: String<32> = writer.serialize_to_string(&("Kraków", 766_683))?;
+    ///
+    /// assert_eq!(csv, "Kraków,766683\n");
+    /// # Ok::<(), serde_csv_core::ser::Error>(())
+    /// ```
+    #[cfg(feature = "heapless")]
+    pub fn serialize_to_string<T, const N: usize>(
+        &mut self,
+        value: &T,
+    ) -> Result<heapless::String<N>>
+    where
+        T: Serialize + ?Sized,
+    {
+        if self.config.encoding != Encoding::Utf8 {
+            return Err(Error::NonUtf8Encoding);
+        }
+        let buf: Vec<u8, N> = self.serialize_to_vec(value)?;
+        let str = core::str::from_utf8(&buf).map_err(|_| Error::InvalidUtf8)?;
+        let mut string = heapless::String::new();
+        string.push_str(str).map_err(|_| Error::Overflow)?;
+        Ok(string)
+    }
 }
 
 /// This type represents all possible errors that can occur when serializing CSV data.
@@ -142,6 +1326,48 @@ impl Writer {
 pub enum Error {
     /// Buffer overflow.
     Overflow,
+    /// The serializer was asked to perform an operation it doesn't support.
+    ///
+    /// Contains the name of the operation, e.g. `Serializer::serialize_map`.
+    Unsupported(&'static str),
+    /// Output of [`Writer::serialize_table`] overflowed.
+    TableOverflow {
+        /// Number of records written completely, not counting the header.
+        records: usize,
+        /// Number of bytes taken by the header and the complete records.
+        nwritten: usize,
+    },
+    /// The permutation passed to [`Writer::serialize_permuted`] doesn't match the fields.
+    InvalidPermutation,
+    /// The output of [`Writer::serialize_to_string`] is not valid UTF-8.
+    InvalidUtf8,
+    /// [`Writer::serialize_to_string`] was called with an encoding other than UTF-8.
+    NonUtf8Encoding,
+    /// A string field would have to be quoted, but [`QuotePolicy::Never`] is set.
+    QuotingRequired,
+    /// A record has a different number of fields than the first one,
+    /// see [`Writer::check_field_count`].
+    InconsistentFieldCount {
+        /// Number of fields of the first record.
+        expected: usize,
+        /// Number of fields of the rejected record.
+        got: usize,
+    },
+    /// A [`Nested`](crate::Nested) record didn't fit in its buffer.
+    NestedOverflow,
+    /// A map has different keys than the first one, see [`Writer::serialize_keyed`].
+    KeyMismatch {
+        /// Index of the first key that differs.
+        index: usize,
+    },
+    /// The keys of a map didn't fit in [`Keys`].
+    KeysOverflow,
+    /// A character can't be represented in the encoding set by [`Writer::encoding`].
+    Unrepresentable(char),
+    /// Text formatted by [`Writer::write_fmt_field`] didn't fit in [`Writer::FMT_CAPACITY`] bytes.
+    FmtOverflow,
+    /// Error with a custom message had to be discarded.
+    Custom,
 }
 
 /// Alias for a `core::result::Result` with the error type `serde_csv_core::ser::Error`.
@@ -151,6 +1377,28 @@ macro_rules! impl_format {
     ($self:ident, $write:ident, $f:ident) => {
         match $self {
             Self::Overflow => $write!($f, "Buffer overflow"),
+            Self::Unsupported(operation) => $write!($f, "`{}` is not supported", operation),
+            Self::TableOverflow { records, nwritten } => $write!(
+                $f,
+                "Buffer overflow after {} records ({} bytes)",
+                records,
+                nwritten
+            ),
+            Self::InvalidPermutation => $write!($f, "Invalid permutation of fields"),
+            Self::InvalidUtf8 => $write!($f, "Output is not valid UTF-8"),
+            Self::NonUtf8Encoding => $write!($f, "Output encoding is not UTF-8"),
+            Self::QuotingRequired => $write!($f, "Field requires quoting"),
+            Self::InconsistentFieldCount { expected, got } => {
+                $write!($f, "Record has {} fields instead of {}", got, expected)
+            }
+            Self::NestedOverflow => $write!($f, "Nested record overflow"),
+            Self::KeyMismatch { index } => $write!($f, "Key {} doesn't match the first map", index),
+            Self::KeysOverflow => $write!($f, "Keys overflow"),
+            Self::Unrepresentable(c) => {
+                $write!($f, "Character `{}` can't be represented in the encoding", c)
+            }
+            Self::FmtOverflow => $write!($f, "Formatted field overflow"),
+            Self::Custom => $write!($f, "Value could not be serialized"),
         }
     };
 }
@@ -168,7 +1416,7 @@ impl serde::ser::Error for Error {
     where
         T: core::fmt::Display,
     {
-        unimplemented!("custom is not supported")
+        Self::Custom
     }
 }
 
@@ -184,17 +1432,66 @@ impl defmt::Format for Error {
 #[derive(Debug)]
 pub struct Serializer<'a> {
     writer: &'a mut csv_core::Writer,
-    output: &'a mut [u8],
+    config: Config,
+    output: Output<'a>,
     nwritten: usize,
+    // Whether any field of the current record was written.
+    started: bool,
+    // Whether csv-core wrote any bytes of the current record.
+    inner_wrote: bool,
+    // Whether the serializer wrote any bytes of the current record bypassing csv-core.
+    manual_wrote: bool,
+    // Whether field names are written in place of values.
+    header: bool,
+    // Name of the struct field being serialized.
+    key: Option<&'static str>,
+    // Whether fields are collected unquoted, each preceded by its length, instead of written.
+    collect: bool,
+    // Number of fields of the current record.
+    nfields: usize,
+    // Number of fields of every record, remembered if `Config::check_field_count` is set.
+    expected_fields: Option<usize>,
+    // Whether elements of a sequence are written as records, until a compound value begins.
+    rows: bool,
+    // Checksum register of the bytes of the current record up to `checksum_start`,
+    // unless no bytes were checksummed yet.
+    crc: Option<u32>,
+    // Offset of the first byte of the current record not included in `crc`.
+    checksum_start: usize,
+    // Sequence number of the next record, if `Config::sequence_start` is set.
+    sequence: u32,
+    // Keys of maps stored or checked by `Writer::serialize_keyed`.
+    keys: Option<KeyCursor<'a>>,
+    // Whether the terminator of the previous record is deferred to the start of the next one.
+    pending_terminator: bool,
 }
 
 impl<'a> Serializer<'a> {
     /// Creates a new CSV serializer.
     pub fn new(writer: &'a mut csv_core::Writer, output: &'a mut [u8]) -> Self {
+        Self::with_config(writer, Config::default(), Output::Slice(output))
+    }
+
+    fn with_config(writer: &'a mut csv_core::Writer, config: Config, output: Output<'a>) -> Self {
         Self {
             writer,
+            config,
             output,
             nwritten: 0,
+            started: false,
+            inner_wrote: false,
+            manual_wrote: false,
+            header: false,
+            key: None,
+            collect: false,
+            nfields: 0,
+            expected_fields: None,
+            rows: false,
+            crc: None,
+            checksum_start: 0,
+            sequence: 0,
+            keys: None,
+            pending_terminator: false,
         }
     }
 
@@ -204,26 +1501,585 @@ impl<'a> Serializer<'a> {
     }
 
     fn field(&mut self, input: impl AsRef<[u8]>) -> Result<()> {
-        let (r, _, n) = self
-            .writer
-            .field(input.as_ref(), &mut self.output[self.nwritten..]);
-        self.nwritten += n;
-        if r == csv_core::WriteResult::OutputFull {
-            return Err(Error::Overflow);
+        if self.header {
+            let key = self
+                .key
+                .ok_or(Error::Unsupported("Writer::serialize_header"))?;
+            return self.name_field(|serializer| serializer.text_field(key.as_bytes()));
+        }
+        self.plain_field(input.as_ref())
+    }
+
+    /// Checks that no stored key of maps was left out, see [`KeyCursor::finish`].
+    fn finish_keys(&self) -> Result<()> {
+        match &self.keys {
+            Some(keys) => keys.finish(),
+            None => Ok(()),
+        }
+    }
+
+    /// Writes a column name of a header record with `write`.
+    fn name_field(&mut self, write: impl FnOnce(&mut Self) -> Result<()>) -> Result<()> {
+        if !self.started {
+            // The leading fields are named as well.
+            self.started = self.leading_fields()?;
+        }
+        // Field names are written like string values.
+        self.header = false;
+        let result = write(self);
+        self.header = true;
+        result
+    }
+
+    /// Writes a field through csv-core, which quotes it only if necessary.
+    fn plain_field(&mut self, input: &[u8]) -> Result<()> {
+        if self.config.field_encoder.is_some() {
+            return self.chunked_field(QuotePolicy::Minimal, |f| f(input));
+        }
+        if self.collect {
+            return self.collected_field(input.len(), false, |f| f(input));
+        }
+        self.begin_field()?;
+        let n = self.emit(input, |writer, input, output| writer.field(input, output))?;
+        self.inner_wrote |= n > 0;
+        Ok(())
+    }
+
+    /// Writes a delimiter unless the field is the first one in the record.
+    fn begin_field(&mut self) -> Result<()> {
+        if self.started {
+            self.delimiter()?;
+        } else {
+            self.started = true;
+            if self.leading_fields()? {
+                self.delimiter()?;
+            }
+        }
+        self.nfields += 1;
+        Ok(())
+    }
+
+    /// Writes the fields that precede the value in every record, set by [`Writer::record_prefix`]
+    /// and [`Writer::sequence_column`], returning whether any were written.
+    fn leading_fields(&mut self) -> Result<bool> {
+        if self.pending_terminator {
+            self.pending_terminator = false;
+            self.manual_terminator()?;
+            // The terminator belongs to the previous record.
+            self.checksum_start = self.nwritten;
+        }
+        let mut written = false;
+        if let Some(prefix) = self.config.record_prefix {
+            self.leading_field(prefix)?;
+            written = true;
         }
+        if self.config.sequence_start.is_some() {
+            if written {
+                self.delimiter()?;
+            }
+            if self.header {
+                self.leading_field(self.config.sequence_name.as_bytes())?;
+            } else {
+                let mut buffer = itoa::Buffer::new();
+                self.leading_field(buffer.format(self.sequence).as_bytes())?;
+                self.sequence = self.sequence.wrapping_add(1);
+            }
+            written = true;
+        }
+        Ok(written)
+    }
+
+    fn leading_field(&mut self, input: &[u8]) -> Result<()> {
+        let n = self.emit(input, |writer, input, output| writer.field(input, output))?;
+        self.inner_wrote |= n > 0;
+        self.nfields += 1;
         Ok(())
     }
 
     fn delimiter(&mut self) -> Result<()> {
-        let (r, n) = self.writer.delimiter(&mut self.output[self.nwritten..]);
-        self.nwritten += n;
-        if r == csv_core::WriteResult::OutputFull {
-            return Err(Error::Overflow);
+        let n = self.emit(&[], |writer, _, output| {
+            let (r, n) = writer.delimiter(output);
+            (r, 0, n)
+        })?;
+        self.inner_wrote |= n > 0;
+        Ok(())
+    }
+
+    fn terminator(&mut self) -> Result<()> {
+        if !self.started {
+            // A record without fields still has its leading fields.
+            self.started = self.leading_fields()?;
+        }
+        if self.config.check_field_count {
+            match self.expected_fields {
+                Some(expected) if expected != self.nfields => {
+                    return Err(Error::InconsistentFieldCount {
+                        expected,
+                        got: self.nfields,
+                    })
+                }
+                _ => self.expected_fields = Some(self.nfields),
+            }
+        }
+        if let Some(checksum) = self.config.checksum {
+            // csv-core writes the closing quote of a field lazily, but it belongs to the record.
+            let n = self.emit(&[], |writer, _, output| {
+                let (r, n) = writer.finish(output);
+                (r, 0, n)
+            })?;
+            self.inner_wrote |= n > 0;
+            self.update_checksum();
+            let crc = self.crc.unwrap_or_else(|| checksum.init());
+            let mut hex = [0; 8];
+            let len = checksum.to_hex(checksum.value(crc), &mut hex);
+            self.plain_field(&hex[..len])?;
+        }
+        if self.config.defer_terminator {
+            self.defer_inner_terminator()?;
+        } else if self.manual_wrote && !self.inner_wrote {
+            // csv-core writes `""` for records it considers empty,
+            // so records written entirely by the serializer are terminated manually.
+            self.manual_terminator()?;
+        } else {
+            self.inner_terminator()?;
+        }
+        self.started = false;
+        self.inner_wrote = false;
+        self.manual_wrote = false;
+        self.nfields = 0;
+        self.crc = None;
+        self.checksum_start = self.nwritten;
+        Ok(())
+    }
+
+    /// Includes the bytes written since `checksum_start` in the checksum of the current record.
+    fn update_checksum(&mut self) {
+        let Some(checksum) = self.config.checksum else {
+            return;
+        };
+        let mut crc = self.crc.unwrap_or_else(|| checksum.init());
+        let mut offset = self.checksum_start;
+        while offset < self.nwritten {
+            let written = self.output.rest(offset);
+            let n = written.len().min(self.nwritten - offset);
+            crc = checksum.update(crc, &written[..n]);
+            offset += n;
+        }
+        self.crc = Some(crc);
+        self.checksum_start = self.nwritten;
+    }
+
+    fn manual_terminator(&mut self) -> Result<()> {
+        match self.writer.get_terminator() {
+            csv_core::Terminator::CRLF => self.copy(b"\r\n"),
+            csv_core::Terminator::Any(b) => self.copy(&[b]),
+            _ => self.inner_terminator(),
+        }
+    }
+
+    /// Finishes the record like [`Serializer::inner_terminator`],
+    /// but leaves the terminator itself to the start of the next record.
+    fn defer_inner_terminator(&mut self) -> Result<()> {
+        let n = self.emit(&[], |writer, _, output| {
+            let (r, n) = writer.finish(output);
+            (r, 0, n)
+        })?;
+        self.inner_wrote |= n > 0;
+        if !self.inner_wrote && !self.manual_wrote {
+            // A record without fields is written as an empty quoted field.
+            let quote = self.writer.get_quote();
+            self.copy(&[quote, quote])?;
+        }
+        // csv-core can't be reset to the start of a record,
+        // so the terminator is written to a scratch buffer instead.
+        self.writer.terminator(&mut [0; 4]);
+        self.pending_terminator = true;
+        Ok(())
+    }
+
+    fn inner_terminator(&mut self) -> Result<()> {
+        self.emit(&[], |writer, _, output| {
+            let (r, n) = writer.terminator(output);
+            (r, 0, n)
+        })?;
+        Ok(())
+    }
+
+    /// Writes a string-like field, applying the configured transformations.
+    fn text_field(&mut self, input: &[u8]) -> Result<()> {
+        if self.header {
+            return self.field(input);
+        }
+        let config = self.config;
+        let guard = config.sanitize_formulas && crate::is_formula(input, config.formula_guard);
+        let escape = config.escape_control_chars && input.iter().copied().any(is_control);
+        if !guard && !escape && config.quote_policy == QuotePolicy::Minimal {
+            // The field encoder is applied by `plain_field`.
+            return self.plain_field(input);
+        }
+        self.chunked_field(config.quote_policy, |f| {
+            if guard {
+                f(&[config.formula_guard])?;
+            }
+            if escape {
+                escape_control_chars(input, f)
+            } else {
+                f(input)
+            }
+        })
+    }
+
+    /// Writes a string field, transcoding it from UTF-8 if [`Writer::encoding`] is set otherwise.
+    fn str_field(&mut self, input: &str) -> Result<()> {
+        if self.config.encoding == Encoding::Utf8 || input.is_ascii() {
+            return self.text_field(input.as_bytes());
+        }
+        self.display_field(&input)
+    }
+
+    /// Writes a string field formatted by `value` without storing it,
+    /// applying the same transformations as [`Serializer::text_field`]
+    /// and transcoding it according to [`Writer::encoding`].
+    ///
+    /// `value` is formatted more than once, so it must always produce the same output.
+    /// [`Serializer::str_field`] passes non-ASCII strings here when transcoding them,
+    /// which are deterministic, and so is the buffer of [`Writer::write_fmt_field`].
+    fn display_field(&mut self, value: &dyn core::fmt::Display) -> Result<()> {
+        if self.header {
+            return self.field([]);
+        }
+        let config = self.config;
+        let mut first = None;
+        let mut escape = false;
+        write_display(value, config.encoding, &mut |chunk| {
+            if first.is_none() {
+                first = chunk.iter().find(|&&b| b != config.formula_guard).copied();
+            }
+            escape |= chunk.iter().copied().any(is_control);
+            Ok(())
+        })?;
+        // Only the first byte that isn't a guard decides whether the field is a formula.
+        let guard = config.sanitize_formulas
+            && first.is_some_and(|b| crate::is_formula(&[b], config.formula_guard));
+        let escape = config.escape_control_chars && escape;
+        self.chunked_field(config.quote_policy, |f| {
+            if guard {
+                f(&[config.formula_guard])?;
+            }
+            write_display(value, config.encoding, &mut |chunk| {
+                if escape {
+                    escape_control_chars(chunk, f)
+                } else {
+                    f(chunk)
+                }
+            })
+        })
+    }
+
+    /// Writes a single field whose contents are produced in chunks by `chunks`,
+    /// encoding them with [`Writer::field_encoder`] and quoting the field according to `policy`.
+    ///
+    /// `chunks` is called twice: once to decide whether the field needs quoting
+    /// and once to write it. The field is written bypassing csv-core,
+    /// since csv-core decides about quoting based on the first chunk only.
+    fn chunked_field<F>(&mut self, policy: QuotePolicy, chunks: F) -> Result<()>
+    where
+        F: Fn(&mut dyn FnMut(&[u8]) -> Result<()>) -> Result<()>,
+    {
+        let encoder = self.config.field_encoder;
+        let chunks = |f: &mut dyn FnMut(&[u8]) -> Result<()>| match encoder {
+            Some(encoder) => chunks(&mut |chunk| encoder.encode(chunk, f)),
+            None => chunks(f),
+        };
+        let mut quoting = false;
+        let mut len = 0;
+        chunks(&mut |chunk| {
+            quoting |= self.writer.should_quote(chunk);
+            len += chunk.len();
+            Ok(())
+        })?;
+        match policy {
+            QuotePolicy::Minimal => {}
+            QuotePolicy::AlwaysStrings => quoting = true,
+            QuotePolicy::Never if quoting => return Err(Error::QuotingRequired),
+            QuotePolicy::Never => {}
+        }
+        if self.collect {
+            return self.collected_field(len, quoting, chunks);
+        }
+        self.begin_field()?;
+
+        let quote = self.writer.get_quote();
+        if quoting {
+            self.raw(&[quote])?;
+        }
+        chunks(&mut |chunk| {
+            if quoting {
+                self.quoted(chunk)
+            } else {
+                self.raw(chunk)
+            }
+        })?;
+        if quoting {
+            self.raw(&[quote])?;
+        }
+        Ok(())
+    }
+
+    /// Collects a field of length `len`, produced in chunks by `chunks`, preceded by its length
+    /// and whether it has to be quoted.
+    fn collected_field<F>(&mut self, len: usize, quoting: bool, chunks: F) -> Result<()>
+    where
+        F: Fn(&mut dyn FnMut(&[u8]) -> Result<()>) -> Result<()>,
+    {
+        self.raw(&len.to_ne_bytes())?;
+        self.raw(&[u8::from(quoting)])?;
+        chunks(&mut |chunk| self.raw(chunk))
+    }
+
+    fn raw(&mut self, input: &[u8]) -> Result<()> {
+        self.copy(input)?;
+        self.manual_wrote |= !input.is_empty();
+        Ok(())
+    }
+
+    fn quoted(&mut self, input: &[u8]) -> Result<()> {
+        let n = self.emit(input, |writer, input, output| {
+            csv_core::quote(
+                input,
+                output,
+                writer.get_quote(),
+                writer.get_escape(),
+                writer.get_double_quote(),
+            )
+        })?;
+        self.manual_wrote |= n > 0;
+        Ok(())
+    }
+
+    /// Copies `input` to the output, continuing in the next part if the current one is full.
+    fn copy(&mut self, mut input: &[u8]) -> Result<()> {
+        while !input.is_empty() {
+            let output = self.output.rest(self.nwritten);
+            if output.is_empty() {
+                return Err(Error::Overflow);
+            }
+            let n = input.len().min(output.len());
+            output[..n].copy_from_slice(&input[..n]);
+            self.nwritten += n;
+            input = &input[n..];
+        }
+        Ok(())
+    }
+
+    /// Writes `input` to the output with a csv-core function, returning the number of bytes written.
+    ///
+    /// csv-core doesn't split some sequences, like an escaped quote or a CRLF terminator,
+    /// so a sequence that doesn't fit in the rest of the current part is written to a scratch
+    /// buffer first and then copied across the parts.
+    fn emit<F>(&mut self, mut input: &[u8], mut f: F) -> Result<usize>
+    where
+        F: FnMut(&mut csv_core::Writer, &[u8], &mut [u8]) -> (csv_core::WriteResult, usize, usize),
+    {
+        let start = self.nwritten;
+        loop {
+            let output = self.output.rest(self.nwritten);
+            let full = output.is_empty();
+            let (r, nin, nout) = f(self.writer, input, output);
+            input = &input[nin..];
+            self.nwritten += nout;
+            if r == csv_core::WriteResult::InputEmpty {
+                return Ok(self.nwritten - start);
+            }
+            if nin == 0 && nout == 0 {
+                if full {
+                    return Err(Error::Overflow);
+                }
+                let mut scratch = [0; 8];
+                let (r, nin, nout) = f(self.writer, input, &mut scratch);
+                input = &input[nin..];
+                self.copy(&scratch[..nout])?;
+                if r == csv_core::WriteResult::InputEmpty {
+                    return Ok(self.nwritten - start);
+                }
+            }
+        }
+    }
+}
+
+/// Output of a [`Serializer`], either a single buffer or a sequence of buffers.
+#[derive(Debug)]
+enum Output<'a> {
+    Slice(&'a mut [u8]),
+    Parts(&'a mut dyn Parts),
+}
+
+impl Output<'_> {
+    fn reborrow(&mut self) -> Output<'_> {
+        match self {
+            Output::Slice(output) => Output::Slice(output),
+            Output::Parts(parts) => Output::Parts(*parts),
+        }
+    }
+
+    /// Returns the unwritten rest of the buffer containing the byte at `offset`.
+    fn rest(&mut self, offset: usize) -> &mut [u8] {
+        match self {
+            Output::Slice(output) => &mut output[offset..],
+            Output::Parts(parts) => parts.rest(offset),
+        }
+    }
+}
+
+/// Buffers written one after another, as if they were a single buffer.
+trait Parts: core::fmt::Debug {
+    /// Returns the rest of the part containing the byte at `offset`, skipping full parts.
+    fn rest(&mut self, offset: usize) -> &mut [u8];
+}
+
+impl Parts for &mut [&mut [u8]] {
+    fn rest(&mut self, mut offset: usize) -> &mut [u8] {
+        for part in self.iter_mut() {
+            if offset < part.len() {
+                return &mut part[offset..];
+            }
+            offset -= part.len();
         }
+        &mut []
+    }
+}
+
+/// Splits fields collected by a serializer in the `collect` mode
+/// into their contents and whether they have to be quoted.
+fn collected_fields(mut collected: &[u8]) -> impl Iterator<Item = (&[u8], bool)> {
+    const LEN: usize = core::mem::size_of::<usize>();
+    core::iter::from_fn(move || {
+        let (len, rest) = collected.split_at_checked(LEN)?;
+        let len = usize::from_ne_bytes(len.try_into().ok()?);
+        let (&quoting, rest) = rest.split_first()?;
+        let (field, rest) = rest.split_at_checked(len)?;
+        collected = rest;
+        Some((field, quoting != 0))
+    })
+}
+
+/// Text formatted by [`Writer::write_fmt_field`].
+struct FmtBuffer {
+    bytes: [u8; Writer::FMT_CAPACITY],
+    len: usize,
+}
+
+impl core::fmt::Write for FmtBuffer {
+    fn write_str(&mut self, s: &str) -> core::fmt::Result {
+        let end = self.len + s.len();
+        let bytes = self.bytes.get_mut(self.len..end).ok_or(core::fmt::Error)?;
+        bytes.copy_from_slice(s.as_bytes());
+        self.len = end;
         Ok(())
     }
 }
 
+/// Passes the formatted `value`, transcoded from UTF-8 to `encoding`, to `f` in chunks.
+fn write_display(
+    value: &dyn core::fmt::Display,
+    encoding: Encoding,
+    f: &mut dyn FnMut(&[u8]) -> Result<()>,
+) -> Result<()> {
+    use core::fmt::Write;
+
+    struct Chunks<'a> {
+        f: &'a mut dyn FnMut(&[u8]) -> Result<()>,
+        encoding: Encoding,
+        error: Option<Error>,
+    }
+
+    impl Write for Chunks<'_> {
+        fn write_str(&mut self, s: &str) -> core::fmt::Result {
+            let result = match self.encoding {
+                Encoding::Utf8 => (self.f)(s.as_bytes()),
+                encoding => write_encoded(s, encoding, self.f),
+            };
+            result.map_err(|e| {
+                self.error = Some(e);
+                core::fmt::Error
+            })
+        }
+    }
+
+    let mut chunks = Chunks {
+        f,
+        encoding,
+        error: None,
+    };
+    write!(chunks, "{}", value).map_err(|_| chunks.error.unwrap_or(Error::Custom))
+}
+
+/// Passes `s` encoded in the single-byte `encoding` to `f` in chunks.
+fn write_encoded(
+    s: &str,
+    encoding: Encoding,
+    f: &mut dyn FnMut(&[u8]) -> Result<()>,
+) -> Result<()> {
+    let mut buffer = [0; 32];
+    let mut len = 0;
+    for c in s.chars() {
+        buffer[len] = encoding.encode_char(c).ok_or(Error::Unrepresentable(c))?;
+        len += 1;
+        if len == buffer.len() {
+            f(&buffer)?;
+            len = 0;
+        }
+    }
+    match len {
+        0 => Ok(()),
+        len => f(&buffer[..len]),
+    }
+}
+
+fn is_control(b: u8) -> bool {
+    b < 0x20 || b == 0x7f
+}
+
+/// Encodes a byte as two uppercase hexadecimal digits.
+fn hex_byte(b: u8) -> [u8; 2] {
+    [
+        crate::HEX_DIGITS[usize::from(b >> 4)],
+        crate::HEX_DIGITS[usize::from(b & 0xf)],
+    ]
+}
+
+/// Encodes up to 3 bytes as 4 padded Base64 characters.
+fn base64_chunk(chunk: &[u8]) -> [u8; 4] {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let mut bytes = [0; 3];
+    bytes[..chunk.len()].copy_from_slice(chunk);
+    let n = u32::from_be_bytes([0, bytes[0], bytes[1], bytes[2]]);
+    let mut out = [b'='; 4];
+    for (i, c) in out.iter_mut().enumerate().take(chunk.len() + 1) {
+        *c = ALPHABET[(n >> (18 - 6 * i)) as usize & 0x3f];
+    }
+    out
+}
+
+/// Splits `input` into chunks with control characters replaced by `\xNN` escapes.
+fn escape_control_chars(input: &[u8], f: &mut dyn FnMut(&[u8]) -> Result<()>) -> Result<()> {
+    let mut start = 0;
+    for (i, &b) in input.iter().enumerate() {
+        if is_control(b) {
+            if start < i {
+                f(&input[start..i])?;
+            }
+            let [hi, lo] = hex_byte(b);
+            f(&[b'\\', b'x', hi, lo])?;
+            start = i + 1;
+        }
+    }
+    if start < input.len() {
+        f(&input[start..])?;
+    }
+    Ok(())
+}
+
 impl<'a, 'b> ser::Serializer for &'a mut Serializer<'b> {
     type Ok = ();
 
@@ -237,7 +2093,7 @@ impl<'a, 'b> ser::Serializer for &'a mut Serializer<'b> {
 
     type SerializeTupleVariant = Unreachable;
 
-    type SerializeMap = Unreachable;
+    type SerializeMap = Compound<'a, 'b>;
 
     type SerializeStruct = Compound<'a, 'b>;
 
@@ -302,19 +2158,30 @@ impl<'a, 'b> ser::Serializer for &'a mut Serializer<'b> {
     }
 
     fn serialize_char(self, v: char) -> Result<Self::Ok> {
-        self.field(v.encode_utf8(&mut [0; 4]))
+        self.str_field(v.encode_utf8(&mut [0; 4]))
     }
 
     fn serialize_str(self, v: &str) -> Result<Self::Ok> {
-        self.field(v)
+        self.str_field(v)
     }
 
     fn serialize_bytes(self, v: &[u8]) -> Result<Self::Ok> {
-        self.field(v)
+        let policy = self.config.quote_policy;
+        match self.config.bytes_mode {
+            BytesMode::SingleField => self.text_field(v),
+            BytesMode::PerByte => v.iter().try_for_each(|b| self.serialize_u8(*b)),
+            _ if self.header => self.field(v),
+            BytesMode::Hex => {
+                self.chunked_field(policy, |f| v.iter().try_for_each(|&b| f(&hex_byte(b))))
+            }
+            BytesMode::Base64 => self.chunked_field(policy, |f| {
+                v.chunks(3).try_for_each(|chunk| f(&base64_chunk(chunk)))
+            }),
+        }
     }
 
     fn serialize_none(self) -> Result<Self::Ok> {
-        self.field([])
+        self.field(self.config.null_literal)
     }
 
     fn serialize_some<T>(self, value: &T) -> Result<Self::Ok>
@@ -341,10 +2208,13 @@ impl<'a, 'b> ser::Serializer for &'a mut Serializer<'b> {
         self.field(variant)
     }
 
-    fn serialize_newtype_struct<T>(self, _name: &'static str, value: &T) -> Result<Self::Ok>
+    fn serialize_newtype_struct<T>(self, name: &'static str, value: &T) -> Result<Self::Ok>
     where
         T: ser::Serialize + ?Sized,
     {
+        if name == crate::nested::OVERFLOW {
+            return Err(Error::NestedOverflow);
+        }
         value.serialize(self)
     }
 
@@ -358,7 +2228,7 @@ impl<'a, 'b> ser::Serializer for &'a mut Serializer<'b> {
     where
         T: ser::Serialize + ?Sized,
     {
-        unimplemented!("`Serializer::serialize_newtype_variant` is not supported");
+        Err(Error::Unsupported("Serializer::serialize_newtype_variant"))
     }
 
     fn serialize_seq(self, _len: Option<usize>) -> Result<Self::SerializeSeq> {
@@ -384,11 +2254,11 @@ impl<'a, 'b> ser::Serializer for &'a mut Serializer<'b> {
         _variant: &'static str,
         _len: usize,
     ) -> Result<Self::SerializeTupleVariant> {
-        unimplemented!("`Serializer::serialize_tuple_variant` is not supported");
+        Err(Error::Unsupported("Serializer::serialize_tuple_variant"))
     }
 
     fn serialize_map(self, _len: Option<usize>) -> Result<Self::SerializeMap> {
-        unimplemented!("`Serializer::serialize_map` is not supported");
+        Ok(Compound::new(self))
     }
 
     fn serialize_struct(self, _name: &'static str, _len: usize) -> Result<Self::SerializeStruct> {
@@ -402,28 +2272,38 @@ impl<'a, 'b> ser::Serializer for &'a mut Serializer<'b> {
         _variant: &'static str,
         _len: usize,
     ) -> Result<Self::SerializeStructVariant> {
-        unimplemented!("`Serializer::serialize_struct_variant` is not supported");
+        Err(Error::Unsupported("Serializer::serialize_struct_variant"))
     }
 
-    fn collect_str<T>(self, _value: &T) -> Result<Self::Ok>
+    /// Writes the text of `value` as a string field without storing it.
+    ///
+    /// `value` is formatted more than once, so its `Display` implementation
+    /// must always produce the same output.
+    fn collect_str<T>(self, value: &T) -> Result<Self::Ok>
     where
         T: core::fmt::Display + ?Sized,
     {
-        unimplemented!("`Serializer::collect_str` is not supported");
+        self.display_field(&value)
     }
 }
 
 #[doc(hidden)]
 pub struct Compound<'a, 'b> {
     serializer: &'a mut Serializer<'b>,
-    nfields: usize,
+    // Whether elements of this sequence are written as records.
+    rows: bool,
+    // Name of the struct field containing this value, restored after struct fields.
+    key: Option<&'static str>,
 }
 
 impl<'a, 'b> Compound<'a, 'b> {
     fn new(serializer: &'a mut Serializer<'b>) -> Self {
+        let rows = core::mem::take(&mut serializer.rows);
+        let key = serializer.key;
         Self {
             serializer,
-            nfields: 0,
+            rows,
+            key,
         }
     }
 
@@ -431,12 +2311,20 @@ impl<'a, 'b> Compound<'a, 'b> {
     where
         T: ser::Serialize + ?Sized,
     {
-        if self.nfields > 0 {
-            self.serializer.delimiter()?;
-        }
-        self.nfields += 1;
         value.serialize(&mut *self.serializer)
     }
+
+    /// Serializes an element of a sequence, terminating the record after it in the rows mode.
+    fn seq_element<T>(&mut self, value: &T) -> Result<()>
+    where
+        T: ser::Serialize + ?Sized,
+    {
+        self.element(value)?;
+        if self.rows {
+            self.serializer.terminator()?;
+        }
+        Ok(())
+    }
 }
 
 impl ser::SerializeSeq for Compound<'_, '_> {
@@ -448,7 +2336,7 @@ impl ser::SerializeSeq for Compound<'_, '_> {
     where
         T: ser::Serialize + ?Sized,
     {
-        self.element(value)
+        self.seq_element(value)
     }
 
     fn end(self) -> Result<Self::Ok> {
@@ -465,7 +2353,7 @@ impl ser::SerializeTuple for Compound<'_, '_> {
     where
         T: ser::Serialize + ?Sized,
     {
-        self.element(value)
+        self.seq_element(value)
     }
 
     fn end(self) -> Result<Self::Ok> {
@@ -495,51 +2383,113 @@ impl ser::SerializeStruct for Compound<'_, '_> {
 
     type Error = Error;
 
-    fn serialize_field<T>(&mut self, _key: &'static str, value: &T) -> Result<()>
+    fn serialize_field<T>(&mut self, key: &'static str, value: &T) -> Result<()>
     where
         T: ser::Serialize + ?Sized,
     {
+        self.serializer.key = Some(key);
         self.element(value)
     }
 
     fn end(self) -> Result<Self::Ok> {
+        self.serializer.key = self.key;
         Ok(())
     }
 }
 
-#[doc(hidden)]
-pub struct Unreachable;
-
-impl ser::SerializeTupleVariant for Unreachable {
+impl ser::SerializeMap for Compound<'_, '_> {
     type Ok = ();
 
     type Error = Error;
 
-    fn serialize_field<T>(&mut self, _value: &T) -> Result<()>
+    fn serialize_key<T>(&mut self, key: &T) -> Result<()>
     where
         T: ser::Serialize + ?Sized,
     {
-        unreachable!()
+        if let Some(keys) = &mut self.serializer.keys {
+            keys.visit(key)?;
+        }
+        // Keys are only written as column names.
+        if !self.serializer.header {
+            return Ok(());
+        }
+        self.serializer
+            .name_field(|serializer| key.serialize(serializer))
+    }
+
+    fn serialize_value<T>(&mut self, value: &T) -> Result<()>
+    where
+        T: ser::Serialize + ?Sized,
+    {
+        if self.serializer.header {
+            // The key is already written as the name of a single column.
+            return match count_fields(value, self.serializer.config)? {
+                1 => Ok(()),
+                _ => Err(Error::Unsupported("Writer::serialize_header")),
+            };
+        }
+        self.element(value)
+    }
+
+    fn serialize_entry<K, V>(&mut self, key: &K, value: &V) -> Result<()>
+    where
+        K: ser::Serialize + ?Sized,
+        V: ser::Serialize + ?Sized,
+    {
+        if !self.serializer.header {
+            self.serialize_key(key)?;
+            return self.serialize_value(value);
+        }
+        if let Some(keys) = &mut self.serializer.keys {
+            keys.visit(key)?;
+        }
+        // Every field of a compound value is named after the key.
+        for _ in 0..count_fields(value, self.serializer.config)? {
+            self.serializer
+                .name_field(|serializer| key.serialize(serializer))?;
+        }
+        Ok(())
     }
 
     fn end(self) -> Result<Self::Ok> {
-        unreachable!()
+        Ok(())
     }
 }
 
-impl ser::SerializeMap for Unreachable {
-    type Ok = ();
+/// Returns the number of fields `value` is written as, without the leading fields of a record.
+fn count_fields<T>(value: &T, mut config: Config) -> Result<usize>
+where
+    T: ser::Serialize + ?Sized,
+{
+    config.record_prefix = None;
+    config.sequence_start = None;
+    config.checksum = None;
+    let mut writer = csv_core::Writer::new();
+    let mut discard = Discard([0; 16]);
+    let mut serializer = Serializer::with_config(&mut writer, config, Output::Parts(&mut discard));
+    value.serialize(&mut serializer)?;
+    Ok(serializer.nfields)
+}
 
-    type Error = Error;
+/// Discards the bytes written to it through a small window.
+#[derive(Debug)]
+struct Discard([u8; 16]);
 
-    fn serialize_key<T>(&mut self, _key: &T) -> Result<()>
-    where
-        T: ser::Serialize + ?Sized,
-    {
-        unreachable!()
+impl Parts for Discard {
+    fn rest(&mut self, _offset: usize) -> &mut [u8] {
+        &mut self.0
     }
+}
+
+#[doc(hidden)]
+pub struct Unreachable;
+
+impl ser::SerializeTupleVariant for Unreachable {
+    type Ok = ();
+
+    type Error = Error;
 
-    fn serialize_value<T>(&mut self, _value: &T) -> Result<()>
+    fn serialize_field<T>(&mut self, _value: &T) -> Result<()>
     where
         T: ser::Serialize + ?Sized,
     {
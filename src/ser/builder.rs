@@ -0,0 +1,245 @@
+use super::{BytesMode, Config, FieldEncoder, QuotePolicy, Writer};
+use crate::{Checksum, Encoding};
+
+/// Builds a [`Writer`] configured with both the CSV format and the serde-level options.
+///
+/// The format is set by the methods passed through to [`csv_core::WriterBuilder`].
+/// Other methods are equivalent to the methods of [`Writer`] with the same names.
+/// Presets for common formats are available as [`WriterBuilder::tsv`],
+/// [`WriterBuilder::excel_eu`] and [`WriterBuilder::ascii_delimited`].
+///
+/// # Example
+/// ```
+/// use serde_csv_core::ser::{QuotePolicy, WriterBuilder};
+///
+/// let mut writer = WriterBuilder::new()
+///     .delimiter(b';')
+///     .null_literal(Some(b"NULL"))
+///     .quote_policy(QuotePolicy::AlwaysStrings)
+///     .build();
+///
+/// let mut csv = [0; 32];
+/// let nwritten = writer.serialize(&("a", None::<u8>, 1), &mut csv)?;
+///
+/// assert_eq!(&csv[..nwritten], b"\"a\";NULL;1\n");
+/// # Ok::<(), serde_csv_core::ser::Error>(())
+/// ```
+#[derive(Debug, Default)]
+pub struct WriterBuilder {
+    inner: csv_core::WriterBuilder,
+    config: Config,
+}
+
+impl WriterBuilder {
+    /// Constructs a builder with the default configuration.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Constructs a builder of tab-separated values.
+    ///
+    /// # Example
+    /// ```
+    /// let mut writer = serde_csv_core::ser::WriterBuilder::tsv().build();
+    ///
+    /// let mut csv = [0; 16];
+    /// let nwritten = writer.serialize(&("a b", 1), &mut csv)?;
+    ///
+    /// assert_eq!(&csv[..nwritten], b"a b\t1\n");
+    /// # Ok::<(), serde_csv_core::ser::Error>(())
+    /// ```
+    pub fn tsv() -> Self {
+        let mut builder = Self::new();
+        builder.delimiter(b'\t');
+        builder
+    }
+
+    /// Constructs a builder of the format Excel uses in locales with a decimal comma:
+    /// fields delimited with `;` and records terminated with `\r\n`.
+    ///
+    /// # Example
+    /// ```
+    /// let mut writer = serde_csv_core::ser::WriterBuilder::excel_eu().build();
+    ///
+    /// let mut csv = [0; 16];
+    /// let nwritten = writer.serialize(&("a;b", 1), &mut csv)?;
+    ///
+    /// assert_eq!(&csv[..nwritten], b"\"a;b\";1\r\n");
+    /// # Ok::<(), serde_csv_core::ser::Error>(())
+    /// ```
+    pub fn excel_eu() -> Self {
+        let mut builder = Self::new();
+        builder
+            .delimiter(b';')
+            .terminator(csv_core::Terminator::CRLF);
+        builder
+    }
+
+    /// Constructs a builder of ASCII-delimited text: fields delimited with the unit separator
+    /// (`0x1F`) and records terminated with the record separator (`0x1E`).
+    ///
+    /// Fields are never quoted, so quotes and line breaks are written as they are.
+    /// [`Error::QuotingRequired`](super::Error::QuotingRequired) is returned for a string
+    /// that contains a separator.
+    ///
+    /// # Example
+    /// ```
+    /// let mut writer = serde_csv_core::ser::WriterBuilder::ascii_delimited().build();
+    ///
+    /// let mut csv = [0; 16];
+    /// let nwritten = writer.serialize(&("\"a\"\n", 1), &mut csv)?;
+    ///
+    /// assert_eq!(&csv[..nwritten], b"\"a\"\n\x1F1\x1E");
+    /// # Ok::<(), serde_csv_core::ser::Error>(())
+    /// ```
+    pub fn ascii_delimited() -> Self {
+        let mut builder = Self::new();
+        // The quote is never written, so it's set to the delimiter to keep `"` an ordinary byte.
+        builder
+            .delimiter(0x1F)
+            .terminator(csv_core::Terminator::Any(0x1E))
+            .quote(0x1F)
+            .quote_policy(QuotePolicy::Never);
+        builder
+    }
+
+    /// Builds a writer.
+    pub fn build(&self) -> Writer {
+        let mut writer = Writer::from_builder(&self.inner);
+        writer.config = self.config;
+        writer.reset();
+        writer
+    }
+
+    /// Sets the field delimiter. Defaults to `,`.
+    pub fn delimiter(&mut self, delimiter: u8) -> &mut Self {
+        self.inner.delimiter(delimiter);
+        self
+    }
+
+    /// Sets the record terminator. Defaults to `\n`.
+    pub fn terminator(&mut self, terminator: csv_core::Terminator) -> &mut Self {
+        self.inner.terminator(terminator);
+        self
+    }
+
+    /// Sets the quoting of fields by csv-core. Defaults to [`csv_core::QuoteStyle::Necessary`].
+    ///
+    /// Strings, chars and byte slices are quoted according to [`WriterBuilder::quote_policy`]
+    /// as well.
+    pub fn quote_style(&mut self, style: csv_core::QuoteStyle) -> &mut Self {
+        self.inner.quote_style(style);
+        self
+    }
+
+    /// Sets the quote character. Defaults to `"`.
+    pub fn quote(&mut self, quote: u8) -> &mut Self {
+        self.inner.quote(quote);
+        self
+    }
+
+    /// Sets the escape character used instead of doubled quotes
+    /// when [`WriterBuilder::double_quote`] is disabled. Defaults to `\`.
+    pub fn escape(&mut self, escape: u8) -> &mut Self {
+        self.inner.escape(escape);
+        self
+    }
+
+    /// Sets whether quotes in a quoted field are written as two adjacent quotes.
+    /// Enabled by default.
+    pub fn double_quote(&mut self, yes: bool) -> &mut Self {
+        self.inner.double_quote(yes);
+        self
+    }
+
+    /// Sets the character that starts a comment line, so that fields containing it are quoted.
+    /// Disabled by default.
+    pub fn comment(&mut self, comment: Option<u8>) -> &mut Self {
+        self.inner.comment(comment);
+        self
+    }
+
+    /// See [`Writer::encoding`].
+    pub fn encoding(&mut self, encoding: Encoding) -> &mut Self {
+        self.config.encoding = encoding;
+        self
+    }
+
+    /// See [`Writer::escape_control_chars`].
+    pub fn escape_control_chars(&mut self, yes: bool) -> &mut Self {
+        self.config.escape_control_chars = yes;
+        self
+    }
+
+    /// See [`Writer::sanitize_formulas`].
+    pub fn sanitize_formulas(&mut self, yes: bool) -> &mut Self {
+        self.config.sanitize_formulas = yes;
+        self
+    }
+
+    /// See [`Writer::formula_guard`].
+    pub fn formula_guard(&mut self, guard: u8) -> &mut Self {
+        self.config.formula_guard = guard;
+        self
+    }
+
+    /// See [`Writer::null_literal`].
+    pub fn null_literal(&mut self, literal: Option<&'static [u8]>) -> &mut Self {
+        self.config.null_literal = literal.unwrap_or_default();
+        self
+    }
+
+    /// See [`Writer::quote_policy`].
+    pub fn quote_policy(&mut self, policy: QuotePolicy) -> &mut Self {
+        self.config.quote_policy = policy;
+        self
+    }
+
+    /// See [`Writer::bytes_mode`].
+    pub fn bytes_mode(&mut self, mode: BytesMode) -> &mut Self {
+        self.config.bytes_mode = mode;
+        self
+    }
+
+    /// See [`Writer::field_encoder`].
+    pub fn field_encoder(&mut self, encoder: Option<&'static dyn FieldEncoder>) -> &mut Self {
+        self.config.field_encoder = encoder;
+        self
+    }
+
+    /// See [`Writer::check_field_count`].
+    pub fn check_field_count(&mut self, yes: bool) -> &mut Self {
+        self.config.check_field_count = yes;
+        self
+    }
+
+    /// See [`Writer::checksum`].
+    pub fn checksum(&mut self, checksum: Option<Checksum>) -> &mut Self {
+        self.config.checksum = checksum;
+        self
+    }
+
+    /// See [`Writer::record_prefix`].
+    pub fn record_prefix(&mut self, prefix: Option<&'static [u8]>) -> &mut Self {
+        self.config.record_prefix = prefix;
+        self
+    }
+
+    /// See [`Writer::defer_terminator`].
+    pub fn defer_terminator(&mut self, yes: bool) -> &mut Self {
+        self.config.defer_terminator = yes;
+        self
+    }
+
+    /// See [`Writer::sequence_column`].
+    pub fn sequence_column(&mut self, start: Option<u32>) -> &mut Self {
+        self.config.sequence_start = start;
+        self
+    }
+
+    /// See [`Writer::sequence_column_name`].
+    pub fn sequence_column_name(&mut self, name: &'static str) -> &mut Self {
+        self.config.sequence_name = name;
+        self
+    }
+}
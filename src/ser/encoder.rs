@@ -0,0 +1,59 @@
+#[cfg(feature = "percent-encoding")]
+use super::hex_byte;
+use super::Result;
+
+/// Transformation applied to the contents of every field before it's quoted,
+/// set by [`Writer::field_encoder`](super::Writer::field_encoder).
+///
+/// A field may be passed to [`FieldEncoder::encode`] in several chunks,
+/// so the encoding of a byte must not depend on the bytes in other chunks.
+///
+/// Encoders are shared by reference, so they must be `Sync` to keep writers `Send` and `Sync`.
+pub trait FieldEncoder: core::fmt::Debug + Sync {
+    /// Passes the encoded `input` to `f` in chunks.
+    fn encode(&self, input: &[u8], f: &mut dyn FnMut(&[u8]) -> Result<()>) -> Result<()>;
+}
+
+/// Percent-encoding of all bytes except the unreserved characters of RFC 3986:
+/// ASCII letters, digits, `-`, `.`, `_` and `~`.
+///
+/// Bytes are written as `%` followed by two uppercase hexadecimal digits.
+///
+/// # Example
+/// ```
+/// use serde_csv_core::ser::PercentEncoder;
+///
+/// let mut writer = serde_csv_core::Writer::new();
+/// writer.field_encoder(Some(&PercentEncoder));
+///
+/// let mut csv = [0; 32];
+/// let nwritten = writer.serialize(&("a b,c", 1.5), &mut csv)?;
+///
+/// assert_eq!(&csv[..nwritten], b"a%20b%2Cc,1.5\n");
+/// # Ok::<(), serde_csv_core::ser::Error>(())
+/// ```
+#[cfg(feature = "percent-encoding")]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct PercentEncoder;
+
+#[cfg(feature = "percent-encoding")]
+impl FieldEncoder for PercentEncoder {
+    fn encode(&self, input: &[u8], f: &mut dyn FnMut(&[u8]) -> Result<()>) -> Result<()> {
+        let unreserved = |b: u8| b.is_ascii_alphanumeric() || b"-._~".contains(&b);
+        let mut start = 0;
+        for (i, &b) in input.iter().enumerate() {
+            if !unreserved(b) {
+                if start < i {
+                    f(&input[start..i])?;
+                }
+                let [hi, lo] = hex_byte(b);
+                f(&[b'%', hi, lo])?;
+                start = i + 1;
+            }
+        }
+        if start < input.len() {
+            f(&input[start..])?;
+        }
+        Ok(())
+    }
+}
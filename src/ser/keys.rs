@@ -0,0 +1,179 @@
+use super::{Config, Error, Output, Parts, Result, Serializer};
+use serde::Serialize;
+
+/// Keys of the maps written by [`Writer::serialize_keyed`](super::Writer::serialize_keyed).
+///
+/// `C` is the maximum number of keys and `B` is a capacity of a buffer
+/// that stores all the keys, in bytes.
+#[derive(Debug, Clone)]
+pub struct Keys<const C: usize, const B: usize> {
+    names: [u8; B],
+    ends: [usize; C],
+    len: usize,
+}
+
+impl<const C: usize, const B: usize> Default for Keys<C, B> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<const C: usize, const B: usize> Keys<C, B> {
+    /// Constructs an empty set of keys.
+    pub const fn new() -> Self {
+        Self {
+            names: [0; B],
+            ends: [0; C],
+            len: 0,
+        }
+    }
+
+    /// Returns the number of keys.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if there are no keys.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns the key at `index`, unless it isn't valid UTF-8.
+    pub fn get(&self, index: usize) -> Option<&str> {
+        let end = *self.ends[..self.len].get(index)?;
+        let start = match index {
+            0 => 0,
+            i => self.ends[i - 1],
+        };
+        core::str::from_utf8(&self.names[start..end]).ok()
+    }
+
+    /// Removes all the keys, so that the next map written with them starts with a header again.
+    pub fn clear(&mut self) {
+        self.len = 0;
+    }
+
+    /// Returns a cursor that stores the keys of a map if `record` is set,
+    /// or checks them against the stored ones otherwise.
+    pub(super) fn cursor(&mut self, record: bool) -> KeyCursor<'_> {
+        if record {
+            self.len = 0;
+        }
+        KeyCursor {
+            names: &mut self.names,
+            ends: &mut self.ends,
+            len: &mut self.len,
+            index: 0,
+            record,
+        }
+    }
+}
+
+/// A view of [`Keys`] that doesn't depend on their capacity.
+#[derive(Debug)]
+pub(super) struct KeyCursor<'a> {
+    names: &'a mut [u8],
+    ends: &'a mut [usize],
+    len: &'a mut usize,
+    index: usize,
+    record: bool,
+}
+
+impl KeyCursor<'_> {
+    /// Stores or checks the next key.
+    pub fn visit<T>(&mut self, key: &T) -> Result<()>
+    where
+        T: Serialize + ?Sized,
+    {
+        let index = self.index;
+        let start = match index {
+            0 => 0,
+            i => self.ends[i - 1],
+        };
+        if self.record {
+            if index == self.ends.len() {
+                return Err(Error::KeysOverflow);
+            }
+            let len =
+                render(key, Output::Slice(&mut self.names[start..])).map_err(|e| match e {
+                    Error::Overflow => Error::KeysOverflow,
+                    e => e,
+                })?;
+            self.ends[index] = start + len;
+            *self.len += 1;
+        } else {
+            if index >= *self.len {
+                return Err(Error::KeyMismatch { index });
+            }
+            let mut compare = Compare::new(&self.names[start..self.ends[index]]);
+            let len = render(key, Output::Parts(&mut compare))?;
+            if !compare.matches(len) {
+                return Err(Error::KeyMismatch { index });
+            }
+        }
+        self.index += 1;
+        Ok(())
+    }
+
+    /// Checks that no stored key was left out.
+    pub fn finish(&self) -> Result<()> {
+        if self.index != *self.len {
+            return Err(Error::KeyMismatch { index: self.index });
+        }
+        Ok(())
+    }
+}
+
+/// Writes a key to `output` as a single unquoted field, returning its length.
+fn render<T>(key: &T, mut output: Output<'_>) -> Result<usize>
+where
+    T: Serialize + ?Sized,
+{
+    let mut writer = csv_core::WriterBuilder::new()
+        .quote_style(csv_core::QuoteStyle::Never)
+        .build();
+    let mut serializer = Serializer::with_config(&mut writer, Config::default(), output.reborrow());
+    key.serialize(&mut serializer)?;
+    Ok(serializer.bytes_written())
+}
+
+/// Compares the bytes written to it with `expected`, passing them through a small window,
+/// so that a key can be checked without being stored.
+#[derive(Debug)]
+struct Compare<'a> {
+    expected: &'a [u8],
+    checked: usize,
+    equal: bool,
+    window: [u8; 16],
+}
+
+impl<'a> Compare<'a> {
+    fn new(expected: &'a [u8]) -> Self {
+        Self {
+            expected,
+            checked: 0,
+            equal: true,
+            window: [0; 16],
+        }
+    }
+
+    /// Compares the bytes written to the window up to `offset`.
+    fn check(&mut self, offset: usize) {
+        let written = &self.window[..offset - self.checked];
+        self.equal &= self.expected.get(self.checked..offset) == Some(written);
+        self.checked = offset;
+    }
+
+    /// Returns whether `len` bytes were written in total, all equal to the expected ones.
+    fn matches(&mut self, len: usize) -> bool {
+        self.check(len);
+        self.equal && len == self.expected.len()
+    }
+}
+
+impl Parts for Compare<'_> {
+    fn rest(&mut self, offset: usize) -> &mut [u8] {
+        self.check(offset);
+        &mut self.window
+    }
+}
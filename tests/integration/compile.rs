@@ -0,0 +1,31 @@
+#[test]
+fn assert_field_capacity() {
+    let t = trybuild::TestCases::new();
+    t.pass("tests/ui/field_capacity_pass.rs");
+}
+
+// Run with `cargo test -- --ignored` on the toolchain the snapshot was taken with.
+#[test]
+#[ignore = "the expected compiler output depends on the toolchain"]
+fn assert_field_capacity_fail() {
+    let t = trybuild::TestCases::new();
+    t.compile_fail("tests/ui/field_capacity_fail.rs");
+}
+
+/// Fails to compile unless `T` is `Send` and `Sync`.
+fn assert_send_sync<T: Send + Sync>() {}
+
+#[test]
+fn send_sync() {
+    use serde_csv_core::{de, ser};
+
+    assert_send_sync::<serde_csv_core::Reader<16>>();
+    assert_send_sync::<serde_csv_core::Writer>();
+    assert_send_sync::<de::ReaderBuilder>();
+    assert_send_sync::<ser::WriterBuilder>();
+    assert_send_sync::<de::Headers<4, 32>>();
+    assert_send_sync::<ser::Keys<4, 32>>();
+    assert_send_sync::<de::Error>();
+    assert_send_sync::<ser::Error>();
+    assert_send_sync::<serde_csv_core::Error>();
+}
@@ -1,4 +1,5 @@
-use serde_csv_core::de::{Error, Reader};
+use serde_csv_core::de::{DuplicateHeaders, Error, FieldInterner, Headers, Reader, Snippet};
+use serde_csv_core::Checksum;
 
 #[test]
 fn bool_true() {
@@ -27,7 +28,7 @@ fn bool_empty() {
 
     let result = reader.deserialize::<bool>(&input[..]);
 
-    assert_eq!(result, Err(Error::InvalidBool))
+    assert_eq!(result, Err(Error::InvalidBool(Snippet::new(b""))))
 }
 
 #[test]
@@ -67,7 +68,44 @@ fn i8_invalid() {
 
     let result = reader.deserialize::<i8>(&input[..]);
 
-    assert_eq!(result, Err(Error::InvalidInt))
+    assert_eq!(result, Err(Error::InvalidInt(Snippet::new(b"256"))))
+}
+
+#[test]
+fn int_trailing_garbage() {
+    let mut reader: Reader<8> = Reader::new();
+
+    for input in ["12x", "12.0", "1 2", "12-", "0x1f"] {
+        assert_eq!(
+            reader.deserialize::<i32>(input.as_bytes()),
+            Err(Error::InvalidInt(Snippet::new(input.as_bytes()))),
+            "{input}"
+        );
+    }
+}
+
+#[test]
+fn int_plus_sign() {
+    let input = b"+12";
+    let mut reader: Reader<4> = Reader::new();
+
+    let result = reader.deserialize::<i32>(&input[..]);
+
+    assert_eq!(result, Ok((12, 3)))
+}
+
+#[test]
+fn int_sign_only() {
+    let mut reader: Reader<8> = Reader::new();
+
+    assert_eq!(
+        reader.deserialize::<i32>(b"-\n"),
+        Err(Error::InvalidInt(Snippet::new(b"-")))
+    );
+    assert_eq!(
+        reader.deserialize::<u32>(b"+\n"),
+        Err(Error::InvalidInt(Snippet::new(b"+")))
+    );
 }
 
 #[test]
@@ -150,7 +188,7 @@ fn empty_records() {
 
     let result = reader.deserialize::<(i32, i32)>(&input[..]);
 
-    assert_eq!(result, Err(Error::InvalidInt))
+    assert_eq!(result, Err(Error::InvalidInt(Snippet::new(b""))))
 }
 
 #[test]
@@ -252,7 +290,7 @@ fn compound_missing_fields() {
 
     let result = reader.deserialize::<Data>(&input[..]);
 
-    assert_eq!(result, Err(Error::Custom));
+    assert_eq!(result, Err(Error::InvalidLength { len: 4 }));
 }
 
 #[test]
@@ -266,9 +304,6 @@ fn array_too_many_fields() {
 }
 
 #[test]
-#[should_panic(
-    expected = "not implemented: `VariantAccess::newtype_variant_seed` is not supported"
-)]
 fn stateful_enum() {
     #[derive(Debug, PartialEq, Eq, serde::Deserialize)]
     struct A {
@@ -296,3 +331,3728 @@ fn stateful_enum() {
     let (value, _) = reader.deserialize::<Data>(&input[nread..]).unwrap();
     assert_eq!(value, Data::B(B { x: 0 }));
 }
+
+#[test]
+fn from_vec() {
+    let input: heapless::Vec<u8, 8> = heapless::Vec::from_slice(b"1,2\n3,4\n").unwrap();
+    let mut reader: Reader<2> = Reader::new();
+
+    let result = reader.deserialize_from_vec::<(i32, i32), 8>(&input);
+
+    assert_eq!(result, Ok(((1, 2), 4)))
+}
+
+#[test]
+fn lossy_utf8_continuation_byte() {
+    let input = b"a\x80b,\xbf";
+    let mut reader: Reader<8> = Reader::new();
+    reader.lossy_utf8(true);
+
+    let result = reader.deserialize::<(heapless::String<8>, heapless::String<8>)>(&input[..]);
+
+    assert_eq!(result, Ok((("a\u{FFFD}b".into(), "\u{FFFD}".into()), 5)))
+}
+
+#[test]
+fn lossy_utf8_overlong() {
+    // overlong encoding of '/'
+    let input = b"\xc0\xaf";
+    let mut reader: Reader<8> = Reader::new();
+    reader.lossy_utf8(true);
+
+    let result = reader.deserialize::<heapless::String<8>>(&input[..]);
+
+    assert_eq!(result, Ok(("\u{FFFD}\u{FFFD}".into(), 2)))
+}
+
+#[test]
+fn lossy_utf8_truncated() {
+    let input = b"\xe2\x82";
+    let mut reader: Reader<8> = Reader::new();
+    reader.lossy_utf8(true);
+
+    let result = reader.deserialize::<heapless::String<8>>(&input[..]);
+
+    assert_eq!(result, Ok(("\u{FFFD}".into(), 2)))
+}
+
+#[test]
+fn lossy_utf8_char() {
+    let input = b"\xff";
+    let mut reader: Reader<3> = Reader::new();
+    reader.lossy_utf8(true);
+
+    let result = reader.deserialize::<char>(&input[..]);
+
+    assert_eq!(result, Ok(('\u{FFFD}', 1)))
+}
+
+#[test]
+fn lossy_utf8_overflow() {
+    let input = b"a\x80b";
+    let mut reader: Reader<4> = Reader::new();
+    reader.lossy_utf8(true);
+
+    let result = reader.deserialize::<heapless::String<8>>(&input[..]);
+
+    assert_eq!(result, Err(Error::Overflow))
+}
+
+#[test]
+fn lossy_utf8_disabled() {
+    let input = b"a\x80b";
+    let mut reader: Reader<8> = Reader::new();
+
+    let result = reader.deserialize::<heapless::String<8>>(&input[..]);
+
+    assert_eq!(result, Err(Error::InvalidUtf8String))
+}
+
+#[test]
+fn latin1_accented() {
+    let input = b"Cr\xe8me br\xfbl\xe9e,\xc5ngstr\xf6m";
+    let mut reader: Reader<16> = Reader::new();
+    reader.encoding(serde_csv_core::Encoding::Latin1);
+
+    let result = reader.deserialize::<(heapless::String<16>, heapless::String<16>)>(&input[..]);
+
+    assert_eq!(result, Ok((("Crème brûlée".into(), "Ångström".into()), 21)))
+}
+
+#[test]
+fn latin1_std_string() {
+    let input = b"caf\xe9\n";
+    let mut reader: Reader<8> = Reader::new();
+    reader.encoding(serde_csv_core::Encoding::Latin1);
+
+    let result = reader.deserialize::<String>(&input[..]);
+
+    assert_eq!(result, Ok(("café".to_string(), 5)))
+}
+
+#[test]
+fn latin1_numbers_unaffected() {
+    let input = b"\xe9t\xe9,-12,1.5";
+    let mut reader: Reader<8> = Reader::new();
+    reader.encoding(serde_csv_core::Encoding::Latin1);
+
+    let result = reader.deserialize::<(heapless::String<8>, i8, f32)>(&input[..]);
+
+    assert_eq!(result, Ok((("été".into(), -12, 1.5), 11)))
+}
+
+#[test]
+fn latin1_char() {
+    let input = b"\xe9";
+    let mut reader: Reader<2> = Reader::new();
+    reader.encoding(serde_csv_core::Encoding::Latin1);
+
+    let result = reader.deserialize::<char>(&input[..]);
+
+    assert_eq!(result, Ok(('é', 1)))
+}
+
+#[test]
+fn latin1_overflow() {
+    // fits in the buffer before transcoding, but not after
+    let input = b"\xe9\xe9\xe9";
+    let mut reader: Reader<4> = Reader::new();
+    reader.encoding(serde_csv_core::Encoding::Latin1);
+
+    let result = reader.deserialize::<heapless::String<8>>(&input[..]);
+
+    assert_eq!(result, Err(Error::TranscodedTooLong { len: 6 }))
+}
+
+#[test]
+fn windows1252() {
+    let input = b"\x80\x99,\x80";
+    let mut reader: Reader<8> = Reader::new();
+    reader.encoding(serde_csv_core::Encoding::Windows1252);
+
+    let result = reader.deserialize::<(heapless::String<8>, heapless::String<8>)>(&input[..]);
+
+    assert_eq!(result, Ok((("€™".into(), "€".into()), 4)))
+}
+
+#[test]
+fn unsanitize_formulas() {
+    let input = b"'=A1,'+1,'-1,'@x,'\tx";
+    let mut reader: Reader<8> = Reader::new();
+    reader.unsanitize_formulas(true);
+
+    let result = reader.deserialize::<[heapless::String<8>; 5]>(&input[..]);
+
+    assert_eq!(
+        result,
+        Ok((
+            [
+                "=A1".into(),
+                "+1".into(),
+                "-1".into(),
+                "@x".into(),
+                "\tx".into()
+            ],
+            20
+        ))
+    )
+}
+
+#[test]
+fn unsanitize_formulas_keeps_legitimate_quotes() {
+    let input = b"'quoted',',''";
+    let mut reader: Reader<9> = Reader::new();
+    reader.unsanitize_formulas(true);
+
+    let result = reader.deserialize::<[heapless::String<8>; 3]>(&input[..]);
+
+    assert_eq!(
+        result,
+        Ok((["'quoted'".into(), "'".into(), "''".into()], 13))
+    )
+}
+
+#[test]
+fn unsanitize_formulas_disabled() {
+    let input = b"'=A1";
+    let mut reader: Reader<4> = Reader::new();
+
+    let result = reader.deserialize::<heapless::String<4>>(&input[..]);
+
+    assert_eq!(result, Ok(("'=A1".into(), 4)))
+}
+
+#[test]
+fn unsanitize_formulas_roundtrip() {
+    let values = ["=1+2", "'=1+2", "''-3", "'", "'x", "-", "plain", "@"];
+
+    let mut writer = serde_csv_core::Writer::new();
+    writer.sanitize_formulas(true);
+    let mut reader: Reader<8> = Reader::new();
+    reader.unsanitize_formulas(true);
+
+    for value in values {
+        let mut buf = [0; 16];
+        let nwritten = writer.serialize(value, &mut buf).unwrap();
+
+        let result = reader.deserialize::<heapless::String<8>>(&buf[..nwritten]);
+
+        assert_eq!(result, Ok((value.into(), nwritten)));
+    }
+}
+
+#[test]
+fn unsanitize_formulas_custom_guard() {
+    let values = [" =1", "=1", " x"];
+
+    let mut writer = serde_csv_core::Writer::new();
+    writer.sanitize_formulas(true).formula_guard(b' ');
+    let mut reader: Reader<8> = Reader::new();
+    reader.unsanitize_formulas(true).formula_guard(b' ');
+
+    for value in values {
+        let mut buf = [0; 16];
+        let nwritten = writer.serialize(value, &mut buf).unwrap();
+
+        let result = reader.deserialize::<heapless::String<8>>(&buf[..nwritten]);
+
+        assert_eq!(result, Ok((value.into(), nwritten)));
+    }
+}
+
+#[test]
+fn field_too_long() {
+    let input = b"0123456789";
+    let mut reader: Reader<16> = Reader::new();
+
+    let result = reader.deserialize::<heapless::String<8>>(&input[..]);
+
+    assert_eq!(result, Err(Error::FieldTooLong { len: 10 }))
+}
+
+#[test]
+fn field_too_long_quoted() {
+    #[derive(Debug, PartialEq, Eq, serde::Deserialize)]
+    struct Record {
+        id: u8,
+        name: heapless::String<8>,
+    }
+
+    let input = b"1,\"a,b,\"\"c\"\",d\"\n";
+    let mut reader: Reader<16> = Reader::new();
+
+    let result = reader.deserialize::<Record>(&input[..]);
+
+    assert_eq!(result, Err(Error::FieldTooLong { len: 9 }))
+}
+
+#[test]
+fn field_fits() {
+    let input = b"01234567";
+    let mut reader: Reader<16> = Reader::new();
+
+    let result = reader.deserialize::<heapless::String<8>>(&input[..]);
+
+    assert_eq!(result, Ok(("01234567".into(), 8)))
+}
+
+#[test]
+fn array_length_mismatch_is_not_field_too_long() {
+    let input = b"0,1\n";
+    let mut reader: Reader<2> = Reader::new();
+
+    let result = reader.deserialize::<[u8; 3]>(&input[..]);
+
+    assert_eq!(result, Err(Error::InvalidLength { len: 2 }))
+}
+
+#[test]
+fn ignored_any_consumes_whole_record() {
+    let input = b"a,b,c\n1,22,333\n\"x,y\",z\n";
+    let mut reader: Reader<8> = Reader::new();
+
+    let mut nread = 0;
+    let mut records = 0;
+    while nread < input.len() {
+        let (_, n) = reader
+            .deserialize::<serde::de::IgnoredAny>(&input[nread..])
+            .unwrap();
+        nread += n;
+        records += 1;
+    }
+
+    assert_eq!(records, 3);
+    assert_eq!(nread, input.len());
+}
+
+#[test]
+fn ignored_any_without_terminator() {
+    let input = b"1,2,3";
+    let mut reader: Reader<8> = Reader::new();
+
+    let result = reader.deserialize::<serde::de::IgnoredAny>(&input[..]);
+
+    assert_eq!(result, Ok((serde::de::IgnoredAny, 5)))
+}
+
+#[test]
+fn max_fields_per_record_seq() {
+    let mut input = [b','; 1000];
+    input[999] = b'\n';
+    let mut reader: Reader<8> = Reader::new();
+    reader.max_fields_per_record(16);
+
+    let result = reader.deserialize::<heapless::Vec<heapless::String<8>, 2000>>(&input[..]);
+
+    assert_eq!(result, Err(Error::TooManyFields))
+}
+
+#[test]
+fn max_fields_per_record_ignored_any() {
+    let mut input = [b','; 1000];
+    input[999] = b'\n';
+    let mut reader: Reader<8> = Reader::new();
+    reader.max_fields_per_record(16);
+
+    let result = reader.deserialize::<serde::de::IgnoredAny>(&input[..]);
+
+    assert_eq!(result, Err(Error::TooManyFields))
+}
+
+#[test]
+fn max_fields_per_record_struct() {
+    #[derive(Debug, PartialEq, serde::Deserialize)]
+    struct Record {
+        a: u8,
+        b: u8,
+        c: u8,
+    }
+    let mut reader: Reader<8> = Reader::new();
+
+    reader.max_fields_per_record(3);
+    let result = reader.deserialize::<Record>(b"1,2,3\n");
+    assert_eq!(result, Ok((Record { a: 1, b: 2, c: 3 }, 6)));
+
+    let result = reader.deserialize::<Record>(b"1,2,3,,,,,,,,,,,,,,,,,,,,,,,,,\n");
+    assert_eq!(result, Err(Error::TooManyFields));
+
+    reader.max_fields_per_record(2);
+    let result = reader.deserialize::<Record>(b"1,2,3\n");
+    assert_eq!(result, Err(Error::TooManyFields));
+}
+
+#[test]
+fn max_fields_per_record_unread_fields() {
+    let input = b"1,2,3,4,5,6,7\n8,9\n";
+    let mut reader: Reader<8> = Reader::new();
+    reader.max_fields_per_record(3);
+
+    let wide = reader.deserialize::<(u8, u8)>(&input[..]);
+    let mut nread = reader.last_bytes_read();
+    nread += reader.skip_record(&input[nread..]);
+    let narrow = reader.deserialize::<(u8, u8)>(&input[nread..]);
+
+    assert_eq!(wide, Err(Error::TooManyFields));
+    assert_eq!(narrow, Ok(((8, 9), 4)));
+}
+
+#[test]
+fn max_fields_per_record_default_unlimited() {
+    let mut input = [b','; 1000];
+    input[999] = b'\n';
+    let mut reader: Reader<8> = Reader::new();
+
+    let result = reader.deserialize::<serde::de::IgnoredAny>(&input[..]);
+
+    assert_eq!(result, Ok((serde::de::IgnoredAny, 1000)))
+}
+
+#[derive(Debug, PartialEq, Eq, serde::Deserialize)]
+struct PaddedRecord {
+    name: heapless::String<8>,
+    count: Option<u32>,
+    ratio: (Option<u8>, Option<u8>),
+    note: heapless::String<8>,
+}
+
+#[test]
+fn pad_short_records() {
+    let input = b"abc,7\n";
+    let mut reader: Reader<8> = Reader::new();
+    reader.pad_short_records(true);
+
+    let result = reader.deserialize::<PaddedRecord>(&input[..]);
+
+    assert_eq!(
+        result,
+        Ok((
+            PaddedRecord {
+                name: "abc".into(),
+                count: Some(7),
+                ratio: (None, None),
+                note: "".into(),
+            },
+            6
+        ))
+    )
+}
+
+#[test]
+fn pad_short_records_inside_tuple() {
+    let input = b"abc,7,1";
+    let mut reader: Reader<8> = Reader::new();
+    reader.pad_short_records(true);
+
+    let result = reader.deserialize::<PaddedRecord>(&input[..]);
+
+    assert_eq!(
+        result,
+        Ok((
+            PaddedRecord {
+                name: "abc".into(),
+                count: Some(7),
+                ratio: (Some(1), None),
+                note: "".into(),
+            },
+            7
+        ))
+    )
+}
+
+#[test]
+fn pad_short_records_needs_empty_compatible_type() {
+    let input = b"1\n";
+    let mut reader: Reader<8> = Reader::new();
+    reader.pad_short_records(true);
+
+    let result = reader.deserialize::<(u8, u8)>(&input[..]);
+
+    assert_eq!(result, Err(Error::InvalidInt(Snippet::new(b""))))
+}
+
+#[test]
+fn pad_short_records_disabled() {
+    let input = b"abc,7\n";
+    let mut reader: Reader<8> = Reader::new();
+
+    let result = reader.deserialize::<PaddedRecord>(&input[..]);
+
+    assert_eq!(result, Err(Error::InvalidLength { len: 2 }))
+}
+
+#[test]
+fn ignored_fields_between_live_columns() {
+    #[derive(Debug, PartialEq, Eq, serde::Deserialize)]
+    struct Record {
+        id: u8,
+        position: serde_csv_core::de::IgnoredFields<2>,
+        population: u32,
+    }
+    let input = b"1,50,19,766683\n";
+    let mut reader: Reader<8> = Reader::new();
+
+    let result = reader.deserialize::<Record>(&input[..]);
+
+    assert_eq!(
+        result,
+        Ok((
+            Record {
+                id: 1,
+                position: serde_csv_core::de::IgnoredFields,
+                population: 766_683,
+            },
+            15
+        ))
+    )
+}
+
+#[test]
+fn ignored_fields_nested_in_tuple() {
+    let input = b"1,x,\"y,z\",2,3\n";
+    let mut reader: Reader<8> = Reader::new();
+
+    let result =
+        reader.deserialize::<(u8, (serde_csv_core::de::IgnoredFields<2>, u8), u8)>(&input[..]);
+
+    assert_eq!(
+        result,
+        Ok(((1, (serde_csv_core::de::IgnoredFields, 2), 3), 14))
+    )
+}
+
+#[test]
+fn ignored_fields_short_record() {
+    let input = b"1,50\n";
+    let mut reader: Reader<8> = Reader::new();
+
+    let result = reader.deserialize::<(u8, serde_csv_core::de::IgnoredFields<2>, u8)>(&input[..]);
+
+    assert_eq!(result, Err(Error::InvalidLength { len: 2 }))
+}
+
+#[test]
+fn ignored_any_spans_single_field() {
+    let input = b"1,50,19\n";
+    let mut reader: Reader<8> = Reader::new();
+
+    let result = reader.deserialize::<(u8, serde::de::IgnoredAny, u8)>(&input[..]);
+
+    assert_eq!(result, Ok(((1, serde::de::IgnoredAny, 19), 8)))
+}
+
+#[test]
+fn ignored_any_longer_than_buffer() {
+    let input = b"1,\"a field, longer than the buffer\",19\n";
+    let mut reader: Reader<4> = Reader::new();
+
+    let result = reader.deserialize::<(u8, serde::de::IgnoredAny, u8)>(&input[..]);
+
+    assert_eq!(result, Ok(((1, serde::de::IgnoredAny, 19), input.len())))
+}
+
+#[test]
+fn nested_option_some_some() {
+    let input = b"0,1,2\n";
+    let mut reader: Reader<8> = Reader::new();
+
+    let result = reader.deserialize::<(u8, Option<Option<u8>>, u8)>(&input[..]);
+
+    assert_eq!(result, Ok(((0, Some(Some(1)), 2), 6)))
+}
+
+#[test]
+fn nested_option_empty() {
+    let input = b"0,,2\n";
+    let mut reader: Reader<8> = Reader::new();
+
+    let result = reader.deserialize::<(u8, Option<Option<u8>>, u8)>(&input[..]);
+
+    assert_eq!(result, Ok(((0, None, 2), 5)))
+}
+
+#[test]
+fn nested_option_quoted_empty() {
+    let input = b"0,\"\",2\n";
+    let mut reader: Reader<8> = Reader::new();
+
+    let result = reader.deserialize::<(u8, Option<Option<u8>>, u8)>(&input[..]);
+
+    assert_eq!(result, Ok(((0, None, 2), 7)))
+}
+
+#[test]
+fn nested_option_quoted_empty_is_some() {
+    let input = b"0,\"\",,1\n";
+    let mut reader: Reader<8> = Reader::new();
+    reader.quoted_empty_is_none(false);
+
+    let result = reader.deserialize::<[Option<Option<u8>>; 4]>(&input[..]);
+
+    assert_eq!(
+        result,
+        Ok(([Some(Some(0)), Some(None), None, Some(Some(1))], 8))
+    )
+}
+
+#[test]
+fn quoted_empty_is_some() {
+    let mut reader: Reader<8> = Reader::new();
+    reader.quoted_empty_is_none(false);
+
+    let strings = reader
+        .deserialize::<(Option<heapless::String<8>>, Option<heapless::String<8>>)>(b"\"\",\n");
+    let int = reader.deserialize::<Option<u8>>(b"\"\"\n");
+
+    assert_eq!(strings, Ok(((Some("".into()), None), 4)));
+    assert_eq!(int, Err(Error::InvalidInt(Snippet::new(b""))));
+}
+
+#[test]
+fn quoted_empty_after_skipped_lines() {
+    let input = b"\n\n,1\n";
+    let mut reader: Reader<8> = Reader::new();
+    reader.quoted_empty_is_none(false);
+
+    let result = reader.deserialize::<(Option<Option<u8>>, u8)>(&input[..]);
+
+    assert_eq!(result, Ok(((None, 1), 5)))
+}
+
+#[test]
+fn quoted_empty_is_some_fixed_widths() {
+    let mut reader: Reader<8> = Reader::new();
+    reader
+        .quoted_empty_is_none(false)
+        .fixed_widths(Some(&[1, 2, 1]))
+        .trim_padding(true);
+
+    let result = reader.deserialize::<(u8, Option<Option<u8>>, u8)>(b"0  1\n");
+
+    assert_eq!(result, Ok(((0, None, 1), 5)));
+}
+
+#[test]
+fn nested_option_quoted_empty_with_null_literal() {
+    let input = b"\\N,\"\",\n";
+    let mut reader: Reader<8> = Reader::new();
+    reader.null_literal(Some(b"\\N"));
+
+    let result = reader.deserialize::<[Option<Option<heapless::String<8>>>; 3]>(&input[..]);
+
+    assert_eq!(result, Ok(([None, Some(None), Some(Some("".into()))], 7)))
+}
+
+#[test]
+fn none_between_fields() {
+    let input = b"0,,2\n";
+    let mut reader: Reader<8> = Reader::new();
+
+    let result = reader.deserialize::<(u8, Option<u8>, u8)>(&input[..]);
+
+    assert_eq!(result, Ok(((0, None, 2), 5)))
+}
+
+#[test]
+fn none_first_and_last_fields() {
+    let input = b",1,\n,2,\n";
+    let mut reader: Reader<8> = Reader::new();
+
+    let (first, nread) = reader
+        .deserialize::<(Option<u8>, u8, Option<u8>)>(&input[..])
+        .unwrap();
+    let second = reader.deserialize::<(Option<u8>, u8, Option<u8>)>(&input[nread..]);
+
+    assert_eq!(first, (None, 1, None));
+    assert_eq!(second, Ok(((None, 2, None), 4)));
+}
+
+#[test]
+fn offsets_of_records_ending_with_none() {
+    let input = b"1,\n2,3\n4,\n";
+    let mut reader: Reader<8> = Reader::new();
+
+    let mut records = [(0, None); 3];
+    let mut offsets = [0; 3];
+    let mut nread = 0;
+    for (record, offset) in records.iter_mut().zip(&mut offsets) {
+        let (value, n) = reader
+            .deserialize::<(u8, Option<u8>)>(&input[nread..])
+            .unwrap();
+        nread += n;
+        *record = value;
+        *offset = nread;
+    }
+
+    assert_eq!(records, [(1, None), (2, Some(3)), (4, None)]);
+    assert_eq!(offsets, [3, 7, 10]);
+}
+
+#[test]
+fn offsets_of_records_ending_with_nested_option() {
+    let input = b"1,\n2,3\n";
+    let mut reader: Reader<8> = Reader::new();
+
+    let (first, n1) = reader
+        .deserialize::<(u8, Option<Option<u8>>)>(&input[..])
+        .unwrap();
+    let (second, n2) = reader
+        .deserialize::<(u8, Option<Option<u8>>)>(&input[n1..])
+        .unwrap();
+
+    assert_eq!((first, n1), ((1, None), 3));
+    assert_eq!((second, n2), ((2, Some(Some(3))), 4));
+}
+
+#[test]
+fn offsets_of_records_consisting_of_none() {
+    let input = b"\"\"\n1\n";
+    let mut reader: Reader<8> = Reader::new();
+
+    let (first, n1) = reader.deserialize::<Option<u8>>(&input[..]).unwrap();
+    let (second, n2) = reader.deserialize::<Option<u8>>(&input[n1..]).unwrap();
+
+    assert_eq!((first, n1), (None, 3));
+    assert_eq!((second, n2), (Some(1), 2));
+}
+
+#[test]
+fn last_bytes_read_early_error() {
+    let input = b"x,2,3\n4,5,6\n";
+    let mut reader: Reader<8> = Reader::new();
+
+    let result = reader.deserialize::<(u8, u8, u8)>(&input[..]);
+    assert_eq!(result, Err(Error::InvalidInt(Snippet::new(b"x"))));
+    let mut nread = reader.last_bytes_read();
+    assert_eq!(nread, 2);
+
+    nread += reader.skip_record(&input[nread..]);
+    assert_eq!(nread, 6);
+
+    let result = reader.deserialize::<(u8, u8, u8)>(&input[nread..]);
+    assert_eq!(result, Ok(((4, 5, 6), 6)));
+}
+
+#[test]
+fn last_bytes_read_late_error() {
+    let input = b"1,2,x\n4,5,6\n";
+    let mut reader: Reader<8> = Reader::new();
+
+    let result = reader.deserialize::<(u8, u8, u8)>(&input[..]);
+    assert_eq!(result, Err(Error::InvalidInt(Snippet::new(b"x"))));
+    let mut nread = reader.last_bytes_read();
+    assert_eq!(nread, 6);
+
+    nread += reader.skip_record(&input[nread..]);
+    assert_eq!(nread, 6);
+
+    let result = reader.deserialize::<(u8, u8, u8)>(&input[nread..]);
+    assert_eq!(result, Ok(((4, 5, 6), 6)));
+}
+
+#[test]
+fn last_bytes_read_overflow() {
+    let input = b"1,too long for buffer,3\n4,5,6\n";
+    let mut reader: Reader<4> = Reader::new();
+
+    let result = reader.deserialize::<(u8, heapless::String<4>, u8)>(&input[..]);
+    assert_eq!(result, Err(Error::Overflow));
+    let mut nread = reader.last_bytes_read();
+
+    nread += reader.skip_record(&input[nread..]);
+    assert_eq!(nread, 24);
+
+    let result = reader.deserialize::<(u8, heapless::String<4>, u8)>(&input[nread..]);
+    assert_eq!(result, Ok(((4, "5".into(), 6), 6)));
+}
+
+#[test]
+fn last_bytes_read_success() {
+    let input = b"1,2,3\n";
+    let mut reader: Reader<8> = Reader::new();
+
+    let (_, nread) = reader.deserialize::<(u8, u8, u8)>(&input[..]).unwrap();
+
+    assert_eq!(reader.last_bytes_read(), nread);
+}
+
+#[test]
+fn skip_record_after_extra_fields() {
+    let input = b"1,2,3\n4,5,6\n";
+    let mut reader: Reader<8> = Reader::new();
+
+    let (_, mut nread) = reader.deserialize::<(u8, u8)>(&input[..]).unwrap();
+    nread += reader.skip_record(&input[nread..]);
+    assert_eq!(nread, 6);
+    nread += reader.skip_record(&input[nread..]);
+    assert_eq!(nread, 6);
+
+    let result = reader.deserialize::<(u8, u8, u8)>(&input[nread..]);
+    assert_eq!(result, Ok(((4, 5, 6), 6)));
+}
+
+#[derive(Debug, PartialEq, serde::Deserialize)]
+enum Event {
+    Temperature { timestamp: u32, celsius: f32 },
+    Door(u32, bool),
+    Reset(u32),
+}
+
+#[test]
+fn enum_tag_column() {
+    let input = b"100,Temperature,21.5\n200,Door,true\n300,Reset\n";
+    let mut reader: Reader<16> = Reader::new();
+    reader.enum_tag_column(1);
+
+    let (first, n1) = reader.deserialize::<Event>(&input[..]).unwrap();
+    let (second, n2) = reader.deserialize::<Event>(&input[n1..]).unwrap();
+    let (third, n3) = reader.deserialize::<Event>(&input[n1 + n2..]).unwrap();
+
+    assert_eq!(
+        first,
+        Event::Temperature {
+            timestamp: 100,
+            celsius: 21.5
+        }
+    );
+    assert_eq!(second, Event::Door(200, true));
+    assert_eq!(third, Event::Reset(300));
+    assert_eq!((n1, n2, n3), (21, 14, 10));
+}
+
+#[test]
+fn enum_tag_column_last() {
+    let input = b"200,true,Door\n1,2\n";
+    let mut reader: Reader<16> = Reader::new();
+    reader.enum_tag_column(2);
+
+    let (event, nread) = reader.deserialize::<Event>(&input[..]).unwrap();
+
+    assert_eq!((event, nread), (Event::Door(200, true), 14));
+}
+
+#[test]
+fn enum_tag_column_nested() {
+    let input = b"7,100,Temperature,21.5\n";
+    let mut reader: Reader<16> = Reader::new();
+    reader.enum_tag_column(1);
+
+    let result = reader.deserialize::<(u8, Event)>(&input[..]);
+
+    assert_eq!(
+        result,
+        Ok((
+            (
+                7,
+                Event::Temperature {
+                    timestamp: 100,
+                    celsius: 21.5
+                }
+            ),
+            23
+        ))
+    );
+}
+
+#[test]
+fn enum_tag_column_missing() {
+    let input = b"100\n";
+    let mut reader: Reader<16> = Reader::new();
+    reader.enum_tag_column(1);
+
+    let result = reader.deserialize::<Event>(&input[..]);
+
+    assert_eq!(result, Err(Error::InvalidLength { len: 1 }));
+}
+
+#[test]
+fn enum_tag_column_past_record_end() {
+    let mut reader: Reader<16> = Reader::new();
+    reader.enum_tag_column(3);
+
+    let nested = reader.deserialize::<(u8, Event)>(b"7,100,true\n8,9,Door,1\n");
+    let unterminated = reader.deserialize::<Event>(b"100,true");
+
+    assert_eq!(nested, Err(Error::InvalidLength { len: 3 }));
+    assert_eq!(unterminated, Err(Error::InvalidLength { len: 2 }));
+}
+
+#[test]
+fn enum_tag_column_in_option() {
+    let input = b"100,Reset,7\n";
+    let mut reader: Reader<16> = Reader::new();
+    reader.enum_tag_column(1);
+
+    let result = reader.deserialize::<(Option<Event>, i32)>(&input[..]);
+
+    assert_eq!(result, Ok(((Some(Event::Reset(100)), 7), 12)))
+}
+
+#[test]
+fn deserialize_rest() {
+    let input = b"1,2\n3,4\n";
+    let mut reader: Reader<8> = Reader::new();
+
+    let (first, rest) = reader.deserialize_rest::<(u8, u8)>(&input[..]).unwrap();
+    assert_eq!((first, rest), ((1, 2), &b"3,4\n"[..]));
+
+    let (second, rest) = reader.deserialize_rest::<(u8, u8)>(rest).unwrap();
+    assert_eq!((second, rest), ((3, 4), &b""[..]));
+}
+
+#[test]
+fn deserialize_rest_without_terminator() {
+    let input = b"1,2\n3,4";
+    let mut reader: Reader<8> = Reader::new();
+
+    let (first, rest) = reader.deserialize_rest::<(u8, u8)>(&input[..]).unwrap();
+    assert_eq!((first, rest), ((1, 2), &b"3,4"[..]));
+
+    let (second, rest) = reader.deserialize_rest::<(u8, u8)>(rest).unwrap();
+    assert_eq!((second, rest), ((3, 4), &b""[..]));
+}
+
+#[test]
+fn deserialize_rest_crlf() {
+    let input = b"1,2\r\n3,4\r\n";
+    let mut reader: Reader<8> = Reader::new();
+
+    let (first, rest) = reader.deserialize_rest::<(u8, u8)>(&input[..]).unwrap();
+    assert_eq!((first, rest), ((1, 2), &b"3,4\r\n"[..]));
+}
+
+#[derive(Debug, PartialEq, serde::Deserialize, serde::Serialize)]
+struct Sample {
+    ts: u32,
+    values: heapless::Vec<f32, 4>,
+}
+
+#[test]
+fn trailing_vec_roundtrip() {
+    let samples = [
+        Sample {
+            ts: 1,
+            values: heapless::Vec::from_slice(&[0.5, 1.5, 2.5]).unwrap(),
+        },
+        Sample {
+            ts: 2,
+            values: heapless::Vec::new(),
+        },
+        Sample {
+            ts: 3,
+            values: heapless::Vec::from_slice(&[-1.0]).unwrap(),
+        },
+    ];
+    let mut writer = serde_csv_core::Writer::new();
+    let mut csv = [0; 64];
+    let mut nwritten = 0;
+    for sample in &samples {
+        nwritten += writer.serialize(sample, &mut csv[nwritten..]).unwrap();
+    }
+    assert_eq!(&csv[..nwritten], b"1,0.5,1.5,2.5\n2\n3,-1.0\n");
+
+    let mut reader: Reader<8> = Reader::new();
+    let mut rest = &csv[..nwritten];
+    for sample in &samples {
+        let (value, tail) = reader.deserialize_rest::<Sample>(rest).unwrap();
+        assert_eq!(&value, sample);
+        rest = tail;
+    }
+    assert!(rest.is_empty());
+}
+
+#[test]
+fn trailing_vec_too_long() {
+    let input = b"1,1,2,3,4,5\n";
+    let mut reader: Reader<8> = Reader::new();
+
+    let result = reader.deserialize::<Sample>(&input[..]);
+
+    assert_eq!(result, Err(Error::SeqTooLong { len: 5 }))
+}
+
+#[test]
+fn vec_not_last() {
+    #[derive(Debug, PartialEq, serde::Deserialize)]
+    struct Record {
+        values: heapless::Vec<u8, 4>,
+        last: u8,
+    }
+    let input = b"1,2,3\n";
+    let mut reader: Reader<8> = Reader::new();
+
+    let result = reader.deserialize::<Record>(&input[..]);
+
+    assert_eq!(result, Err(Error::SeqNotLast))
+}
+
+#[test]
+fn vec_element_too_long() {
+    let input = b"ab,abcdef\n";
+    let mut reader: Reader<8> = Reader::new();
+
+    let result = reader.deserialize::<heapless::Vec<heapless::String<4>, 4>>(&input[..]);
+
+    assert_eq!(result, Err(Error::FieldTooLong { len: 6 }))
+}
+
+const COUNTRIES: &[u8] =
+    b"Poland,Warsaw,38\nJapan,Tokyo,125\n\"Korea, South\",Seoul,51\nFrance,Paris,68";
+
+#[test]
+fn find_by_key_first() {
+    let mut reader: Reader<16> = Reader::new();
+
+    let result = reader
+        .find_by_key::<(heapless::String<16>, heapless::String<16>, u32)>(COUNTRIES, b"Poland");
+
+    assert_eq!(
+        result,
+        Ok(Some((("Poland".into(), "Warsaw".into(), 38), 17)))
+    );
+}
+
+#[test]
+fn find_by_key_quoted() {
+    let mut reader: Reader<16> = Reader::new();
+
+    let result = reader.find_by_key::<(heapless::String<16>, heapless::String<16>, u32)>(
+        COUNTRIES,
+        b"Korea, South",
+    );
+
+    assert_eq!(
+        result,
+        Ok(Some((("Korea, South".into(), "Seoul".into(), 51), 57)))
+    );
+}
+
+#[test]
+fn find_by_key_last() {
+    let mut reader: Reader<16> = Reader::new();
+
+    let result = reader
+        .find_by_key::<(heapless::String<16>, heapless::String<16>, u32)>(COUNTRIES, b"France");
+
+    assert_eq!(
+        result,
+        Ok(Some((
+            ("France".into(), "Paris".into(), 68),
+            COUNTRIES.len()
+        )))
+    );
+}
+
+#[test]
+fn find_by_key_prefix_is_not_a_match() {
+    let mut reader: Reader<16> = Reader::new();
+
+    let result = reader
+        .find_by_key::<(heapless::String<16>, heapless::String<16>, u32)>(COUNTRIES, b"Korea");
+
+    assert_eq!(result, Ok(None));
+}
+
+#[test]
+fn find_by_key_none() {
+    let mut reader: Reader<16> = Reader::new();
+
+    let result = reader
+        .find_by_key::<(heapless::String<16>, heapless::String<16>, u32)>(COUNTRIES, b"Germany");
+
+    assert_eq!(result, Ok(None));
+}
+
+#[test]
+fn find_by_key_long_fields() {
+    let input = b"a very long key that doesn't fit,1\nanother very long key that doesn't fit,2\n";
+    let mut reader: Reader<4> = Reader::new();
+
+    let result = reader.find_by_key::<(serde::de::IgnoredAny, u8)>(
+        input,
+        b"another very long key that doesn't fit",
+    );
+
+    assert_eq!(result, Ok(Some(((serde::de::IgnoredAny, 2), input.len()))));
+}
+
+#[test]
+fn find_by_key_invalid_record() {
+    let input = b"a,1\nb,x\nc,3\n";
+    let mut reader: Reader<8> = Reader::new();
+
+    let result = reader.find_by_key::<(heapless::String<4>, u8)>(input, b"b");
+    let mut nread = reader.last_bytes_read();
+    nread += reader.skip_record(&input[nread..]);
+    let next = reader.deserialize::<(heapless::String<4>, u8)>(&input[nread..]);
+
+    assert_eq!(result, Err(Error::InvalidInt(Snippet::new(b"x"))));
+    assert_eq!(nread, 8);
+    assert_eq!(next, Ok((("c".into(), 3), 4)));
+}
+
+#[test]
+fn find_record_invalid_record() {
+    let input = b"a,1\nb,x\nc,3\n";
+    let mut reader: Reader<8> = Reader::new();
+
+    let result =
+        reader.find_record::<(heapless::String<4>, u8), _>(input, |_, field| field == b"b");
+    let nread = reader.last_bytes_read();
+
+    assert_eq!(result, Err(Error::InvalidInt(Snippet::new(b"x"))));
+    assert_eq!(nread, 8);
+}
+
+#[derive(Debug, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+struct Line {
+    text: heapless::String<64>,
+}
+
+#[test]
+fn single_quoted_field_record() {
+    let input = b"\"a,b,c\"\n";
+    let mut reader: Reader<64> = Reader::new();
+
+    let result = reader.deserialize::<Line>(&input[..]);
+
+    assert_eq!(
+        result,
+        Ok((
+            Line {
+                text: "a,b,c".into()
+            },
+            8
+        ))
+    )
+}
+
+#[test]
+fn single_quoted_field_records_with_escapes() {
+    let input = b"\"say \"\"hi\"\", then leave\"\n\"multi\nline, text\"\n";
+    let mut reader: Reader<64> = Reader::new();
+
+    let (first, rest) = reader.deserialize_rest::<Line>(&input[..]).unwrap();
+    let (second, rest) = reader.deserialize_rest::<Line>(rest).unwrap();
+
+    assert_eq!(first.text, "say \"hi\", then leave");
+    assert_eq!(second.text, "multi\nline, text");
+    assert!(rest.is_empty());
+}
+
+#[test]
+fn single_quoted_field_record_roundtrip() {
+    let line = Line {
+        text: "a,b,\"c\"".into(),
+    };
+    let mut writer = serde_csv_core::Writer::new();
+    let mut csv = [0; 64];
+    let nwritten = writer.serialize(&line, &mut csv).unwrap();
+    assert_eq!(&csv[..nwritten], b"\"a,b,\"\"c\"\"\"\n");
+
+    let mut reader: Reader<64> = Reader::new();
+    let result = reader.deserialize::<Line>(&csv[..nwritten]);
+
+    assert_eq!(result, Ok((line, nwritten)))
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn boxed_reader() {
+    // Would overflow the stack of the test thread if constructed on it.
+    let mut reader = Reader::<{ 8 << 20 }>::new_boxed();
+
+    let result = reader.deserialize::<(u8, heapless::String<8>)>(b"1,abc\n");
+
+    assert_eq!(result, Ok(((1, "abc".into()), 6)))
+}
+
+const LOG: &[u8] = b"1,INFO,started\n2,WARN,\"low disk, 10%\"\n3,ERROR,disk full\n4,ERROR,halted";
+
+type LogRecord = (u32, heapless::String<8>, heapless::String<16>);
+
+#[test]
+fn find_record_by_first_column() {
+    let mut reader: Reader<16> = Reader::new();
+
+    let result = reader.find_record::<LogRecord, _>(LOG, |i, field| i == 0 && field == b"2");
+
+    assert_eq!(
+        result,
+        Ok(Some(((2, "WARN".into(), "low disk, 10%".into()), 15)))
+    );
+    assert_eq!(reader.last_bytes_read(), 38);
+}
+
+#[test]
+fn find_record_by_middle_column() {
+    let mut reader: Reader<16> = Reader::new();
+
+    let result = reader.find_record::<LogRecord, _>(LOG, |i, field| i == 1 && field == b"ERROR");
+
+    assert_eq!(
+        result,
+        Ok(Some(((3, "ERROR".into(), "disk full".into()), 38)))
+    );
+}
+
+#[test]
+fn find_record_by_last_column_without_terminator() {
+    let mut reader: Reader<16> = Reader::new();
+
+    let result = reader.find_record::<LogRecord, _>(LOG, |i, field| i == 2 && field == b"halted");
+
+    assert_eq!(result, Ok(Some(((4, "ERROR".into(), "halted".into()), 56))));
+    assert_eq!(reader.last_bytes_read(), LOG.len());
+}
+
+#[test]
+fn find_record_continues_after_match() {
+    let mut reader: Reader<16> = Reader::new();
+
+    let mut starts = [0; 2];
+    let mut nread = 0;
+    for start in &mut starts {
+        let (_, offset) = reader
+            .find_record::<LogRecord, _>(&LOG[nread..], |i, field| i == 1 && field == b"ERROR")
+            .unwrap()
+            .unwrap();
+        *start = nread + offset;
+        nread += reader.last_bytes_read();
+    }
+
+    assert_eq!(starts, [38, 56]);
+}
+
+#[test]
+fn find_record_no_match() {
+    let mut reader: Reader<16> = Reader::new();
+
+    let result = reader.find_record::<LogRecord, _>(LOG, |_, field| field == b"DEBUG");
+
+    assert_eq!(result, Ok(None));
+}
+
+#[test]
+fn field_fills_buffer_exactly() {
+    let input = b"abcd,efgh\n";
+    let mut reader: Reader<4> = Reader::new();
+
+    let result = reader.deserialize::<(heapless::String<4>, heapless::String<4>)>(&input[..]);
+
+    assert_eq!(result, Ok((("abcd".into(), "efgh".into()), 10)))
+}
+
+#[test]
+fn field_fills_buffer_exactly_quoted_or_unterminated() {
+    type Record = (heapless::String<4>, heapless::String<4>);
+    let mut reader: Reader<4> = Reader::new();
+
+    let quoted = reader.deserialize::<Record>(b"\"ab,c\",efgh\n");
+    let unterminated = reader.deserialize::<Record>(b"abcd,efgh");
+    let too_long = reader.deserialize::<Record>(b"abcde,f\n");
+
+    assert_eq!(quoted, Ok((("ab,c".into(), "efgh".into()), 12)));
+    assert_eq!(unterminated, Ok((("abcd".into(), "efgh".into()), 9)));
+    assert_eq!(too_long, Err(Error::Overflow));
+}
+
+#[derive(Debug, PartialEq, Eq, serde::Deserialize)]
+struct Reading {
+    sensor: heapless::String<8>,
+    value: i32,
+}
+
+#[test]
+fn headers_map_fields_by_name() {
+    let input = b"value,sensor\n-5,t1\n";
+    let mut reader: Reader<8> = Reader::new();
+    let mut headers = Headers::<4, 32>::new();
+
+    let nread = reader.read_headers(&input[..], &mut headers).unwrap();
+    let result = reader.deserialize_with_headers(&input[nread..], &headers);
+
+    assert_eq!(nread, 13);
+    assert_eq!(headers.iter().collect::<Vec<_>>(), ["value", "sensor"]);
+    assert_eq!(
+        result,
+        Ok((
+            Reading {
+                sensor: "t1".into(),
+                value: -5
+            },
+            6
+        ))
+    );
+}
+
+#[test]
+fn headers_skip_unknown_columns() {
+    let input = b"id,sensor,unit,value\n1,t1,C,20\n";
+    let mut reader: Reader<8> = Reader::new();
+    let mut headers = Headers::<4, 32>::new();
+
+    let nread = reader.read_headers(&input[..], &mut headers).unwrap();
+    let result = reader.deserialize_with_headers(&input[nread..], &headers);
+
+    assert_eq!(
+        result,
+        Ok((
+            Reading {
+                sensor: "t1".into(),
+                value: 20
+            },
+            10
+        ))
+    );
+}
+
+#[test]
+fn headers_duplicate() {
+    let input = b"sensor,value,value\nt1,1,2\n";
+    let mut reader: Reader<8> = Reader::new();
+    let mut headers = Headers::<4, 32>::new();
+
+    let result = reader.read_headers(&input[..], &mut headers);
+
+    assert_eq!(
+        result,
+        Err(Error::DuplicateHeader {
+            index_a: 1,
+            index_b: 2
+        })
+    );
+    assert_eq!(reader.last_bytes_read(), 19);
+}
+
+#[test]
+fn headers_duplicate_far_apart() {
+    let input = b"value,id,sensor,unit,value\n";
+    let mut reader: Reader<8> = Reader::new();
+    let mut headers = Headers::<8, 32>::new();
+
+    let result = reader.read_headers(&input[..], &mut headers);
+
+    assert_eq!(
+        result,
+        Err(Error::DuplicateHeader {
+            index_a: 0,
+            index_b: 4
+        })
+    );
+}
+
+#[test]
+fn headers_duplicate_first_wins() {
+    let input = b"value,sensor,value\n-5,t1,7\n";
+    let mut reader: Reader<8> = Reader::new();
+    reader.duplicate_headers(DuplicateHeaders::FirstWins);
+    let mut headers = Headers::<4, 32>::new();
+
+    let nread = reader.read_headers(&input[..], &mut headers).unwrap();
+    let result = reader.deserialize_with_headers(&input[nread..], &headers);
+
+    assert_eq!(headers.len(), 3);
+    assert!(headers.is_skipped(2));
+    assert_eq!(
+        result,
+        Ok((
+            Reading {
+                sensor: "t1".into(),
+                value: -5
+            },
+            8
+        ))
+    );
+}
+
+#[test]
+fn headers_duplicate_last_wins() {
+    let input = b"value,value,sensor\n-5,7,t1\n";
+    let mut reader: Reader<8> = Reader::new();
+    reader.duplicate_headers(DuplicateHeaders::LastWins);
+    let mut headers = Headers::<4, 32>::new();
+
+    let nread = reader.read_headers(&input[..], &mut headers).unwrap();
+    let result = reader.deserialize_with_headers(&input[nread..], &headers);
+
+    assert!(headers.is_skipped(0));
+    assert!(!headers.is_skipped(1));
+    assert_eq!(
+        result,
+        Ok((
+            Reading {
+                sensor: "t1".into(),
+                value: 7
+            },
+            8
+        ))
+    );
+}
+
+#[test]
+fn headers_duplicate_case_sensitive() {
+    let input = b"Temp,temp\n";
+    let mut reader: Reader<8> = Reader::new();
+    let mut headers = Headers::<4, 32>::new();
+
+    let result = reader.read_headers(&input[..], &mut headers);
+
+    assert_eq!(result, Ok(10));
+    assert_eq!(headers.iter().collect::<Vec<_>>(), ["Temp", "temp"]);
+}
+
+#[test]
+fn headers_duplicate_case_insensitive() {
+    let input = b"Temp,temp\n";
+    let mut reader: Reader<8> = Reader::new();
+    reader.case_insensitive_headers(true);
+    let mut headers = Headers::<4, 32>::new();
+
+    let result = reader.read_headers(&input[..], &mut headers);
+
+    assert_eq!(
+        result,
+        Err(Error::DuplicateHeader {
+            index_a: 0,
+            index_b: 1
+        })
+    );
+}
+
+#[test]
+fn headers_case_insensitive_fields() {
+    let input = b"VALUE,Sensor\n-5,t1\n";
+    let mut reader: Reader<8> = Reader::new();
+    reader.case_insensitive_headers(true);
+    let mut headers = Headers::<4, 32>::new();
+
+    let nread = reader.read_headers(&input[..], &mut headers).unwrap();
+    let result = reader.deserialize_with_headers(&input[nread..], &headers);
+
+    assert_eq!(
+        result,
+        Ok((
+            Reading {
+                sensor: "t1".into(),
+                value: -5
+            },
+            6
+        ))
+    );
+}
+
+#[test]
+fn headers_overflow() {
+    let input = b"a,b,c\n";
+    let mut reader: Reader<8> = Reader::new();
+    let mut headers = Headers::<2, 32>::new();
+
+    let result = reader.read_headers(&input[..], &mut headers);
+
+    assert_eq!(result, Err(Error::Overflow));
+}
+
+/// Interns every string it's given, leaking one copy of each distinct value.
+#[derive(Default)]
+struct LeakingInterner {
+    strings: Vec<&'static str>,
+}
+
+impl FieldInterner for LeakingInterner {
+    fn intern(&mut self, value: &str) -> Option<&'static str> {
+        if let Some(s) = self.strings.iter().find(|s| **s == value) {
+            return Some(s);
+        }
+        let s: &'static str = Box::leak(value.into());
+        self.strings.push(s);
+        Some(s)
+    }
+}
+
+#[test]
+fn interner_dedups_repeated_values() {
+    let input = b"1,sensor\n2,actuator\n3,sensor\n";
+    let mut reader: Reader<16> = Reader::new();
+    let mut interner = LeakingInterner::default();
+
+    let mut records = Vec::new();
+    let mut nread = 0;
+    while nread < input.len() {
+        let (record, n) = reader
+            .deserialize_with_interner::<(u32, &str)>(&input[nread..], &mut interner)
+            .unwrap();
+        records.push(record);
+        nread += n;
+    }
+
+    assert_eq!(records, [(1, "sensor"), (2, "actuator"), (3, "sensor")]);
+    assert!(std::ptr::eq(records[0].1, records[2].1));
+    assert_eq!(interner.strings, ["sensor", "actuator"]);
+}
+
+#[test]
+fn interner_declines_value() {
+    struct Nothing;
+
+    impl FieldInterner for Nothing {
+        fn intern(&mut self, _value: &str) -> Option<&'static str> {
+            None
+        }
+    }
+
+    let mut reader: Reader<16> = Reader::new();
+
+    let owned =
+        reader.deserialize_with_interner::<(u32, heapless::String<8>)>(b"1,a\n", &mut Nothing);
+    let borrowed = reader.deserialize_with_interner::<(u32, &str)>(b"1,a\n", &mut Nothing);
+
+    assert_eq!(owned, Ok(((1, "a".into()), 4)));
+    assert_eq!(borrowed, Err(Error::InvalidType(Snippet::new(b"a"))));
+}
+
+#[test]
+fn from_str_matches_bytes() {
+    type Record = (
+        heapless::String<16>,
+        heapless::String<16>,
+        char,
+        Option<heapless::String<16>>,
+    );
+
+    let inputs = [
+        "Kraków,\"Łódź, Polska\",ż,\n",
+        "\"a\"\"ą\"\"\",zażółć,ę,gęślą\r\n",
+        "plain,ascii,x,text",
+        "\n\nść,,ą,\"\"\n",
+    ];
+    for input in inputs {
+        let mut bytes_reader: Reader<16> = Reader::new();
+        let mut str_reader: Reader<16> = Reader::new();
+
+        let expected = bytes_reader.deserialize::<Record>(input.as_bytes());
+        let result = str_reader.deserialize_from_str::<Record>(input);
+
+        assert!(expected.is_ok());
+        assert_eq!(result, expected, "{input:?}");
+    }
+}
+
+#[test]
+fn from_str_non_ascii_delimiter() {
+    // `§` is encoded as `C2 A7`, so a field ending before `A7` ends with a partial char.
+    let builder = {
+        let mut builder = serde_csv_core::csv_core::ReaderBuilder::new();
+        builder.delimiter(0xa7);
+        builder
+    };
+    let input = "a§b\n";
+    let mut bytes_reader: Reader<16> = Reader::from_builder(&builder);
+    let mut str_reader: Reader<16> = Reader::from_builder(&builder);
+
+    let expected =
+        bytes_reader.deserialize::<(heapless::String<4>, heapless::String<4>)>(input.as_bytes());
+    let result =
+        str_reader.deserialize_from_str::<(heapless::String<4>, heapless::String<4>)>(input);
+
+    assert_eq!(expected, Err(Error::InvalidUtf8String));
+    assert_eq!(result, expected);
+}
+
+#[test]
+fn option_some_int_followed_by_field() {
+    let input = b"1,2\n";
+    let mut reader: Reader<8> = Reader::new();
+
+    let result = reader.deserialize::<(Option<i32>, i32)>(&input[..]);
+
+    assert_eq!(result, Ok(((Some(1), 2), 4)))
+}
+
+#[test]
+fn option_some_tuple_followed_by_field() {
+    let input = b"1,2,3\n";
+    let mut reader: Reader<8> = Reader::new();
+
+    let result = reader.deserialize::<(Option<(i32, i32)>, i32)>(&input[..]);
+
+    assert_eq!(result, Ok(((Some((1, 2)), 3), 6)))
+}
+
+#[test]
+fn option_some_string_followed_by_field() {
+    let input = b"abc,2\n";
+    let mut reader: Reader<8> = Reader::new();
+
+    let result = reader.deserialize::<(Option<heapless::String<8>>, i32)>(&input[..]);
+
+    assert_eq!(result, Ok(((Some("abc".into()), 2), 6)))
+}
+
+#[test]
+fn max_record_len_excludes_terminator() {
+    let mut reader: Reader<8> = Reader::new();
+    reader.max_record_len(9);
+
+    assert_eq!(
+        reader.deserialize::<(u32, u32)>(b"1234,5678\n"),
+        Ok(((1234, 5678), 10))
+    );
+    assert_eq!(
+        reader.deserialize::<(u32, u32)>(b"1234,56789\n"),
+        Err(Error::RecordTooLong { len: 10 })
+    );
+}
+
+#[test]
+fn max_record_len_includes_terminator() {
+    let mut reader: Reader<8> = Reader::new();
+    reader
+        .max_record_len(10)
+        .record_len_includes_terminator(true);
+
+    assert_eq!(
+        reader.deserialize::<(u32, u32)>(b"1234,5678\n"),
+        Ok(((1234, 5678), 10))
+    );
+    assert_eq!(
+        reader.deserialize::<(u32, u32)>(b"1234,5678\r\n"),
+        Err(Error::RecordTooLong { len: 11 })
+    );
+    assert_eq!(
+        reader.deserialize::<(u32, u32)>(b"1234,56789"),
+        Ok(((1234, 56789), 10))
+    );
+}
+
+#[test]
+fn max_record_len_counts_quotes() {
+    let mut reader: Reader<8> = Reader::new();
+    reader.max_record_len(8);
+
+    assert_eq!(
+        reader.deserialize::<(u32, heapless::String<8>)>(b"1234,567\n"),
+        Ok(((1234, "567".into()), 9))
+    );
+    assert_eq!(
+        reader.deserialize::<(u32, heapless::String<8>)>(b"1234,\"56\"\n"),
+        Err(Error::RecordTooLong { len: 9 })
+    );
+}
+
+#[test]
+fn max_record_len_stops_early() {
+    let mut reader: Reader<8> = Reader::new();
+    reader.max_record_len(4);
+
+    let result = reader.deserialize::<(u32, u32, u32)>(b"1,2,3,4,5\n");
+
+    assert_eq!(result, Err(Error::RecordTooLong { len: 6 }));
+}
+
+#[test]
+fn with_raw_records() {
+    let input = b"1,\"a \"\"b\"\"\"\n\n2,c\r\n3,d";
+    let mut reader: Reader<8> = Reader::new();
+    let mut raws = Vec::new();
+
+    let mut nread = 0;
+    while nread < input.len() {
+        let (_, raw, n) = reader
+            .deserialize_with_raw::<(u8, heapless::String<8>)>(&input[nread..])
+            .unwrap();
+        raws.push(&input[nread..][raw]);
+        nread += n;
+    }
+
+    assert_eq!(raws, [&b"1,\"a \"\"b\"\"\""[..], b"2,c", b"3,d"]);
+}
+
+#[test]
+fn with_raw_ignored_fields() {
+    let input = b"\n1,2,3,\"4\r\n\"\r\n5,6";
+    let mut reader: Reader<8> = Reader::new();
+
+    let first = reader.deserialize_with_raw::<(u8, u8)>(&input[..]);
+    let finished = reader.finished_record();
+    let second = reader.deserialize_with_raw::<(u8,)>(&input[14..]);
+
+    assert_eq!(first, Ok(((1, 2), 1..12, 14)));
+    assert_eq!(&input[1..12], b"1,2,3,\"4\r\n\"");
+    assert!(finished);
+    assert_eq!(second, Ok(((5,), 0..3, 3)));
+}
+
+#[test]
+fn expected_fields_exact() {
+    let mut reader: Reader<8> = Reader::new();
+    reader.with_expected_fields(3);
+
+    let result = reader.deserialize::<(u8, u8, u8)>(b"1,2,3\n");
+
+    assert_eq!(result, Ok(((1, 2, 3), 6)));
+}
+
+#[test]
+fn expected_fields_short_record() {
+    let mut reader: Reader<8> = Reader::new();
+    reader.with_expected_fields(3);
+
+    let result = reader.deserialize::<(u8, u8, u8)>(b"1,2\n");
+
+    assert_eq!(
+        result,
+        Err(Error::FieldCount {
+            expected: 3,
+            got: 2
+        })
+    );
+}
+
+#[test]
+fn expected_fields_long_record() {
+    let mut reader: Reader<8> = Reader::new();
+    reader.with_expected_fields(3);
+
+    let result = reader.deserialize::<(u8, u8, u8)>(b"1,2,3,4\n");
+
+    assert_eq!(
+        result,
+        Err(Error::FieldCount {
+            expected: 3,
+            got: 4
+        })
+    );
+}
+
+#[test]
+fn expected_fields_ignored_fields() {
+    let input = b"1,2,3\n4,5\n";
+    let mut reader: Reader<8> = Reader::new();
+    reader.with_expected_fields(3);
+
+    let (first, nread) = reader.deserialize::<(u8, u8)>(&input[..]).unwrap();
+    let second = reader.deserialize::<(u8, u8)>(&input[nread..]);
+
+    assert_eq!(first, (1, 2));
+    assert_eq!(
+        second,
+        Err(Error::FieldCount {
+            expected: 3,
+            got: 2
+        })
+    );
+}
+
+#[test]
+fn expected_fields_ignored_any() {
+    let mut reader: Reader<8> = Reader::new();
+    reader.with_expected_fields(2);
+
+    let result = reader.deserialize::<serde::de::IgnoredAny>(b"1,2,3\n");
+
+    assert_eq!(
+        result,
+        Err(Error::FieldCount {
+            expected: 2,
+            got: 3
+        })
+    );
+}
+
+#[test]
+fn expected_fields_skip_record_checked() {
+    let input = b"x,2,3\nx,2\nx,2,3,4\nx\n";
+    let mut reader: Reader<8> = Reader::new();
+    reader.with_expected_fields(3);
+    let mut nread = 0;
+    let mut results = Vec::new();
+
+    for validate in [true, true, true, false] {
+        let result = reader.deserialize::<(u8, u8, u8)>(&input[nread..]);
+        assert!(result.is_err());
+        nread += reader.last_bytes_read();
+        results.push(reader.skip_record_checked(&input[nread..], validate));
+        nread += reader.last_bytes_read();
+    }
+
+    assert_eq!(
+        results,
+        [
+            Ok(4),
+            Err(Error::FieldCount {
+                expected: 3,
+                got: 2
+            }),
+            Err(Error::FieldCount {
+                expected: 3,
+                got: 4
+            }),
+            Ok(0),
+        ]
+    );
+    assert_eq!(nread, input.len());
+}
+
+#[test]
+fn nested_overflow() {
+    type Codes<const N: usize> = serde_csv_core::Nested<(heapless::String<8>, u16), b';', N>;
+    let input = b"1,\"E1;404\"\n";
+
+    let inner = Reader::<16>::new().deserialize::<(u8, Codes<2>)>(&input[..]);
+    let outer = Reader::<4>::new().deserialize::<(u8, Codes<8>)>(&input[..]);
+
+    assert_eq!(inner, Err(Error::NestedOverflow));
+    assert_eq!(outer, Err(Error::Overflow));
+}
+
+#[test]
+fn nested_does_not_disturb_outer_reader() {
+    type Codes = serde_csv_core::Nested<(u8, u8), b';', 8>;
+    let input = b"\"1;2\",3\n\"4;5\",6\n";
+    let mut reader: Reader<8> = Reader::new();
+
+    let (first, nread) = reader.deserialize::<(Codes, u8)>(&input[..]).unwrap();
+    let (second, _) = reader.deserialize::<(Codes, u8)>(&input[nread..]).unwrap();
+
+    assert_eq!(first, (serde_csv_core::Nested((1, 2)), 3));
+    assert_eq!(second, (serde_csv_core::Nested((4, 5)), 6));
+}
+
+#[test]
+fn vec_unterminated_record() {
+    let mut reader: Reader<8> = Reader::new();
+
+    let result = reader.deserialize::<heapless::Vec<u8, 4>>(b"1,2");
+
+    assert_eq!(result, Ok((heapless::Vec::from_slice(&[1, 2]).unwrap(), 3)));
+}
+
+#[test]
+fn vec_unterminated_record_then_next() {
+    let mut reader: Reader<8> = Reader::new();
+
+    let first = reader.deserialize::<heapless::Vec<u8, 4>>(b"1,2");
+    let finished = reader.finished_record();
+    let second = reader.deserialize::<heapless::Vec<u8, 4>>(b"3\n");
+
+    assert_eq!(first, Ok((heapless::Vec::from_slice(&[1, 2]).unwrap(), 3)));
+    assert!(finished);
+    assert_eq!(second, Ok((heapless::Vec::from_slice(&[3]).unwrap(), 2)));
+}
+
+#[test]
+fn percent() {
+    let mut reader: Reader<8> = Reader::new();
+    reader.percent(true);
+
+    let result = reader.deserialize::<(f64, f32, f64)>(b"12.5%,-50%,0.5\n");
+
+    assert_eq!(result, Ok(((0.125, -0.5, 0.5), 15)));
+}
+
+#[test]
+fn percent_invalid() {
+    let mut reader: Reader<8> = Reader::new();
+    reader.percent(true);
+
+    for input in [&b"1%2\n"[..], b"1%%\n", b"%\n", b"1-%\n", b"%1\n"] {
+        assert_eq!(
+            reader.deserialize::<f64>(input),
+            Err(Error::InvalidFloat(Snippet::new(&input[..input.len() - 1]))),
+            "{:?}",
+            input
+        );
+    }
+}
+
+#[test]
+fn percent_disabled() {
+    let mut reader: Reader<8> = Reader::new();
+
+    let result = reader.deserialize::<f64>(b"12.5%\n");
+
+    assert_eq!(result, Err(Error::InvalidFloat(Snippet::new(b"12.5%"))));
+}
+
+#[test]
+fn accounting_negatives() {
+    let mut reader: Reader<8> = Reader::new();
+    reader.accounting_negatives(true);
+
+    let result = reader.deserialize::<(i32, f64, i8, f32)>(b"(123),(12.5),-4,3.5\n");
+
+    assert_eq!(result, Ok(((-123, -12.5, -4, 3.5), 20)));
+}
+
+#[test]
+fn accounting_negatives_percent() {
+    let mut reader: Reader<8> = Reader::new();
+    reader.accounting_negatives(true).percent(true);
+
+    let result = reader.deserialize::<f64>(b"(12.5%)\n");
+
+    assert_eq!(result, Ok((-0.125, 8)));
+}
+
+#[test]
+fn accounting_negatives_invalid() {
+    let mut reader: Reader<8> = Reader::new();
+    reader.accounting_negatives(true);
+
+    assert_eq!(
+        reader.deserialize::<i32>(b"(123\n"),
+        Err(Error::InvalidInt(Snippet::new(b"(123")))
+    );
+    assert_eq!(
+        reader.deserialize::<i32>(b"123)\n"),
+        Err(Error::InvalidInt(Snippet::new(b"123)")))
+    );
+    assert_eq!(
+        reader.deserialize::<i32>(b"(-123)\n"),
+        Err(Error::InvalidInt(Snippet::new(b"--123")))
+    );
+    assert_eq!(
+        reader.deserialize::<i32>(b"()\n"),
+        Err(Error::InvalidInt(Snippet::new(b"-")))
+    );
+    assert_eq!(
+        reader.deserialize::<u32>(b"(123)\n"),
+        Err(Error::InvalidInt(Snippet::new(b"-123")))
+    );
+    assert_eq!(
+        reader.deserialize::<f64>(b"(1.5\n"),
+        Err(Error::InvalidFloat(Snippet::new(b"(1.5")))
+    );
+    assert_eq!(
+        reader.deserialize::<f64>(b"(+1.5)\n"),
+        Err(Error::InvalidFloat(Snippet::new(b"-+1.5")))
+    );
+}
+
+#[test]
+fn accounting_negatives_trim_padding() {
+    let read = |input: &[u8], trim: bool| {
+        let mut reader: Reader<8> = Reader::new();
+        reader
+            .accounting_negatives(true)
+            .fixed_widths(Some(&[7, 7]))
+            .trim_padding(trim);
+        reader.deserialize::<(i32, f64)>(input)
+    };
+
+    assert_eq!(read(b"  (123)(12.5) \n", true), Ok(((-123, -12.5), 15)));
+    assert_eq!(
+        read(b"  (123)(12.5) \n", false),
+        Err(Error::InvalidInt(Snippet::new(b"  (123)")))
+    );
+    assert_eq!(
+        read(b"( 123 )(12.5) \n", true),
+        Err(Error::InvalidInt(Snippet::new(b"- 123 ")))
+    );
+}
+
+#[test]
+fn accounting_negatives_disabled() {
+    let mut reader: Reader<8> = Reader::new();
+
+    let result = reader.deserialize::<i32>(b"(123)\n");
+
+    assert_eq!(result, Err(Error::InvalidInt(Snippet::new(b"(123)"))));
+}
+
+#[test]
+fn no_panic_empty_input_after_partial_record() {
+    let mut reader: Reader<8> = Reader::new();
+
+    let first = reader.deserialize::<u8>(b"1,");
+    let second = reader.deserialize::<(u8,)>(b"");
+    let third = reader.deserialize_with_raw::<(u8,)>(b"");
+
+    assert_eq!(first, Ok((1, 2)));
+    assert_eq!(second, Err(Error::InvalidInt(Snippet::new(b""))));
+    assert_eq!(third, Err(Error::InvalidInt(Snippet::new(b""))));
+}
+
+#[test]
+fn empty_input_has_no_fields() {
+    type Record = (heapless::String<8>, heapless::String<8>);
+    let mut reader: Reader<8> = Reader::new();
+
+    let empty = reader.deserialize::<Record>(b"");
+    let short = reader.deserialize::<Record>(b"a");
+
+    assert_eq!(empty, Err(Error::InvalidLength { len: 0 }));
+    assert_eq!(short, Err(Error::InvalidLength { len: 1 }));
+}
+
+#[test]
+fn no_panic_trailing_delimiter_at_end_of_input() {
+    let mut reader: Reader<8> = Reader::new();
+
+    let result = reader.deserialize_with_raw::<(u8, ())>(b"1,");
+
+    assert_eq!(result, Ok(((1, ()), 0..2, 2)));
+}
+
+#[test]
+fn newtype_struct() {
+    #[derive(Debug, PartialEq, serde::Deserialize)]
+    struct Celsius(i16);
+
+    let mut reader: Reader<8> = Reader::new();
+
+    let result = reader.deserialize::<(Celsius, Celsius)>(b"-5,21\n");
+
+    assert_eq!(result, Ok(((Celsius(-5), Celsius(21)), 6)));
+}
+
+#[test]
+fn deserialize_any_unsupported() {
+    #[derive(Debug, PartialEq, serde::Deserialize)]
+    #[serde(untagged)]
+    enum Untagged {
+        Number(u8),
+        Flag(bool),
+    }
+
+    let mut reader: Reader<8> = Reader::new();
+
+    let result = reader.deserialize::<Untagged>(b"1\n");
+
+    assert_eq!(
+        result,
+        Err(Error::Unsupported("Deserializer::deserialize_any"))
+    );
+}
+
+#[test]
+fn owned_string() {
+    let mut reader: Reader<8> = Reader::new();
+
+    let result = reader.deserialize::<(std::string::String, u8)>(b"abc,1\n");
+
+    assert_eq!(result, Ok((("abc".to_string(), 1), 6)));
+}
+
+const SPLIT: &[u8] = b"1,\"a, \"\"b\"\"\",2.5,true\r\n\n2,c,,false\n";
+
+type SplitRecord = (u8, heapless::String<8>, Option<f32>, bool);
+
+/// Drops the first `n` bytes of `parts`.
+fn advance<'a>(parts: &[&'a [u8]], mut n: usize) -> Vec<&'a [u8]> {
+    let mut rest = Vec::new();
+    for part in parts {
+        let skip = n.min(part.len());
+        n -= skip;
+        rest.push(&part[skip..]);
+    }
+    rest
+}
+
+fn check_split(parts: &[&[u8]]) {
+    let mut reader: Reader<8> = Reader::new();
+
+    let (first, n1) = reader
+        .deserialize_from_slices::<SplitRecord>(parts)
+        .unwrap();
+    let rest = advance(parts, n1);
+    let (second, n2) = reader
+        .deserialize_from_slices::<SplitRecord>(&rest)
+        .unwrap();
+
+    assert_eq!(
+        first,
+        (1, "a, \"b\"".into(), Some(2.5), true),
+        "{:?}",
+        parts
+    );
+    assert_eq!(second, (2, "c".into(), None, false), "{:?}", parts);
+    assert_eq!(n1 + n2, SPLIT.len(), "{:?}", parts);
+}
+
+#[test]
+fn from_slices_two_parts() {
+    for i in 0..=SPLIT.len() {
+        check_split(&[&SPLIT[..i], &SPLIT[i..]]);
+    }
+}
+
+#[test]
+fn from_slices_three_parts() {
+    for i in 0..=SPLIT.len() {
+        for j in i..=SPLIT.len() {
+            check_split(&[&SPLIT[..i], &SPLIT[i..j], &SPLIT[j..]]);
+        }
+    }
+}
+
+#[test]
+fn from_slices_field_overflow_across_parts() {
+    let mut reader: Reader<4> = Reader::new();
+
+    let result = reader.deserialize_from_slices::<(u8, u8)>(&[b"12", b"34", b"5,6\n"]);
+
+    assert_eq!(result, Err(Error::Overflow));
+}
+
+#[test]
+fn from_slices_enum_tag_column() {
+    #[derive(Debug, PartialEq, serde::Deserialize)]
+    enum Reading {
+        Temperature(i8),
+        Humidity(u8),
+    }
+
+    let mut reader: Reader<16> = Reader::new();
+    reader.enum_tag_column(1);
+
+    let result = reader.deserialize_from_slices::<Reading>(&[b"4", b"0,Humi", b"dity\n"]);
+
+    assert_eq!(result, Ok((Reading::Humidity(40), 12)));
+}
+
+#[test]
+fn arrayvec_too_long() {
+    let mut reader: Reader<32> = Reader::new();
+
+    let string = reader.deserialize::<(arrayvec::ArrayString<4>, u8)>(b"abcde,1\n");
+    let vec = reader.deserialize::<arrayvec::ArrayVec<u8, 2>>(b"1,2,3\n");
+
+    assert_eq!(string, Err(Error::FieldTooLong { len: 5 }));
+    assert_eq!(vec, Err(Error::SeqTooLong { len: 3 }));
+}
+
+/// Records the size hint given to its visitor and the number of elements.
+#[derive(Debug, PartialEq)]
+struct SizeHint(Option<usize>, usize);
+
+impl<'de> serde::Deserialize<'de> for SizeHint {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct Visitor;
+
+        impl<'de> serde::de::Visitor<'de> for Visitor {
+            type Value = SizeHint;
+
+            fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+                formatter.write_str("a sequence")
+            }
+
+            fn visit_seq<A: serde::de::SeqAccess<'de>>(
+                self,
+                mut seq: A,
+            ) -> Result<SizeHint, A::Error> {
+                let hint = seq.size_hint();
+                let mut len = 0;
+                while seq.next_element::<serde::de::IgnoredAny>()?.is_some() {
+                    len += 1;
+                }
+                Ok(SizeHint(hint, len))
+            }
+        }
+
+        deserializer.deserialize_seq(Visitor)
+    }
+}
+
+#[test]
+fn size_hint_unknown() {
+    let mut reader: Reader<8> = Reader::new();
+
+    let result = reader.deserialize::<SizeHint>(b"a,b,c\n");
+
+    assert_eq!(result, Ok((SizeHint(None, 3), 6)));
+}
+
+#[test]
+fn size_hint_expected_fields() {
+    let mut reader: Reader<8> = Reader::new();
+    reader.with_expected_fields(3);
+
+    let result = reader.deserialize::<(u8, SizeHint)>(b"1,b,c\n");
+
+    assert_eq!(result, Ok(((1, SizeHint(Some(2), 2)), 6)));
+}
+
+#[test]
+fn size_hint_headers() {
+    let input = b"x,y,z\na,b,c\n";
+    let mut reader: Reader<8> = Reader::new();
+    let mut headers = Headers::<4, 32>::new();
+
+    let nread = reader.read_headers(&input[..], &mut headers).unwrap();
+    let result = reader.deserialize_with_headers::<SizeHint, 4, 32>(&input[nread..], &headers);
+
+    assert_eq!(result, Ok((SizeHint(Some(3), 3), 6)));
+}
+
+#[derive(Debug, PartialEq, serde::Deserialize)]
+struct BurstInfo {
+    id: u8,
+    name: heapless::String<8>,
+}
+
+#[derive(Debug, PartialEq, serde::Deserialize)]
+struct BurstSample {
+    time: u8,
+    value: i16,
+}
+
+#[derive(Debug, PartialEq, serde::Deserialize)]
+struct Burst {
+    info: BurstInfo,
+    samples: [BurstSample; 2],
+}
+
+fn burst(id: u8, name: &str, values: [i16; 2]) -> Burst {
+    Burst {
+        info: BurstInfo {
+            id,
+            name: name.into(),
+        },
+        samples: [
+            BurstSample {
+                time: 0,
+                value: values[0],
+            },
+            BurstSample {
+                time: 1,
+                value: values[1],
+            },
+        ],
+    }
+}
+
+#[test]
+fn row_per_struct() {
+    let input = b"1,a\n0,10\n1,-5\n2,b\r\n0,7\r\n1,8\r\n";
+    let mut reader: Reader<8> = Reader::new();
+    reader.row_per_struct(true);
+
+    let (first, n1) = reader.deserialize::<Burst>(&input[..]).unwrap();
+    let (second, n2) = reader.deserialize::<Burst>(&input[n1..]).unwrap();
+
+    assert_eq!((first, n1), (burst(1, "a", [10, -5]), 14));
+    assert_eq!((second, n2), (burst(2, "b", [7, 8]), 15));
+}
+
+#[test]
+fn row_per_struct_skips_unread_fields() {
+    let input = b"1,a,x\n0,10,y,z\n1,-5\n";
+    let mut reader: Reader<8> = Reader::new();
+    reader.row_per_struct(true);
+
+    let result = reader.deserialize::<Burst>(&input[..]);
+
+    assert_eq!(result, Ok((burst(1, "a", [10, -5]), 20)));
+}
+
+#[test]
+fn row_per_struct_missing_row() {
+    let input = b"1,a\n0,10\n";
+    let mut reader: Reader<8> = Reader::new();
+    reader.row_per_struct(true);
+
+    let result = reader.deserialize::<Burst>(&input[..]);
+
+    assert!(result.is_err());
+}
+
+#[test]
+fn row_per_struct_max_record_len() {
+    let input = b"1,a\n0,10\n1,-5\n";
+    let mut reader: Reader<8> = Reader::new();
+    reader.row_per_struct(true).max_record_len(4);
+
+    let result = reader.deserialize::<Burst>(&input[..]);
+
+    assert_eq!(result, Ok((burst(1, "a", [10, -5]), 14)));
+}
+
+#[test]
+fn row_per_struct_disabled() {
+    let input = b"1,a,0,10,1,-5\n";
+    let mut reader: Reader<8> = Reader::new();
+
+    let result = reader.deserialize::<Burst>(&input[..]);
+
+    assert_eq!(result, Ok((burst(1, "a", [10, -5]), 14)));
+}
+
+#[test]
+#[cfg(any(feature = "minimal-float", not(feature = "lexical-parse-float")))]
+fn minimal_float() {
+    let mut reader: Reader<32> = Reader::new();
+    let mut parse = |input: &str| {
+        reader
+            .deserialize::<f64>(input.as_bytes())
+            .map(|(value, _)| value)
+    };
+
+    assert_eq!(parse("1.5"), Ok(1.5));
+    assert_eq!(parse("-2"), Ok(-2.0));
+    assert_eq!(parse("+0.25"), Ok(0.25));
+    assert_eq!(parse(".5"), Ok(0.5));
+    assert_eq!(parse("5."), Ok(5.0));
+    assert_eq!(parse("1e3"), Ok(1000.0));
+    assert_eq!(parse("-1.25E-2"), Ok(-0.0125));
+    assert_eq!(parse("0.1"), Ok(0.1));
+    assert_eq!(parse("000123.4500"), Ok(123.45));
+    assert_eq!(parse("1.7976931348623157e308"), Ok(f64::MAX));
+    assert_eq!(parse("2.2250738585072014e-308"), Ok(f64::MIN_POSITIVE));
+    assert_eq!(parse("5e-324"), Ok(5e-324));
+    assert_eq!(parse("1e-400"), Ok(0.0));
+    assert!(parse("-0").unwrap().is_sign_negative());
+}
+
+#[test]
+#[cfg(any(feature = "minimal-float", not(feature = "lexical-parse-float")))]
+fn minimal_float_rejected() {
+    let mut reader: Reader<32> = Reader::new();
+
+    for input in [
+        "NaN",
+        "inf",
+        "-infinity",
+        "1e309",
+        "1e",
+        "1e+",
+        "1.2.3",
+        "e5",
+        ".",
+        "-",
+        "0x10",
+        "1_000",
+    ] {
+        assert_eq!(
+            reader.deserialize::<f64>(input.as_bytes()),
+            Err(Error::InvalidFloat(Snippet::new(input.as_bytes()))),
+            "{input}"
+        );
+    }
+    assert_eq!(
+        reader.deserialize::<f32>(b"3.4028235e38"),
+        Ok((f32::MAX, 12))
+    );
+    for input in ["1e39", "3.5e38", "-3.5e38"] {
+        assert_eq!(
+            reader.deserialize::<f32>(input.as_bytes()),
+            Err(Error::InvalidFloat(Snippet::new(input.as_bytes()))),
+            "{input}"
+        );
+    }
+}
+
+#[derive(Debug, PartialEq, serde::Deserialize)]
+struct Bits8 {
+    #[serde(with = "serde_csv_core::bits8")]
+    flags: [bool; 8],
+}
+
+#[test]
+fn bits8() {
+    let mut reader: Reader<8> = Reader::new();
+
+    let result = reader.deserialize::<Bits8>(b"0Xa5\n");
+
+    assert_eq!(
+        result,
+        Ok((
+            Bits8 {
+                flags: [true, false, true, false, false, true, false, true]
+            },
+            5
+        ))
+    );
+}
+
+#[test]
+fn bits8_invalid() {
+    let mut reader: Reader<8> = Reader::new();
+
+    for input in [&b"0xF\n"[..], b"FF\n", b"0xFG\n", b"0x100\n", b"255\n"] {
+        assert_eq!(
+            reader.deserialize::<Bits8>(input),
+            Err(Error::InvalidValue(Snippet::new(&input[..input.len() - 1])))
+        );
+    }
+}
+
+#[test]
+fn reader_builder() {
+    #[derive(Debug, PartialEq, serde::Deserialize)]
+    struct Record {
+        name: heapless::String<8>,
+        amount: f64,
+        note: Option<heapless::String<8>>,
+        padded: heapless::String<8>,
+    }
+
+    let input = b"# comment\n'=1;(2.5%);NULL\n";
+    let mut reader = serde_csv_core::de::ReaderBuilder::new()
+        .delimiter(b';')
+        .comment(Some(b'#'))
+        .unsanitize_formulas(true)
+        .accounting_negatives(true)
+        .percent(true)
+        .null_literal(Some(b"NULL"))
+        .pad_short_records(true)
+        .build::<8>();
+
+    let result = reader.deserialize::<Record>(&input[..]);
+
+    assert_eq!(
+        result,
+        Ok((
+            Record {
+                name: "=1".into(),
+                amount: -0.025,
+                note: None,
+                padded: "".into(),
+            },
+            26
+        ))
+    );
+}
+
+#[test]
+fn max_fields_per_record_keyed() {
+    let input = b"a,b,c,d\n1,2,3,4\n";
+    let mut reader: Reader<8> = Reader::new();
+    reader.max_fields_per_record(3);
+
+    let result =
+        reader.deserialize_keyed::<heapless::LinearMap<heapless::String<8>, u8, 8>>(&input[..]);
+
+    assert_eq!(result, Err(Error::TooManyFields));
+    assert_eq!(reader.last_bytes_read(), input.len());
+}
+
+#[test]
+fn reader_builder_limits() {
+    let mut builder = serde_csv_core::de::ReaderBuilder::new();
+    builder.quote(b'\'').max_fields_per_record(2);
+    let mut reader = builder.build::<8>();
+
+    assert_eq!(
+        reader.deserialize::<(heapless::String<8>, u8)>(b"'a,b',1\n"),
+        Ok((("a,b".into(), 1), 8))
+    );
+    assert_eq!(
+        reader.deserialize::<(u8, u8, u8)>(b"1,2,3\n"),
+        Err(Error::TooManyFields)
+    );
+}
+
+#[test]
+fn reader_builder_default() {
+    let mut built = serde_csv_core::de::ReaderBuilder::new().build::<8>();
+    let mut reader: Reader<8> = Reader::new();
+    let input = b"\"\",,x\n";
+
+    assert_eq!(
+        built.deserialize::<(Option<u8>, Option<u8>, heapless::String<8>)>(&input[..]),
+        reader.deserialize::<(Option<u8>, Option<u8>, heapless::String<8>)>(&input[..])
+    );
+}
+
+#[test]
+fn custom_terminator() {
+    let input = b"1,a;22,b;3,\"c;d\";";
+    let mut reader = serde_csv_core::de::ReaderBuilder::new()
+        .terminator(csv_core::Terminator::Any(b';'))
+        .build::<8>();
+
+    let mut records = heapless::Vec::<_, 3>::new();
+    let mut nread = 0;
+    while nread < input.len() {
+        let (record, n) = reader
+            .deserialize::<(u8, heapless::String<8>)>(&input[nread..])
+            .unwrap();
+        nread += n;
+        records.push((record, nread)).unwrap();
+    }
+
+    assert_eq!(
+        records,
+        [
+            ((1, "a".into()), 4),
+            ((22, "b".into()), 9),
+            ((3, "c;d".into()), 17),
+        ]
+    );
+}
+
+#[test]
+fn custom_terminator_newline_is_data() {
+    let input = b"a\r\nb,1;2";
+    let mut reader = serde_csv_core::de::ReaderBuilder::new()
+        .terminator(csv_core::Terminator::Any(b';'))
+        .build::<8>();
+
+    let result = reader.deserialize::<(heapless::String<8>, u8)>(&input[..]);
+
+    assert_eq!(result, Ok((("a\r\nb".into(), 1), 7)));
+}
+
+#[test]
+fn custom_terminator_raw() {
+    let input = b";;\nx,1;;2,y;";
+    let mut reader = serde_csv_core::de::ReaderBuilder::new()
+        .terminator(csv_core::Terminator::Any(b';'))
+        .build::<8>();
+
+    let (record, raw, nread) = reader
+        .deserialize_with_raw::<(heapless::String<8>, u8)>(&input[..])
+        .unwrap();
+    assert_eq!(record, ("\nx".into(), 1));
+    assert_eq!(&input[raw], b"\nx,1");
+    assert_eq!(nread, 7);
+
+    let (record, raw, nread) = reader
+        .deserialize_with_raw::<(u8, heapless::String<8>)>(&input[nread..])
+        .unwrap();
+    assert_eq!(record, (2, "y".into()));
+    assert_eq!(raw, 1..4);
+    assert_eq!(nread, 5);
+}
+
+#[test]
+fn custom_terminator_unquoted_empty() {
+    let input = b";;,1;";
+    let mut reader = serde_csv_core::de::ReaderBuilder::new()
+        .terminator(csv_core::Terminator::Any(b';'))
+        .quoted_empty_is_none(false)
+        .build::<8>();
+
+    let result = reader.deserialize::<(Option<heapless::String<8>>, u8)>(&input[..]);
+
+    assert_eq!(result, Ok(((None, 1), 5)));
+}
+
+#[derive(Debug, PartialEq, serde::Deserialize)]
+struct City {
+    name: heapless::String<8>,
+    population: u32,
+}
+
+fn checksum_reader(checksum: Checksum) -> Reader<16> {
+    let mut reader = Reader::new();
+    reader.checksum(Some(checksum));
+    reader
+}
+
+#[test]
+fn checksum_valid() {
+    let input = b"Cracow,766683,083A\r\n\r\nWarsaw,1860281,3c1b\r\n";
+    let mut reader = checksum_reader(Checksum::Crc16Ccitt);
+
+    let (first, nread) = reader.deserialize::<City>(&input[..]).unwrap();
+    let (second, rest) = reader.deserialize::<City>(&input[nread..]).unwrap();
+
+    assert_eq!(
+        (first, nread),
+        (
+            City {
+                name: "Cracow".into(),
+                population: 766_683
+            },
+            20
+        )
+    );
+    assert_eq!(
+        (second, rest),
+        (
+            City {
+                name: "Warsaw".into(),
+                population: 1_860_281
+            },
+            input.len() - nread
+        )
+    );
+}
+
+#[test]
+fn checksum_crc32_quoted() {
+    let input = b"a,\"b,c\",400AD88A\n";
+    let mut reader = checksum_reader(Checksum::Crc32);
+
+    let result = reader.deserialize::<(char, heapless::String<8>)>(&input[..]);
+
+    assert_eq!(result, Ok((('a', "b,c".into()), input.len())));
+}
+
+#[test]
+fn checksum_corrupted_byte() {
+    let input = b"Cracow,766684,083A\nWarsaw,1860281,3C1B\n";
+    let mut reader = checksum_reader(Checksum::Crc16Ccitt);
+
+    let result = reader.deserialize::<City>(&input[..]);
+    let nread = reader.last_bytes_read();
+    let (next, _) = reader.deserialize::<City>(&input[nread..]).unwrap();
+
+    assert_eq!(
+        result,
+        Err(Error::ChecksumMismatch {
+            expected: 0x083A,
+            actual: 0x78DD
+        })
+    );
+    assert_eq!(nread, 19);
+    assert_eq!(next.name, "Warsaw");
+}
+
+#[test]
+fn checksum_corrupted_field() {
+    let mut reader = checksum_reader(Checksum::Crc16Ccitt);
+
+    assert_eq!(
+        reader.deserialize::<City>(b"Cracow,766683,083B\n"),
+        Err(Error::ChecksumMismatch {
+            expected: 0x083B,
+            actual: 0x083A
+        })
+    );
+    assert_eq!(
+        reader.deserialize::<City>(b"Cracow,766683,83A\n"),
+        Err(Error::InvalidChecksum(Snippet::new(b"83A")))
+    );
+    assert_eq!(
+        reader.deserialize::<City>(b"Cracow,766683,08XA\n"),
+        Err(Error::InvalidChecksum(Snippet::new(b"08XA")))
+    );
+    assert_eq!(reader.last_bytes_read(), 19);
+    assert_eq!(
+        Error::InvalidChecksum(Snippet::new(b"08XA")).to_string(),
+        "Invalid checksum `08XA`."
+    );
+}
+
+#[test]
+fn checksum_hidden_from_seq() {
+    let input = b"123456789,29B1\n";
+    let mut reader = checksum_reader(Checksum::Crc16Ccitt);
+
+    let result = reader.deserialize::<heapless::Vec<u32, 4>>(&input[..]);
+
+    assert_eq!(
+        result,
+        Ok((heapless::Vec::from_slice(&[123_456_789]).unwrap(), 15))
+    );
+}
+
+#[test]
+fn checksum_headers() {
+    let input = b"population,name,3F8D1B35\n766683,Cracow,523F9508\n";
+    let mut reader = checksum_reader(Checksum::Crc32);
+    let mut headers = Headers::<4, 32>::new();
+
+    let nread = reader.read_headers(&input[..], &mut headers).unwrap();
+    let result = reader.deserialize_with_headers::<City, 4, 32>(&input[nread..], &headers);
+
+    assert_eq!(nread, 25);
+    assert_eq!(headers.len(), 2);
+    assert_eq!(
+        result,
+        Ok((
+            City {
+                name: "Cracow".into(),
+                population: 766_683
+            },
+            input.len() - nread
+        ))
+    );
+}
+
+#[test]
+fn map_typed() {
+    let input = b"1,2.5,2,3.5\n";
+    let mut reader: Reader<8> = Reader::new();
+
+    let result = reader.deserialize::<std::collections::BTreeMap<u8, f32>>(&input[..]);
+
+    assert_eq!(
+        result,
+        Ok((std::collections::BTreeMap::from([(1, 2.5), (2, 3.5)]), 12))
+    );
+}
+
+#[test]
+fn map_after_fields() {
+    let input = b"x,1,true,2,false\n";
+    let mut reader: Reader<8> = Reader::new();
+
+    let result = reader.deserialize::<(char, std::collections::BTreeMap<u8, bool>)>(&input[..]);
+
+    assert_eq!(
+        result,
+        Ok((
+            (
+                'x',
+                std::collections::BTreeMap::from([(1, true), (2, false)])
+            ),
+            17
+        ))
+    );
+}
+
+#[test]
+fn map_odd_field_count() {
+    let input = b"1,2.5,2\n";
+    let mut reader: Reader<8> = Reader::new();
+
+    let result = reader.deserialize::<std::collections::BTreeMap<u8, f32>>(&input[..]);
+
+    assert_eq!(
+        result,
+        Err(Error::FieldCount {
+            expected: 4,
+            got: 3
+        })
+    );
+}
+
+#[test]
+fn map_invalid_key() {
+    let input = b"1,2.5,x,3.5\n";
+    let mut reader: Reader<8> = Reader::new();
+
+    let result = reader.deserialize::<std::collections::BTreeMap<u8, f32>>(&input[..]);
+
+    assert_eq!(result, Err(Error::InvalidInt(Snippet::new(b"x"))));
+}
+
+#[test]
+fn keyed() {
+    let input = b"gain,\"offset, mV\",scale\r\n1.5,-20,0.25\r\nnext\r\n";
+    let mut reader: Reader<16> = Reader::new();
+
+    let (map, nread) = reader
+        .deserialize_keyed::<heapless::LinearMap<heapless::String<16>, f32, 8>>(&input[..])
+        .unwrap();
+
+    let entries: Vec<_> = map.iter().map(|(k, v)| (k.as_str(), *v)).collect();
+    assert_eq!(
+        entries,
+        [("gain", 1.5), ("offset, mV", -20.0), ("scale", 0.25)]
+    );
+    assert_eq!(nread, 39);
+    assert_eq!(
+        reader.deserialize::<(heapless::String<8>,)>(&input[nread..]),
+        Ok((("next".into(),), 6))
+    );
+}
+
+#[test]
+fn keyed_struct() {
+    #[derive(Debug, PartialEq, serde::Deserialize)]
+    struct Calibration {
+        scale: f32,
+        gain: f32,
+    }
+
+    let input = b"gain,unused,scale\n1.5,x,0.25\n";
+    let mut reader: Reader<16> = Reader::new();
+
+    let result = reader.deserialize_keyed::<Calibration>(&input[..]);
+
+    assert_eq!(
+        result,
+        Ok((
+            Calibration {
+                scale: 0.25,
+                gain: 1.5
+            },
+            input.len()
+        ))
+    );
+}
+
+#[test]
+fn keyed_field_count_mismatch() {
+    let input = b"a,b,c\n1,2\n3,4,5\n";
+    let mut reader: Reader<16> = Reader::new();
+
+    let result =
+        reader.deserialize_keyed::<heapless::LinearMap<heapless::String<8>, u8, 8>>(&input[..]);
+
+    assert_eq!(
+        result,
+        Err(Error::FieldCount {
+            expected: 3,
+            got: 2
+        })
+    );
+    assert_eq!(reader.last_bytes_read(), 10);
+}
+
+#[test]
+fn keyed_missing_values() {
+    let input = b"a,b\n";
+    let mut reader: Reader<16> = Reader::new();
+
+    let result =
+        reader.deserialize_keyed::<heapless::LinearMap<heapless::String<8>, u8, 8>>(&input[..]);
+
+    assert_eq!(
+        result,
+        Err(Error::FieldCount {
+            expected: 2,
+            got: 0
+        })
+    );
+}
+
+#[test]
+fn keyed_key_too_long() {
+    let input = b"temperature,b\n1,2\n";
+    let mut reader: Reader<16> = Reader::new();
+
+    let result =
+        reader.deserialize_keyed::<heapless::LinearMap<heapless::String<8>, u8, 8>>(&input[..]);
+
+    assert_eq!(result, Err(Error::FieldTooLong { len: 11 }));
+    assert_eq!(reader.last_bytes_read(), input.len());
+}
+
+#[test]
+fn keyed_map_too_long() {
+    let input = b"a,b,c\n1,2,3\n";
+    let mut reader: Reader<16> = Reader::new();
+
+    let result =
+        reader.deserialize_keyed::<heapless::LinearMap<heapless::String<8>, u8, 2>>(&input[..]);
+
+    assert_eq!(result, Err(Error::MapTooLong { len: 3 }));
+}
+
+#[test]
+fn keyed_map_too_long_duplicate_keys() {
+    let input = b"a,a,b,c\n1,2,3,4\n";
+    let mut reader: Reader<16> = Reader::new();
+
+    let result =
+        reader.deserialize_keyed::<heapless::LinearMap<heapless::String<8>, u8, 2>>(&input[..]);
+
+    assert_eq!(result, Err(Error::MapTooLong { len: 4 }));
+}
+
+#[test]
+fn keyed_multiline_fields() {
+    let input = b"\"a\nb\",c,d\n1,\"2\n\",3\n";
+    let mut reader: Reader<16> = Reader::new();
+
+    let (map, nread) = reader
+        .deserialize_keyed::<heapless::LinearMap<heapless::String<8>, heapless::String<8>, 4>>(
+            &input[..],
+        )
+        .unwrap();
+
+    assert_eq!(nread, input.len());
+    assert_eq!(
+        map.iter()
+            .map(|(k, v)| (k.as_str(), v.as_str()))
+            .collect::<Vec<_>>(),
+        [("a\nb", "1"), ("c", "2\n"), ("d", "3")]
+    );
+}
+
+#[test]
+fn finished_record_exact_fit() {
+    let input = b"0,1,2\n3,4,5\n";
+    let mut reader: Reader<8> = Reader::new();
+
+    let result = reader.deserialize::<[u8; 3]>(&input[..]);
+
+    assert_eq!(result, Ok(([0, 1, 2], 6)));
+    assert!(reader.finished_record());
+}
+
+#[test]
+fn finished_record_under_read() {
+    let input = b"0,1,2,3\n4,5,6\n";
+    let mut reader: Reader<8> = Reader::new();
+
+    let result = reader.deserialize::<[u8; 3]>(&input[..]);
+
+    assert_eq!(result, Ok(([0, 1, 2], 6)));
+    assert!(!reader.finished_record());
+    assert_eq!(reader.skip_record(&input[6..]), 2);
+    assert!(reader.finished_record());
+}
+
+#[test]
+fn finished_record_terminators() {
+    let mut reader: Reader<8> = Reader::new();
+
+    assert_eq!(reader.deserialize::<(u8, u8)>(b"0,1"), Ok(((0, 1), 3)));
+    assert!(reader.finished_record());
+    assert_eq!(reader.deserialize::<(u8, u8)>(b"0,1\r\n"), Ok(((0, 1), 5)));
+    assert!(reader.finished_record());
+    assert_eq!(reader.deserialize::<(u8, u8)>(b"0,1,\r\n"), Ok(((0, 1), 4)));
+    assert!(!reader.finished_record());
+}
+
+#[test]
+fn finished_record_error() {
+    let mut reader: Reader<8> = Reader::new();
+
+    assert_eq!(
+        reader.deserialize::<(u8, u8)>(b"x,1\n"),
+        Err(Error::InvalidInt(Snippet::new(b"x")))
+    );
+    assert!(!reader.finished_record());
+}
+
+#[test]
+fn require_terminator_missing() {
+    let input = b"a,b\nc,d";
+    let mut reader: Reader<8> = Reader::new();
+    reader.require_terminator(true);
+
+    let first = reader.deserialize::<(char, char)>(&input[..]);
+    let second = reader.deserialize::<(char, char)>(&input[4..]);
+
+    assert_eq!(first, Ok((('a', 'b'), 4)));
+    assert_eq!(second, Err(Error::MissingTerminator));
+}
+
+#[test]
+fn require_terminator_present() {
+    let input = b"a,b\nc,d\n";
+    let mut reader: Reader<8> = Reader::new();
+    reader.require_terminator(true);
+
+    let first = reader.deserialize::<(char, char)>(&input[..]);
+    let second = reader.deserialize::<(char, char)>(&input[4..]);
+
+    assert_eq!(first, Ok((('a', 'b'), 4)));
+    assert_eq!(second, Ok((('c', 'd'), 4)));
+}
+
+#[test]
+fn require_terminator_disabled() {
+    let input = b"c,d";
+    let mut reader: Reader<8> = Reader::new();
+
+    let result = reader.deserialize::<(char, char)>(&input[..]);
+
+    assert_eq!(result, Ok((('c', 'd'), 3)));
+}
+
+#[test]
+fn require_terminator_slices() {
+    let parts: [&[u8]; 2] = [b"a,b\nc,", b"d"];
+    let mut reader: Reader<8> = Reader::new();
+    reader.require_terminator(true);
+
+    let first = reader.deserialize_from_slices::<(char, char)>(&parts);
+    let second = reader.deserialize_from_slices::<(char, char)>(&[&parts[0][4..], parts[1]]);
+
+    assert_eq!(first, Ok((('a', 'b'), 4)));
+    assert_eq!(second, Err(Error::MissingTerminator));
+}
+
+#[test]
+fn max_str_len_at_limit() {
+    let mut reader: Reader<32> = Reader::new();
+    reader.max_str_len(4);
+
+    let result = reader.deserialize::<(heapless::String<32>, u8)>(b"\"a,b\"\"\",1\n");
+
+    assert_eq!(result, Ok((("a,b\"".into(), 1), 10)));
+}
+
+#[test]
+fn max_str_len_over_limit() {
+    let mut reader: Reader<32> = Reader::new();
+    reader.max_str_len(4);
+
+    let result = reader.deserialize::<(heapless::String<32>, u8)>(b"\"a,b\"\"c\",1\n");
+
+    assert_eq!(result, Err(Error::StrTooLong { len: 5 }));
+}
+
+#[test]
+fn max_str_len_ignores_other_types() {
+    let mut reader: Reader<32> = Reader::new();
+    reader.max_str_len(1);
+
+    let result = reader.deserialize::<(u32, char)>(b"12345,\xC3\xA9\n");
+
+    assert_eq!(result, Err(Error::StrTooLong { len: 2 }));
+    assert_eq!(
+        reader.deserialize::<(u32, char)>(b"12345,e\n"),
+        Ok(((12345, 'e'), 8))
+    );
+}
+
+#[test]
+fn scaled() {
+    use serde_csv_core::Scaled;
+
+    let mut reader: Reader<16> = Reader::new();
+
+    let cases: [(&[u8], i16); 9] = [
+        (b"23.45\n", 2345),
+        (b"23.4\n", 2340),
+        (b"23\n", 2300),
+        (b"23.\n", 2300),
+        (b".5\n", 50),
+        (b"-0.05\n", -5),
+        (b"+1.00\n", 100),
+        (b"-327.68\n", i16::MIN),
+        (b"-0\n", 0),
+    ];
+    for (input, expected) in cases {
+        let result = reader.deserialize::<Scaled<i16, 2>>(input);
+        assert_eq!(result, Ok((Scaled(expected), input.len())));
+    }
+}
+
+#[test]
+fn scaled_truncates_extra_digits() {
+    use serde_csv_core::Scaled;
+
+    let mut reader: Reader<16> = Reader::new();
+
+    let result = reader.deserialize::<[Scaled<i32, 2>; 3]>(b"23.459,-0.059,0.001\n");
+
+    assert_eq!(result, Ok(([Scaled(2345), Scaled(-5), Scaled(0)], 20)));
+}
+
+#[test]
+fn scaled_invalid() {
+    use serde_csv_core::Scaled;
+
+    let mut reader: Reader<16> = Reader::new();
+
+    for input in [
+        &b"327.68\n"[..],
+        b"-327.69\n",
+        b"1000\n",
+        b"\n",
+        b"-\n",
+        b".\n",
+        b"1.2.3\n",
+        b"1e2\n",
+        b" 1\n",
+        b"1.2x\n",
+    ] {
+        let result = reader.deserialize::<Scaled<i16, 2>>(input);
+        let value = Snippet::new(&input[..input.len() - 1]);
+        assert_eq!(result, Err(Error::InvalidValue(value)), "{:?}", input);
+    }
+    assert_eq!(
+        reader.deserialize::<Scaled<u8, 1>>(b"-0.1\n"),
+        Err(Error::InvalidValue(Snippet::new(b"-0.1")))
+    );
+    assert_eq!(
+        reader.deserialize::<Scaled<u8, 1>>(b"25.5\n"),
+        Ok((Scaled(255), 5))
+    );
+}
+
+#[cfg(feature = "percent-encoding")]
+#[test]
+fn field_decoder_percent() {
+    let mut reader: Reader<16> = Reader::new();
+    reader.field_decoder(Some(&serde_csv_core::de::PercentDecoder));
+
+    let result =
+        reader.deserialize::<(heapless::String<16>, Option<u8>, i8)>(b"%22%c3%A9%22%2c,,%2D1\n");
+
+    assert_eq!(result, Ok((("\"é\",".into(), None, -1), 22)));
+}
+
+#[cfg(feature = "percent-encoding")]
+#[test]
+fn field_decoder_percent_invalid() {
+    let mut reader: Reader<16> = Reader::new();
+    reader.field_decoder(Some(&serde_csv_core::de::PercentDecoder));
+
+    for input in [&b"a%2\n"[..], b"%\n", b"%G0\n", b"100%\n"] {
+        let result = reader.deserialize::<heapless::String<16>>(input);
+        assert_eq!(result, Err(Error::InvalidEncoding), "{:?}", input);
+    }
+}
+
+#[test]
+fn reject_leading_zeros() {
+    let mut reader: Reader<8> = Reader::new();
+    reader.reject_leading_zeros(true);
+
+    assert_eq!(reader.deserialize::<u16>(b"007\n"), Err(Error::LeadingZero));
+    assert_eq!(reader.deserialize::<i16>(b"-07\n"), Err(Error::LeadingZero));
+    assert_eq!(reader.deserialize::<u16>(b"00\n"), Err(Error::LeadingZero));
+    assert_eq!(reader.deserialize::<u16>(b"0\n"), Ok((0, 2)));
+    assert_eq!(reader.deserialize::<i16>(b"-0\n"), Ok((0, 3)));
+    assert_eq!(reader.deserialize::<u16>(b"7\n"), Ok((7, 2)));
+    assert_eq!(reader.deserialize::<u16>(b"70\n"), Ok((70, 3)));
+    assert_eq!(reader.deserialize::<f32>(b"0.5\n"), Ok((0.5, 4)));
+}
+
+#[test]
+fn leading_zeros_accepted_by_default() {
+    let mut reader: Reader<8> = Reader::new();
+
+    assert_eq!(reader.deserialize::<u16>(b"007\n"), Ok((7, 4)));
+}
+
+#[derive(Debug, PartialEq, serde::Deserialize)]
+struct TaggedGps {
+    lat: f32,
+    lon: f32,
+}
+
+#[derive(Debug, PartialEq, serde::Deserialize)]
+struct TaggedImu {
+    ax: i16,
+    ay: i16,
+    az: i16,
+}
+
+#[derive(Debug, PartialEq)]
+enum TaggedSample {
+    Gps(TaggedGps),
+    Imu(TaggedImu),
+}
+
+fn read_tagged(reader: &mut Reader<16>, input: &[u8]) -> Result<(TaggedSample, usize), Error> {
+    reader.deserialize_tagged(input, |record| match record.tag() {
+        "GPS" => record.deserialize().map(TaggedSample::Gps),
+        "IMU" => record.deserialize().map(TaggedSample::Imu),
+        _ => Err(Error::Custom),
+    })
+}
+
+#[test]
+fn deserialize_tagged() {
+    let input = b"GPS,1.5,-2.5\r\n\"IMU\",1,2,3\n";
+    let mut reader: Reader<16> = Reader::new();
+
+    let (first, n) = read_tagged(&mut reader, input).unwrap();
+    let (second, m) = read_tagged(&mut reader, &input[n..]).unwrap();
+
+    assert_eq!(
+        first,
+        TaggedSample::Gps(TaggedGps {
+            lat: 1.5,
+            lon: -2.5
+        })
+    );
+    assert_eq!(
+        second,
+        TaggedSample::Imu(TaggedImu {
+            ax: 1,
+            ay: 2,
+            az: 3
+        })
+    );
+    assert_eq!(n + m, input.len());
+}
+
+#[test]
+fn deserialize_tagged_unknown() {
+    let input = b"ACC,1,2\nGPS,1,2\n";
+    let mut reader: Reader<16> = Reader::new();
+
+    let result = read_tagged(&mut reader, input);
+    assert_eq!(result, Err(Error::Custom));
+    assert!(!reader.finished_record());
+    let nread = reader.skip_record(&input[4..]) + 4;
+    let (record, _) = read_tagged(&mut reader, &input[nread..]).unwrap();
+
+    assert_eq!(record, TaggedSample::Gps(TaggedGps { lat: 1.0, lon: 2.0 }));
+}
+
+#[test]
+fn deserialize_tagged_field_count() {
+    let mut reader: Reader<16> = Reader::new();
+    reader.with_expected_fields(3);
+
+    let result = read_tagged(&mut reader, b"IMU,1,2,3\n");
+
+    assert_eq!(
+        result,
+        Err(Error::FieldCount {
+            expected: 3,
+            got: 4
+        })
+    );
+}
+
+#[derive(Debug, PartialEq, serde::Deserialize)]
+struct BorrowedRecord<'a> {
+    #[serde(borrow)]
+    clean: std::borrow::Cow<'a, str>,
+    #[serde(borrow)]
+    quoted: std::borrow::Cow<'a, str>,
+}
+
+#[test]
+fn deserialize_borrowed_cow() {
+    let input = b"abc,\"d,\"\"e\"\"\"\n";
+    let mut reader: Reader<16> = Reader::new();
+
+    let (record, n) = reader
+        .deserialize_borrowed::<BorrowedRecord>(&input[..])
+        .unwrap();
+
+    assert!(matches!(record.clean, std::borrow::Cow::Borrowed("abc")));
+    assert!(matches!(record.quoted, std::borrow::Cow::Owned(ref s) if s == "d,\"e\""));
+    assert_eq!(n, 14);
+}
+
+#[test]
+fn deserialize_borrowed_str() {
+    let input = b"abc,\"def\"\n";
+    let mut reader: Reader<16> = Reader::new();
+
+    let first = reader.deserialize_borrowed::<(&str, u8)>(b"abc,1\n");
+    let second = reader.deserialize_borrowed::<(&str, &str)>(&input[..]);
+
+    assert_eq!(first, Ok((("abc", 1), 6)));
+    assert_eq!(second, Err(Error::InvalidType(Snippet::new(b"def"))));
+}
+
+#[derive(Debug, PartialEq, serde::Deserialize)]
+struct FixedWidthRecord {
+    id: u16,
+    name: heapless::String<8>,
+    balance: i32,
+}
+
+const FIXED_WIDTHS: &[usize] = &[4, 8, 6];
+
+#[test]
+fn fixed_widths() {
+    let input = b"0042Alice     -120\r\n0007Bob     003400\n";
+    let mut reader: Reader<16> = Reader::new();
+    reader.fixed_widths(Some(FIXED_WIDTHS)).trim_padding(true);
+
+    let (first, n) = reader.deserialize::<FixedWidthRecord>(input).unwrap();
+    let (second, m) = reader.deserialize::<FixedWidthRecord>(&input[n..]).unwrap();
+
+    assert_eq!(
+        first,
+        FixedWidthRecord {
+            id: 42,
+            name: "Alice".into(),
+            balance: -120
+        }
+    );
+    assert_eq!(
+        second,
+        FixedWidthRecord {
+            id: 7,
+            name: "Bob".into(),
+            balance: 3400
+        }
+    );
+    assert_eq!((n, m), (20, 19));
+}
+
+#[test]
+fn fixed_widths_untrimmed() {
+    let mut reader: Reader<16> = Reader::new();
+    reader.fixed_widths(Some(&[3, 3]));
+
+    let result = reader.deserialize::<(heapless::String<3>, heapless::String<3>)>(b"a,b\"c \n");
+
+    assert_eq!(result, Ok((("a,b".into(), "\"c ".into()), 7)));
+}
+
+#[test]
+fn fixed_widths_short_record() {
+    let mut reader: Reader<16> = Reader::new();
+    reader.fixed_widths(Some(FIXED_WIDTHS)).trim_padding(true);
+
+    let result = reader.deserialize::<FixedWidthRecord>(b"0042Alice    \n");
+
+    assert_eq!(result, Err(Error::ShortRecord { len: 13 }));
+}
+
+#[test]
+fn fixed_widths_missing_terminator() {
+    let mut reader: Reader<16> = Reader::new();
+    reader.fixed_widths(Some(FIXED_WIDTHS)).trim_padding(true);
+
+    let long = reader.deserialize::<FixedWidthRecord>(b"0042Alice     -1200\n");
+    let last = reader.deserialize::<FixedWidthRecord>(b"0042Alice     -120");
+    reader.require_terminator(true);
+    let required = reader.deserialize::<FixedWidthRecord>(b"0042Alice     -120");
+
+    assert_eq!(long, Err(Error::MissingTerminator));
+    assert!(last.is_ok());
+    assert_eq!(required, Err(Error::MissingTerminator));
+}
+
+#[test]
+fn fixed_widths_skip_record() {
+    let input = b"aa\"b,c\nxx\"y,z\n";
+    let mut reader: Reader<16> = Reader::new();
+    reader.fixed_widths(Some(&[2, 4]));
+
+    let (first, mut nread) = reader
+        .deserialize::<(heapless::String<4>,)>(&input[..])
+        .unwrap();
+    assert!(!reader.finished_record());
+    nread += reader.skip_record(&input[nread..]);
+    let (second, _) = reader
+        .deserialize::<(heapless::String<4>, heapless::String<4>)>(&input[nread..])
+        .unwrap();
+
+    assert_eq!(first, ("aa".into(),));
+    assert_eq!(nread, 7);
+    assert_eq!(second, ("xx".into(), "\"y,z".into()));
+}
+
+#[test]
+fn fixed_widths_skip_short_record() {
+    let input = b"0042Alice    \n0007Bob     003400\n";
+    let mut reader: Reader<16> = Reader::new();
+    reader.fixed_widths(Some(FIXED_WIDTHS)).trim_padding(true);
+
+    let result = reader.deserialize::<FixedWidthRecord>(&input[..]);
+    let mut nread = reader.last_bytes_read();
+    nread += reader.skip_record(&input[nread..]);
+    let (record, _) = reader
+        .deserialize::<FixedWidthRecord>(&input[nread..])
+        .unwrap();
+
+    assert_eq!(result, Err(Error::ShortRecord { len: 13 }));
+    assert_eq!(nread, 14);
+    assert_eq!(record.name, "Bob");
+}
+
+#[test]
+fn fixed_widths_find() {
+    let input = b"aa\"b,c\nxx\"y,z\n";
+    let mut reader: Reader<16> = Reader::new();
+    reader.fixed_widths(Some(&[2, 4]));
+    type Record = (heapless::String<4>, heapless::String<4>);
+
+    let by_key = reader.find_by_key::<Record>(&input[..], b"xx");
+    let by_field =
+        reader.find_record::<Record, _>(&input[..], |i, field| i == 1 && field == b"\"y,z");
+    let missing = reader.find_by_key::<Record>(&input[..], b"\"y");
+
+    assert_eq!(by_key, Ok(Some((("xx".into(), "\"y,z".into()), 14))));
+    assert_eq!(by_field, Ok(Some((("xx".into(), "\"y,z".into()), 7))));
+    assert_eq!(missing, Ok(None));
+}
+
+#[test]
+fn fixed_widths_empty_input() {
+    let mut reader: Reader<16> = Reader::new();
+    reader.fixed_widths(Some(FIXED_WIDTHS));
+
+    let result = reader.deserialize::<FixedWidthRecord>(b"");
+
+    assert_eq!(result, Err(Error::InvalidLength { len: 0 }));
+}
+
+#[test]
+fn error_snippet_display() {
+    let mut reader: Reader<32> = Reader::new();
+
+    let int = reader.deserialize::<u32>(b"12O34\n").unwrap_err();
+    let bool = reader.deserialize::<bool>(b"yes\n").unwrap_err();
+    let float = reader.deserialize::<f32>(b"\"1,5\"\n").unwrap_err();
+
+    assert_eq!(int.to_string(), "Invalid integer `12O34`.");
+    assert_eq!(
+        bool.to_string(),
+        "Invalid boolean value `yes`. Expected either `true` or `false`."
+    );
+    assert_eq!(float.to_string(), "Invalid floating-point number `1,5`.");
+}
+
+#[test]
+fn error_snippet_truncated() {
+    let mut reader: Reader<32> = Reader::new();
+
+    let result = reader.deserialize::<u64>(b"1234567890abcdefghij\n");
+
+    let Err(Error::InvalidInt(snippet)) = result else {
+        panic!("unexpected result: {:?}", result);
+    };
+    assert_eq!(snippet.as_bytes(), b"1234567890abcdef");
+    assert!(snippet.is_truncated());
+    assert_eq!(snippet.to_string(), "1234567890abcdef...");
+    assert_eq!(snippet, Snippet::new(b"1234567890abcdefXYZ"));
+}
+
+#[test]
+fn error_snippet_non_utf8() {
+    let mut reader: Reader<32> = Reader::new();
+
+    let result = reader.deserialize::<i32>(b"1\xFF2\t`\n");
+
+    assert_eq!(result, Err(Error::InvalidInt(Snippet::new(b"1\xFF2\t`"))));
+    assert_eq!(
+        result.unwrap_err().to_string(),
+        "Invalid integer `1\\xFF2\\x09``."
+    );
+}
+
+#[test]
+fn serde_error_variants() {
+    let mut reader: Reader<16> = Reader::new();
+    let mut headers = Headers::<4, 32>::new();
+
+    let variant = reader.deserialize::<Event>(b"Explosion,1\n");
+    let value = reader.deserialize::<core::num::NonZeroU8>(b"0\n");
+    let length = reader.deserialize::<(u8, u8, u8)>(b"1,2\n");
+    let nread = reader.read_headers(b"sensor\n", &mut headers).unwrap();
+    let field = reader.deserialize_with_headers::<Reading, 4, 32>(b"t1\n", &headers);
+
+    assert_eq!(nread, 7);
+    assert_eq!(
+        variant,
+        Err(Error::UnknownVariant(Snippet::new(b"Explosion")))
+    );
+    assert_eq!(value, Err(Error::InvalidValue(Snippet::new(b"0"))));
+    assert_eq!(length, Err(Error::InvalidLength { len: 2 }));
+    assert_eq!(field, Err(Error::MissingField("value")));
+}
+
+#[test]
+fn serde_error_display() {
+    use serde::de::Error as _;
+
+    let long = "a_very_long_variant_name";
+
+    assert_eq!(
+        Error::invalid_type(serde::de::Unexpected::Unit, &"a u8").to_string(),
+        "Invalid type of `unit value`."
+    );
+    assert_eq!(
+        Error::invalid_value(serde::de::Unexpected::Signed(-3), &"a u8").to_string(),
+        "Invalid value `-3`."
+    );
+    assert_eq!(
+        Error::invalid_value(serde::de::Unexpected::Unit, &"a u8").to_string(),
+        "Invalid value `unit value`."
+    );
+    assert_eq!(
+        Error::unknown_variant(long, &["A"]).to_string(),
+        "Unknown variant `a_very_long_vari...`."
+    );
+    assert_eq!(
+        Error::unknown_field("x", &["y"]).to_string(),
+        "Unknown field `x`."
+    );
+    assert_eq!(
+        Error::duplicate_field("y").to_string(),
+        "Duplicate field `y`."
+    );
+}
+
+#[derive(Debug, serde::Deserialize)]
+#[allow(dead_code)]
+struct Station {
+    id: u16,
+    #[serde(rename = "temp")]
+    temperature: f32,
+    humidity: u8,
+}
+
+fn header_mismatch(index: usize, expected: &str, found: &str) -> Error {
+    Error::HeaderMismatch {
+        index,
+        expected: Snippet::new(expected.as_bytes()),
+        found: Snippet::new(found.as_bytes()),
+    }
+}
+
+#[test]
+fn validate_headers_exact() {
+    let input = b"id,temp,humidity\r\n1,20.5,40\n";
+    let mut reader: Reader<16> = Reader::new();
+
+    let nread = reader.validate_headers::<Station>(&input[..]);
+
+    assert_eq!(nread, Ok(18));
+}
+
+#[test]
+fn validate_headers_reordered() {
+    let input = b"humidity,id,temp\n";
+    let mut reader: Reader<16> = Reader::new();
+
+    let ordered = reader.validate_headers::<Station>(&input[..]);
+    reader.unordered_headers(true);
+    let unordered = reader.validate_headers::<Station>(&input[..]);
+
+    assert_eq!(ordered, Err(header_mismatch(0, "id", "humidity")));
+    assert_eq!(unordered, Ok(17));
+}
+
+#[test]
+fn validate_headers_typo() {
+    let mut reader: Reader<16> = Reader::new();
+
+    let ordered = reader.validate_headers::<Station>(b"id,temperature,humidity\n");
+    reader.unordered_headers(true);
+    let unordered = reader.validate_headers::<Station>(b"humidty,temp,id\n");
+
+    assert_eq!(ordered, Err(header_mismatch(1, "temp", "temperature")));
+    assert_eq!(unordered, Err(header_mismatch(0, "", "humidty")));
+}
+
+#[test]
+fn validate_headers_column_count() {
+    let mut reader: Reader<16> = Reader::new();
+
+    let missing = reader.validate_headers::<Station>(b"id,temp\n");
+    let extra = reader.validate_headers::<Station>(b"id,temp,humidity,wind\n");
+    reader.unordered_headers(true);
+    let missing_unordered = reader.validate_headers::<Station>(b"temp,id\n");
+    let duplicate_unordered = reader.validate_headers::<Station>(b"temp,id,temp\n");
+
+    assert_eq!(missing, Err(header_mismatch(2, "humidity", "")));
+    assert_eq!(extra, Err(header_mismatch(3, "", "wind")));
+    assert_eq!(missing_unordered, Err(header_mismatch(2, "humidity", "")));
+    assert_eq!(duplicate_unordered, Err(header_mismatch(2, "", "temp")));
+}
+
+#[test]
+fn validate_headers_not_a_struct() {
+    let mut reader: Reader<16> = Reader::new();
+
+    let result = reader.validate_headers::<(u16, f32)>(b"id,temp\n");
+
+    assert!(matches!(result, Err(Error::Unsupported(_))));
+}
+
+#[test]
+fn validate_headers_alias() {
+    #[derive(serde::Deserialize)]
+    #[allow(dead_code)]
+    struct Sensor {
+        #[serde(alias = "device_id")]
+        dev_id: u16,
+        #[serde(alias = "t", alias = "temperature")]
+        temp: i8,
+    }
+    let mut reader: Reader<16> = Reader::new();
+
+    let alias = reader.validate_headers::<Sensor>(b"device_id,temp\n");
+    let typo = reader.validate_headers::<Sensor>(b"dev_id,tmp\n");
+    reader.unordered_headers(true);
+    let unordered = reader.validate_headers::<Sensor>(b"temperature,dev_id\n");
+    let duplicate = reader.validate_headers::<Sensor>(b"t,temp\n");
+
+    assert_eq!(alias, Ok(15));
+    assert_eq!(typo, Err(header_mismatch(1, "t", "tmp")));
+    assert_eq!(unordered, Ok(19));
+    assert_eq!(duplicate, Err(header_mismatch(1, "", "temp")));
+}
+
+#[test]
+fn records_lenient() {
+    let input = b"t1,20\r\nt2,x,\"a\nb\"\r\nt3,-5\r\n";
+    let mut reader: Reader<8> = Reader::new();
+
+    let records: Vec<_> = reader
+        .records_lenient::<(heapless::String<4>, i8)>(&input[..])
+        .collect();
+
+    assert_eq!(
+        records,
+        [
+            Ok(("t1".into(), 20)),
+            Err((Error::InvalidInt(Snippet::new(b"x")), 7..19)),
+            Ok(("t3".into(), -5)),
+        ]
+    );
+}
+
+#[test]
+fn records_lenient_last_record() {
+    let input = b"1,2\n3";
+    let mut reader: Reader<8> = Reader::new();
+
+    let mut records = reader.records_lenient::<(u8, u8)>(&input[..]);
+
+    assert_eq!(records.next(), Some(Ok((1, 2))));
+    assert_eq!(
+        records.next(),
+        Some(Err((Error::InvalidLength { len: 1 }, 4..5)))
+    );
+    assert_eq!(records.next(), None);
+}
+
+#[test]
+fn records_lenient_unread_fields() {
+    let input = b"1,2,3\n4,5,6\n";
+    let mut reader: Reader<8> = Reader::new();
+
+    let records: Vec<_> = reader.records_lenient::<(u8, u8)>(&input[..]).collect();
+
+    assert_eq!(records, [Ok((1, 2)), Ok((4, 5))]);
+}
+
+#[derive(Debug, PartialEq, serde::Deserialize)]
+struct Device {
+    device_id: u16,
+    #[serde(alias = "temp")]
+    temperature: i8,
+}
+
+#[derive(Debug, PartialEq, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct CamelDevice {
+    device_id: u16,
+    battery_level: u8,
+}
+
+#[derive(Debug, PartialEq, serde::Deserialize)]
+#[allow(non_snake_case)]
+struct AmbiguousDevice {
+    id: u16,
+    ID: u16,
+}
+
+#[test]
+fn case_insensitive_headers_snake_case() {
+    let input = b"TEMP,Device_ID\n-3,17\n";
+    let mut reader: Reader<16> = Reader::new();
+    reader.case_insensitive_headers(true);
+    let mut headers = Headers::<4, 32>::new();
+
+    let nread = reader.read_headers(&input[..], &mut headers).unwrap();
+    let result = reader.deserialize_with_headers(&input[nread..], &headers);
+
+    assert_eq!(
+        result,
+        Ok((
+            Device {
+                device_id: 17,
+                temperature: -3
+            },
+            6
+        ))
+    );
+}
+
+#[test]
+fn case_insensitive_headers_rename_all() {
+    let input = b"DEVICEID,batterylevel\n17,80\n";
+    let mut reader: Reader<16> = Reader::new();
+    reader.case_insensitive_headers(true);
+    let mut headers = Headers::<4, 32>::new();
+
+    let nread = reader.read_headers(&input[..], &mut headers).unwrap();
+    let result = reader.deserialize_with_headers(&input[nread..], &headers);
+
+    assert_eq!(
+        result,
+        Ok((
+            CamelDevice {
+                device_id: 17,
+                battery_level: 80
+            },
+            6
+        ))
+    );
+    assert_eq!(
+        reader.validate_headers::<CamelDevice>(&input[..]),
+        Ok(nread)
+    );
+}
+
+#[test]
+fn case_sensitive_headers_unmatched() {
+    let input = b"DeviceId,batteryLevel\n17,80\n";
+    let mut reader: Reader<16> = Reader::new();
+    let mut headers = Headers::<4, 32>::new();
+
+    let nread = reader.read_headers(&input[..], &mut headers).unwrap();
+    let result = reader.deserialize_with_headers::<CamelDevice, 4, 32>(&input[nread..], &headers);
+
+    assert_eq!(result, Err(Error::MissingField("deviceId")));
+}
+
+#[test]
+fn case_insensitive_headers_ambiguous_fields() {
+    let input = b"id,other\n1,2\n";
+    let mut reader: Reader<16> = Reader::new();
+    reader.case_insensitive_headers(true);
+    let mut headers = Headers::<4, 32>::new();
+
+    let validated = reader.validate_headers::<AmbiguousDevice>(&input[..]);
+    let nread = reader.read_headers(&input[..], &mut headers).unwrap();
+    let result =
+        reader.deserialize_with_headers::<AmbiguousDevice, 4, 32>(&input[nread..], &headers);
+
+    assert_eq!(validated, Err(Error::AmbiguousField("ID")));
+    assert_eq!(result, Err(Error::AmbiguousField("ID")));
+}
+
+#[derive(Debug, PartialEq, serde::Deserialize)]
+struct PartialReading {
+    sensor: heapless::String<8>,
+    unit: Option<heapless::String<4>>,
+    #[serde(default)]
+    samples: u8,
+}
+
+#[test]
+fn headers_missing_optional_columns() {
+    let input = b"sensor\nt1\n";
+    let mut reader: Reader<8> = Reader::new();
+    let mut headers = Headers::<4, 32>::new();
+
+    let nread = reader.read_headers(&input[..], &mut headers).unwrap();
+    let result = reader.deserialize_with_headers(&input[nread..], &headers);
+
+    assert_eq!(
+        result,
+        Ok((
+            PartialReading {
+                sensor: "t1".into(),
+                unit: None,
+                samples: 0
+            },
+            3
+        ))
+    );
+}
+
+#[test]
+fn headers_present_optional_columns() {
+    let input = b"samples,unit,sensor\n4,C,t1\n";
+    let mut reader: Reader<8> = Reader::new();
+    let mut headers = Headers::<4, 32>::new();
+
+    let nread = reader.read_headers(&input[..], &mut headers).unwrap();
+    let result = reader.deserialize_with_headers(&input[nread..], &headers);
+
+    assert_eq!(
+        result,
+        Ok((
+            PartialReading {
+                sensor: "t1".into(),
+                unit: Some("C".into()),
+                samples: 4
+            },
+            7
+        ))
+    );
+}
+
+#[test]
+fn headers_missing_required_column() {
+    let input = b"unit,samples\nC,4\n";
+    let mut reader: Reader<8> = Reader::new();
+    let mut headers = Headers::<4, 32>::new();
+
+    let nread = reader.read_headers(&input[..], &mut headers).unwrap();
+    let result =
+        reader.deserialize_with_headers::<PartialReading, 4, 32>(&input[nread..], &headers);
+
+    assert_eq!(result, Err(Error::MissingField("sensor")));
+    assert_eq!(reader.last_bytes_read(), 4);
+}
@@ -1,2 +1,4 @@
+mod compile;
 mod de;
+mod roundtrip;
 mod ser;
@@ -0,0 +1,765 @@
+use core::fmt::Debug;
+use serde::{Deserialize, Serialize};
+use serde_csv_core::{Reader, Writer};
+
+fn roundtrip<T>(value: T, csv: &str)
+where
+    T: Serialize + for<'de> Deserialize<'de> + PartialEq + Debug,
+{
+    let mut writer = Writer::new();
+    let mut buf = [0; 256];
+    let nwritten = writer.serialize(&value, &mut buf).unwrap();
+    assert_eq!(core::str::from_utf8(&buf[..nwritten]).unwrap(), csv);
+
+    let mut reader: Reader<32> = Reader::new();
+    let result = reader.deserialize::<T>(&buf[..nwritten]);
+    assert_eq!(result, Ok((value, nwritten)));
+}
+
+#[test]
+fn i8() {
+    roundtrip(i8::MIN, "-128\n");
+    roundtrip(0i8, "0\n");
+    roundtrip(i8::MAX, "127\n");
+}
+
+#[test]
+fn i16() {
+    roundtrip(i16::MIN, "-32768\n");
+    roundtrip(0i16, "0\n");
+    roundtrip(i16::MAX, "32767\n");
+}
+
+#[test]
+fn i32() {
+    roundtrip(i32::MIN, "-2147483648\n");
+    roundtrip(0i32, "0\n");
+    roundtrip(i32::MAX, "2147483647\n");
+}
+
+#[test]
+fn i64() {
+    roundtrip(i64::MIN, "-9223372036854775808\n");
+    roundtrip(0i64, "0\n");
+    roundtrip(i64::MAX, "9223372036854775807\n");
+}
+
+#[test]
+fn u8() {
+    roundtrip(u8::MIN, "0\n");
+    roundtrip(u8::MAX, "255\n");
+}
+
+#[test]
+fn u16() {
+    roundtrip(u16::MIN, "0\n");
+    roundtrip(u16::MAX, "65535\n");
+}
+
+#[test]
+fn u32() {
+    roundtrip(u32::MIN, "0\n");
+    roundtrip(u32::MAX, "4294967295\n");
+}
+
+#[test]
+fn u64() {
+    roundtrip(u64::MIN, "0\n");
+    roundtrip(u64::MAX, "18446744073709551615\n");
+}
+
+#[test]
+fn f32() {
+    roundtrip(f32::MIN, "-3.4028235e38\n");
+    roundtrip(0f32, "0.0\n");
+    roundtrip(f32::MAX, "3.4028235e38\n");
+    roundtrip(f32::MIN_POSITIVE, "1.1754944e-38\n");
+    roundtrip(0.1f32, "0.1\n");
+}
+
+#[test]
+fn f64() {
+    roundtrip(f64::MIN, "-1.7976931348623157e308\n");
+    roundtrip(0f64, "0.0\n");
+    roundtrip(f64::MAX, "1.7976931348623157e308\n");
+    roundtrip(f64::MIN_POSITIVE, "2.2250738585072014e-308\n");
+    roundtrip(0.1f64, "0.1\n");
+}
+
+#[test]
+fn all_widths_in_one_record() {
+    roundtrip(
+        (
+            i8::MIN,
+            i16::MIN,
+            i32::MIN,
+            i64::MIN,
+            u8::MAX,
+            u16::MAX,
+            u32::MAX,
+            u64::MAX,
+            f32::MAX,
+            f64::MIN_POSITIVE,
+        ),
+        "-128,-32768,-2147483648,-9223372036854775808,255,65535,4294967295,\
+         18446744073709551615,3.4028235e38,2.2250738585072014e-308\n",
+    );
+}
+
+/// Doubles the second field of a record, using both error types with `?`.
+fn double_second(input: &[u8], output: &mut [u8]) -> Result<usize, serde_csv_core::Error> {
+    let mut reader: Reader<8> = Reader::new();
+    let ((a, b), _) = reader.deserialize::<(u8, u8)>(input)?;
+    let nwritten = Writer::new().serialize(&(a, u16::from(b) * 2), output)?;
+    Ok(nwritten)
+}
+
+#[test]
+fn unified_error() {
+    use serde_csv_core::{de, ser, Error};
+
+    let mut buf = [0; 8];
+
+    assert_eq!(double_second(b"1,200\n", &mut buf), Ok(6));
+    assert_eq!(&buf[..6], b"1,400\n");
+    assert_eq!(
+        double_second(b"1,x\n", &mut buf),
+        Err(Error::De(de::Error::InvalidInt(de::Snippet::new(b"x"))))
+    );
+    assert_eq!(
+        double_second(b"1,200\n", &mut buf[..4]),
+        Err(Error::Ser(ser::Error::Overflow))
+    );
+}
+
+#[test]
+fn unified_error_display() {
+    use serde_csv_core::{de, ser, Error};
+
+    assert_eq!(
+        Error::from(de::Error::InvalidInt(de::Snippet::new(b"x"))).to_string(),
+        de::Error::InvalidInt(de::Snippet::new(b"x")).to_string()
+    );
+    assert_eq!(
+        Error::from(ser::Error::Overflow).to_string(),
+        ser::Error::Overflow.to_string()
+    );
+}
+
+#[test]
+fn wide_arrays() {
+    // `serde` implements traits for arrays of up to 32 elements.
+    let mut value = ([0u16; 32], [0u16; 32]);
+    for (i, v) in value.0.iter_mut().chain(value.1.iter_mut()).enumerate() {
+        *v = (i * 1000) as u16;
+    }
+
+    let mut writer = Writer::new();
+    let mut buf = [0; 512];
+    let nwritten = writer.serialize(&value, &mut buf).unwrap();
+    let csv = core::str::from_utf8(&buf[..nwritten]).unwrap();
+    assert_eq!(csv.split(',').count(), 64);
+    assert!(csv.ends_with(",62000,63000\n"));
+
+    let mut reader: Reader<8> = Reader::new();
+    let result = reader.deserialize::<([u16; 32], [u16; 32])>(&buf[..nwritten]);
+    assert_eq!(result, Ok((value, nwritten)));
+}
+
+#[test]
+fn wide_vec() {
+    let value: heapless::Vec<u16, 1000> = (0..1000).collect();
+
+    let mut writer = Writer::new();
+    let mut buf = [0; 4096];
+    let nwritten = writer.serialize(&value, &mut buf).unwrap();
+    let csv = core::str::from_utf8(&buf[..nwritten]).unwrap();
+    assert_eq!(csv.split(',').count(), 1000);
+
+    let mut reader: Reader<8> = Reader::new();
+    let result = reader.deserialize::<heapless::Vec<u16, 1000>>(&buf[..nwritten]);
+    assert_eq!(result, Ok((value, nwritten)));
+}
+
+fn roundtrip_null_literal<T>(value: T, csv: &str)
+where
+    T: Serialize + for<'de> Deserialize<'de> + PartialEq + Debug,
+{
+    let mut writer = Writer::new();
+    writer.null_literal(Some(b"\\N"));
+    let mut buf = [0; 256];
+    let nwritten = writer.serialize(&value, &mut buf).unwrap();
+    assert_eq!(core::str::from_utf8(&buf[..nwritten]).unwrap(), csv);
+
+    let mut reader: Reader<32> = Reader::new();
+    reader.null_literal(Some(b"\\N"));
+    let result = reader.deserialize::<T>(&buf[..nwritten]);
+    assert_eq!(result, Ok((value, nwritten)));
+}
+
+fn roundtrip_nested_option<T>(value: T, csv: &str)
+where
+    T: Serialize + for<'de> Deserialize<'de> + PartialEq + Debug,
+{
+    let mut writer = Writer::new();
+    let mut buf = [0; 256];
+    let nwritten = writer.serialize(&value, &mut buf).unwrap();
+    assert_eq!(core::str::from_utf8(&buf[..nwritten]).unwrap(), csv);
+
+    let mut reader: Reader<32> = Reader::new();
+    reader.quoted_empty_is_none(false);
+    let result = reader.deserialize::<T>(&buf[..nwritten]);
+    assert_eq!(result, Ok((value, nwritten)));
+}
+
+#[test]
+fn nested_option() {
+    roundtrip_nested_option((0u8, None::<Option<u8>>, 2u8), "0,,2\n");
+    roundtrip_nested_option((0u8, Some(Some(1u8)), 2u8), "0,1,2\n");
+    roundtrip_nested_option([None, Some(Some(heapless::String::<8>::from("a")))], ",a\n");
+}
+
+#[test]
+fn null_literal() {
+    roundtrip_null_literal(
+        (
+            None::<heapless::String<8>>,
+            Some(heapless::String::<8>::new()),
+        ),
+        "\\N,\n",
+    );
+    roundtrip_null_literal((Some(1u8), None::<u8>, Some(3u8)), "1,\\N,3\n");
+    roundtrip_null_literal(
+        (Some(heapless::String::<8>::from("N")), None::<bool>),
+        "N,\\N\n",
+    );
+}
+
+#[test]
+fn nested_read_back() {
+    let record = (
+        1u8,
+        serde_csv_core::Nested::<_, b';', 32>(("a,b", "c\"d;e")),
+    );
+    let mut writer = Writer::new();
+    let mut buf = [0; 64];
+    let nwritten = writer.serialize(&record, &mut buf).unwrap();
+
+    let mut reader: Reader<32> = Reader::new();
+    let ((id, inner), _) = reader
+        .deserialize::<(u8, heapless::String<32>)>(&buf[..nwritten])
+        .unwrap();
+    let mut inner_reader: Reader<32> =
+        Reader::from_builder(serde_csv_core::csv_core::ReaderBuilder::new().delimiter(b';'));
+    let (fields, _) = inner_reader
+        .deserialize::<(heapless::String<8>, heapless::String<8>)>(inner.as_bytes())
+        .unwrap();
+
+    assert_eq!(id, 1);
+    assert_eq!(fields, ("a,b".into(), "c\"d;e".into()));
+}
+
+#[test]
+fn nested() {
+    type Codes = serde_csv_core::Nested<(heapless::String<8>, heapless::String<8>, u16), b';', 32>;
+
+    roundtrip((1u8, Codes::default(), 2u8), "1,;;0,2\n");
+    roundtrip(
+        (
+            1u8,
+            serde_csv_core::Nested::<_, b';', 32>(("a,b".into(), "c\"d;e".into(), 404u16)) as Codes,
+            2u8,
+        ),
+        "1,\"a,b;\"\"c\"\"\"\"d;e\"\";404\",2\n",
+    );
+}
+
+#[test]
+fn nested_empty() {
+    roundtrip((1u8, serde_csv_core::Nested::<[u8; 0]>([]), 2u8), "1,,2\n");
+    roundtrip(
+        (
+            serde_csv_core::Nested::<()>(()),
+            serde_csv_core::Nested::<heapless::Vec<u8, 4>, b' '>(
+                heapless::Vec::from_slice(&[1, 2]).unwrap(),
+            ),
+        ),
+        "\"\"\"\"\"\",1 2\n",
+    );
+}
+
+#[test]
+fn arrayvec() {
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Sample {
+        name: arrayvec::ArrayString<16>,
+        values: arrayvec::ArrayVec<i32, 4>,
+    }
+
+    roundtrip(
+        Sample {
+            name: arrayvec::ArrayString::from("Cracow, PL").unwrap(),
+            values: arrayvec::ArrayVec::from([1, -2, 3, -4]),
+        },
+        "\"Cracow, PL\",1,-2,3,-4\n",
+    );
+    roundtrip(
+        Sample {
+            name: arrayvec::ArrayString::new(),
+            values: [7].into_iter().collect(),
+        },
+        ",7\n",
+    );
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Flags {
+    #[serde(with = "serde_csv_core::bits8")]
+    flags: [bool; 8],
+    #[serde(with = "serde_csv_core::bits8")]
+    byte: u8,
+}
+
+#[test]
+fn bits8() {
+    roundtrip(
+        Flags {
+            flags: [true; 8],
+            byte: 0xFF,
+        },
+        "0xFF,0xFF\n",
+    );
+    roundtrip(
+        Flags {
+            flags: [false; 8],
+            byte: 0,
+        },
+        "0x00,0x00\n",
+    );
+    roundtrip(
+        Flags {
+            flags: [true, true, false, false, true, false, true, false],
+            byte: 0x5A,
+        },
+        "0x53,0x5A\n",
+    );
+}
+
+#[test]
+fn checksum() {
+    use serde_csv_core::Checksum;
+
+    let value = ("Cracow, Poland", 766_683u32, Some(-1.5f32));
+    for checksum in [Checksum::Crc8, Checksum::Crc16Ccitt, Checksum::Crc32] {
+        let mut writer = Writer::new();
+        writer.checksum(Some(checksum));
+        let mut buf = [0; 64];
+        let nwritten = writer.serialize(&value, &mut buf).unwrap();
+
+        let mut reader: Reader<32> = Reader::new();
+        reader.checksum(Some(checksum));
+        for split in 0..=nwritten {
+            let parts = [&buf[..split], &buf[split..nwritten]];
+            let result =
+                reader.deserialize_from_slices::<(heapless::String<16>, u32, Option<f32>)>(&parts);
+            assert_eq!(
+                result,
+                Ok((("Cracow, Poland".into(), 766_683, Some(-1.5)), nwritten))
+            );
+        }
+    }
+}
+
+#[cfg(feature = "fugit")]
+#[test]
+fn fugit_ticks() {
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Record {
+        #[serde(with = "serde_csv_core::fugit::ticks")]
+        millis: fugit::MillisDurationU32,
+        #[serde(with = "serde_csv_core::fugit::ticks")]
+        ticks_32khz: fugit::Duration<u64, 1, 32_768>,
+        #[serde(with = "serde_csv_core::fugit::ticks")]
+        nanos: fugit::NanosDurationU64,
+    }
+
+    roundtrip(
+        Record {
+            millis: fugit::MillisDurationU32::from_ticks(1500),
+            ticks_32khz: fugit::Duration::<u64, 1, 32_768>::secs(2),
+            nanos: fugit::NanosDurationU64::from_ticks(u64::MAX),
+        },
+        "1500,65536,18446744073709551615\n",
+    );
+}
+
+#[cfg(feature = "fugit")]
+#[test]
+fn fugit_millis() {
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Record {
+        #[serde(with = "serde_csv_core::fugit::millis")]
+        micros: fugit::MicrosDurationU32,
+        #[serde(with = "serde_csv_core::fugit::millis")]
+        ticks_32khz: fugit::Duration<u64, 1, 32_768>,
+        #[serde(with = "serde_csv_core::fugit::millis")]
+        secs: fugit::SecsDurationU32,
+    }
+
+    roundtrip(
+        Record {
+            micros: fugit::MicrosDurationU32::millis(250),
+            ticks_32khz: fugit::Duration::<u64, 1, 32_768>::from_ticks(32_768 * 3),
+            secs: fugit::SecsDurationU32::secs(7),
+        },
+        "250,3000,7000\n",
+    );
+}
+
+#[cfg(feature = "fugit")]
+#[test]
+fn fugit_millis_out_of_range() {
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Record {
+        #[serde(with = "serde_csv_core::fugit::millis")]
+        micros: fugit::MicrosDurationU32,
+    }
+
+    let max_millis = u32::MAX / 1000;
+    roundtrip(
+        Record {
+            micros: fugit::MicrosDurationU32::millis(max_millis),
+        },
+        "4294967\n",
+    );
+
+    let mut reader: Reader<16> = Reader::new();
+    assert_eq!(
+        reader.deserialize::<Record>(b"4294968\n"),
+        Err(serde_csv_core::de::Error::InvalidValue(
+            serde_csv_core::de::Snippet::new(b"4294968")
+        ))
+    );
+    assert_eq!(
+        reader.deserialize::<Record>(b"4294967295\n"),
+        Err(serde_csv_core::de::Error::InvalidValue(
+            serde_csv_core::de::Snippet::new(b"4294967295")
+        ))
+    );
+
+    #[derive(Debug, PartialEq, Serialize)]
+    struct Secs {
+        #[serde(with = "serde_csv_core::fugit::millis")]
+        secs: fugit::SecsDurationU32,
+    }
+
+    let record = Secs {
+        secs: fugit::SecsDurationU32::secs(u32::MAX),
+    };
+    assert_eq!(
+        Writer::new().serialize(&record, &mut [0; 16]),
+        Err(serde_csv_core::ser::Error::Custom)
+    );
+}
+
+#[test]
+fn count() {
+    let mut writer = Writer::new();
+    writer.check_field_count(true).sequence_column(Some(1));
+    let mut buf = [0; 64];
+    let mut nwritten = writer.write_count(2, &mut buf).unwrap();
+    nwritten += writer.serialize(&("a", 1), &mut buf[nwritten..]).unwrap();
+    nwritten += writer.serialize(&("b", 2), &mut buf[nwritten..]).unwrap();
+    assert_eq!(&buf[..nwritten], b"2\n1,a,1\n2,b,2\n");
+
+    let mut reader: Reader<8> = Reader::new();
+    let (count, mut nread) = reader.read_count(&buf[..nwritten]).unwrap();
+    assert_eq!(count, 2);
+    for i in 1..=count {
+        let (record, n) = reader
+            .deserialize::<(u32, char, u8)>(&buf[nread..nwritten])
+            .unwrap();
+        assert_eq!(record.0, i);
+        nread += n;
+    }
+    assert_eq!(nread, nwritten);
+}
+
+#[test]
+fn crc_column() {
+    use serde_csv_core::Checksum;
+
+    let value: (heapless::String<4>, i16, f32) = ("t1".into(), -12, 1.5);
+    for checksum in [Checksum::Crc8, Checksum::Crc16Ccitt] {
+        let mut writer = Writer::new();
+        let mut buf = [0; 32];
+        let nwritten = writer
+            .serialize_with_crc(&value, checksum, &mut buf)
+            .unwrap();
+
+        let mut reader: Reader<16> = Reader::new();
+        let result = reader.deserialize_with_crc(&buf[..nwritten], checksum);
+
+        assert_eq!(result, Ok((value.clone(), nwritten)));
+    }
+}
+
+#[test]
+fn crc_column_corrupted() {
+    use serde_csv_core::de::Error;
+    use serde_csv_core::Checksum;
+
+    for checksum in [Checksum::Crc8, Checksum::Crc16Ccitt] {
+        let mut writer = Writer::new();
+        let mut buf = [0; 32];
+        let nwritten = writer
+            .serialize_with_crc(&("t1", -12i16), checksum, &mut buf)
+            .unwrap();
+        buf[3] = b'+';
+
+        let mut reader: Reader<16> = Reader::new();
+        let result =
+            reader.deserialize_with_crc::<(heapless::String<4>, i16)>(&buf[..nwritten], checksum);
+        assert!(matches!(result, Err(Error::ChecksumMismatch { .. })));
+        assert_eq!(reader.last_bytes_read(), nwritten);
+    }
+}
+
+#[test]
+fn count_checksum() {
+    let mut writer = Writer::new();
+    writer.checksum(Some(serde_csv_core::Checksum::Crc16Ccitt));
+    let mut buf = [0; 32];
+    let nwritten = writer.write_count(u32::MAX, &mut buf).unwrap();
+
+    let mut reader: Reader<16> = Reader::new();
+    reader.checksum(Some(serde_csv_core::Checksum::Crc16Ccitt));
+    assert_eq!(
+        reader.read_count(&buf[..nwritten]),
+        Ok((u32::MAX, nwritten))
+    );
+}
+
+#[test]
+fn count_invalid() {
+    let mut reader: Reader<8> = Reader::new();
+
+    assert_eq!(
+        reader.read_count(b"2,3\n"),
+        Err(serde_csv_core::de::Error::FieldCount {
+            expected: 1,
+            got: 2
+        })
+    );
+    assert_eq!(
+        reader.read_count(b"-1\n"),
+        Err(serde_csv_core::de::Error::InvalidInt(
+            serde_csv_core::de::Snippet::new(b"-1")
+        ))
+    );
+}
+
+#[test]
+fn scaled() {
+    use serde_csv_core::Scaled;
+
+    roundtrip(Scaled::<i16, 2>(2345), "23.45\n");
+    roundtrip(Scaled::<i16, 2>(-5), "-0.05\n");
+    roundtrip(Scaled::<i16, 2>(0), "0.00\n");
+    roundtrip(Scaled::<i16, 2>(i16::MIN), "-327.68\n");
+    roundtrip(Scaled::<i16, 0>(-12), "-12\n");
+    roundtrip(Scaled::<u8, 4>(7), "0.0007\n");
+    roundtrip(Scaled::<i64, 3>(i64::MIN), "-9223372036854775.808\n");
+    roundtrip(Scaled::<u64, 1>(u64::MAX), "1844674407370955161.5\n");
+}
+
+#[cfg(feature = "percent-encoding")]
+#[test]
+fn percent_encoding() {
+    let record = ("a b, c", "x\"y", 1.5);
+    let mut writer = Writer::new();
+    writer.field_encoder(Some(&serde_csv_core::ser::PercentEncoder));
+    let mut buf = [0; 64];
+    let nwritten = writer.serialize(&record, &mut buf).unwrap();
+    assert_eq!(&buf[..nwritten], b"a%20b%2C%20c,x%22y,1.5\n");
+
+    let mut reader: Reader<16> = Reader::new();
+    reader.field_decoder(Some(&serde_csv_core::de::PercentDecoder));
+    let (read, nread) = reader
+        .deserialize::<(heapless::String<16>, heapless::String<16>, f64)>(&buf[..nwritten])
+        .unwrap();
+    assert_eq!(read, ("a b, c".into(), "x\"y".into(), 1.5));
+    assert_eq!(nread, nwritten);
+}
+
+#[test]
+fn scaled_milli_units() {
+    use serde_csv_core::Scaled;
+
+    roundtrip(Scaled::<i32, 3>(12_345), "12.345\n");
+    roundtrip(Scaled::<i32, 3>(-1), "-0.001\n");
+    roundtrip(Scaled::<i32, 3>(-1_000), "-1.000\n");
+    roundtrip(Scaled::<i32, 3>(i32::MIN), "-2147483.648\n");
+    roundtrip(Scaled::<i32, 3>(i32::MAX), "2147483.647\n");
+    roundtrip(Scaled::<i32, 0>(i32::MIN), "-2147483648\n");
+    roundtrip(
+        (Scaled::<i32, 3>(-12), 'a', Scaled::<u32, 3>(5)),
+        "-0.012,a,0.005\n",
+    );
+}
+
+#[test]
+fn scaled_quoted_and_header() {
+    use serde_csv_core::Scaled;
+
+    #[derive(Serialize)]
+    struct Record {
+        temperature: Scaled<i16, 2>,
+    }
+
+    let record = Record {
+        temperature: Scaled(-5),
+    };
+    let mut writer = serde_csv_core::ser::WriterBuilder::new()
+        .delimiter(b'.')
+        .build();
+    let mut buf = [0; 32];
+    let mut nwritten = writer.serialize_header(&record, &mut buf).unwrap();
+    nwritten += writer.serialize(&record, &mut buf[nwritten..]).unwrap();
+    assert_eq!(&buf[..nwritten], b"temperature\n\"-0.05\"\n");
+
+    let mut reader = serde_csv_core::de::ReaderBuilder::new()
+        .delimiter(b'.')
+        .build::<8>();
+    let (value, _) = reader
+        .deserialize::<Scaled<i16, 2>>(&buf[12..nwritten])
+        .unwrap();
+    assert_eq!(value, Scaled(-5));
+}
+
+#[test]
+fn padded() {
+    use serde_csv_core::Padded;
+
+    roundtrip(Padded::<u32, 8>(42), "00000042\n");
+    roundtrip(Padded::<u32, 8>(0), "00000000\n");
+    roundtrip(Padded::<i32, 5>(-42), "-0042\n");
+    roundtrip(Padded::<i32, 5, b' '>(-42), "  -42\n");
+    roundtrip(Padded::<u32, 5, b' '>(42), "   42\n");
+    roundtrip(Padded::<i32, 3>(-42), "-42\n");
+    roundtrip(Padded::<u32, 2>(12345), "12345\n");
+    roundtrip(Padded::<i64, 4>(i64::MIN), "-9223372036854775808\n");
+    roundtrip(Padded::<u8, 0>(7), "7\n");
+}
+
+#[test]
+fn padded_quoting() {
+    use serde_csv_core::Padded;
+
+    let record = (Padded::<u16, 4, b' '>(7), Padded::<u16, 4>(7));
+    let mut writer = serde_csv_core::ser::WriterBuilder::new()
+        .delimiter(b' ')
+        .build();
+    let mut buf = [0; 32];
+    let nwritten = writer.serialize(&record, &mut buf).unwrap();
+    assert_eq!(&buf[..nwritten], b"\"   7\" 0007\n");
+
+    let mut reader = serde_csv_core::de::ReaderBuilder::new()
+        .delimiter(b' ')
+        .build::<8>();
+    let (read, _) = reader
+        .deserialize::<(Padded<u16, 4, b' '>, Padded<u16, 4>)>(&buf[..nwritten])
+        .unwrap();
+    assert_eq!(read, record);
+
+    let mut writer = serde_csv_core::ser::WriterBuilder::new()
+        .quote_policy(serde_csv_core::ser::QuotePolicy::AlwaysStrings)
+        .build();
+    let nwritten = writer.serialize(&record, &mut buf).unwrap();
+    assert_eq!(&buf[..nwritten], b"\"   7\",\"0007\"\n");
+}
+
+#[test]
+fn padded_invalid() {
+    use serde_csv_core::Padded;
+
+    let mut reader: Reader<8> = Reader::new();
+    for input in [
+        &b"\n"[..],
+        b"  \n",
+        b"-\n",
+        b" 0 1\n",
+        b"1.0\n",
+        b"256\n",
+        b"-1\n",
+    ] {
+        assert_eq!(
+            reader.deserialize::<Padded<u8, 4, b' '>>(input),
+            Err(serde_csv_core::de::Error::InvalidValue(
+                serde_csv_core::de::Snippet::new(&input[..input.len() - 1])
+            )),
+            "{:?}",
+            input
+        );
+    }
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Flagged {
+    #[serde(with = "serde_csv_core::opt_flagged")]
+    value: Option<u8>,
+    #[serde(with = "serde_csv_core::opt_flagged")]
+    name: Option<heapless::String<8>>,
+}
+
+#[test]
+fn opt_flagged() {
+    roundtrip(
+        Flagged {
+            value: Some(5),
+            name: Some("".into()),
+        },
+        "1,5,1,\n",
+    );
+    roundtrip(
+        Flagged {
+            value: None,
+            name: None,
+        },
+        "0,,0,\n",
+    );
+}
+
+#[test]
+fn opt_flagged_invalid() {
+    let mut reader: Reader<8> = Reader::new();
+
+    let flag = reader.deserialize::<Flagged>(b"2,5,0,\n");
+    let value = reader.deserialize::<Flagged>(b"0,5,0,\n");
+
+    assert_eq!(
+        flag,
+        Err(serde_csv_core::de::Error::InvalidValue(
+            serde_csv_core::de::Snippet::new(b"2")
+        ))
+    );
+    assert_eq!(value, Err(serde_csv_core::de::Error::ExpectedEmpty));
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+struct FlaggedPair {
+    #[serde(with = "serde_csv_core::opt_flagged")]
+    pair: Option<(u8, u8)>,
+}
+
+#[test]
+fn opt_flagged_compound() {
+    let mut writer = Writer::new();
+    let mut buf = [0; 16];
+
+    let some = writer.serialize(&FlaggedPair { pair: Some((1, 2)) }, &mut buf);
+    let none = writer.serialize(&FlaggedPair { pair: None }, &mut buf);
+
+    assert_eq!(some, Err(serde_csv_core::ser::Error::Custom));
+    assert_eq!(none, Ok(3));
+}
@@ -1,3 +1,7 @@
+use serde_csv_core::ser::{BytesMode, Error, QuotePolicy, SerializeStatus};
+use serde_csv_core::Nested;
+use serde_csv_core::{Checksum, Encoding};
+
 #[test]
 fn serialize_unit() {
     let data = ();
@@ -135,9 +139,6 @@ fn serialize_enum() {
 }
 
 #[test]
-#[should_panic(
-    expected = "not implemented: `Serializer::serialize_newtype_variant` is not supported"
-)]
 fn serialize_stateful_enum() {
     #[derive(serde::Serialize)]
     struct A {
@@ -162,11 +163,2257 @@ fn serialize_stateful_enum() {
     let mut writer = serde_csv_core::Writer::new();
     let mut buf = [0; 32];
 
-    let nwritten = writer.serialize(&data_a, &mut buf).unwrap();
+    let result = writer.serialize(&data_a, &mut buf);
+    assert_eq!(
+        result,
+        Err(Error::Unsupported("Serializer::serialize_newtype_variant"))
+    );
+
+    let result = writer.serialize(&data_b, &mut buf);
+    assert_eq!(
+        result,
+        Err(Error::Unsupported("Serializer::serialize_newtype_variant"))
+    );
+}
+
+#[test]
+fn serialize_tuple_variant() {
+    #[derive(serde::Serialize)]
+    enum Data {
+        A(i32, i32),
+    }
+
+    let mut writer = serde_csv_core::Writer::new();
+    let mut buf = [0; 32];
+    let result = writer.serialize(&Data::A(0, 1), &mut buf);
+
+    assert_eq!(
+        result,
+        Err(Error::Unsupported("Serializer::serialize_tuple_variant"))
+    );
+}
+
+#[test]
+fn serialize_struct_variant() {
+    #[derive(serde::Serialize)]
+    enum Data {
+        A { x: i32 },
+    }
+
+    let mut writer = serde_csv_core::Writer::new();
+    let mut buf = [0; 32];
+    let result = writer.serialize(&Data::A { x: 0 }, &mut buf);
+
+    assert_eq!(
+        result,
+        Err(Error::Unsupported("Serializer::serialize_struct_variant"))
+    );
+}
+
+#[test]
+fn serialize_map() {
+    let data = std::collections::BTreeMap::from([(0, "a"), (1, "b")]);
+
+    let mut writer = serde_csv_core::Writer::new();
+    let mut buf = [0; 32];
+    let nwritten = writer.serialize(&data, &mut buf).unwrap();
+
+    assert_eq!(&buf[..nwritten], b"a,b\n");
+}
+
+#[test]
+fn serialize_map_header() {
+    let data = std::collections::BTreeMap::from([("x", 0), ("y, z", 1)]);
+
+    let mut writer = serde_csv_core::Writer::new();
+    let mut buf = [0; 32];
+    let nwritten = writer.serialize_header(&data, &mut buf).unwrap();
+
+    assert_eq!(&buf[..nwritten], b"x,\"y, z\"\n");
+}
+
+#[test]
+fn serialize_map_header_compound_values() {
+    let data = std::collections::BTreeMap::from([("a", (0, 1)), ("b", (2, 3))]);
+
+    let mut writer = serde_csv_core::Writer::new();
+    let mut buf = [0; 32];
+    let mut nwritten = writer.serialize_header(&data, &mut buf).unwrap();
+    nwritten += writer.serialize(&data, &mut buf[nwritten..]).unwrap();
+
+    assert_eq!(&buf[..nwritten], b"a,a,b,b\n0,1,2,3\n");
+}
+
+/// A map that serializes its keys and values separately.
+struct SplitEntries<V>(&'static str, V);
+
+impl<V: serde::Serialize> serde::Serialize for SplitEntries<V> {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use serde::ser::SerializeMap;
+
+        let mut map = serializer.serialize_map(Some(1))?;
+        map.serialize_key(self.0)?;
+        map.serialize_value(&self.1)?;
+        map.end()
+    }
+}
+
+#[test]
+fn serialize_map_header_split_entries() {
+    let mut writer = serde_csv_core::Writer::new();
+    let mut buf = [0; 32];
+
+    let single = writer.serialize_header(&SplitEntries("a", 0), &mut buf);
+    let compound = writer.serialize_header(&SplitEntries("a", (0, 1)), &mut buf);
+
+    assert_eq!(single, Ok(2));
+    assert_eq!(
+        compound,
+        Err(Error::Unsupported("Writer::serialize_header"))
+    );
+}
+
+#[derive(serde::Serialize)]
+struct Coordinates {
+    lat: f32,
+    lon: f32,
+}
+
+#[derive(serde::Serialize)]
+struct Sample {
+    id: u8,
+    #[serde(flatten)]
+    position: Coordinates,
+    valid: bool,
+}
+
+#[test]
+fn serialize_flatten() {
+    let sample = Sample {
+        id: 1,
+        position: Coordinates {
+            lat: 50.06,
+            lon: 19.94,
+        },
+        valid: true,
+    };
+
+    let mut writer = serde_csv_core::Writer::new();
+    let mut buf = [0; 64];
+    let mut nwritten = writer.serialize_header(&sample, &mut buf).unwrap();
+    nwritten += writer.serialize(&sample, &mut buf[nwritten..]).unwrap();
+
+    assert_eq!(&buf[..nwritten], b"id,lat,lon,valid\n1,50.06,19.94,true\n");
+}
+
+#[test]
+fn serialize_flatten_leading_fields() {
+    let sample = Sample {
+        id: 1,
+        position: Coordinates { lat: 0.5, lon: 1.5 },
+        valid: false,
+    };
+
+    let mut writer = serde_csv_core::Writer::new();
+    writer.sequence_column(Some(0));
+    let mut buf = [0; 64];
+    let mut nwritten = writer.serialize_header(&sample, &mut buf).unwrap();
+    nwritten += writer.serialize(&sample, &mut buf[nwritten..]).unwrap();
+
+    assert_eq!(
+        &buf[..nwritten],
+        b"seq,id,lat,lon,valid\n0,1,0.5,1.5,false\n"
+    );
+}
+
+#[test]
+fn serialize_collect_str() {
+    let mut writer = serde_csv_core::Writer::new();
+    let mut buf = [0; 32];
+    let nwritten = writer
+        .serialize(&format_args!("{}-{}", 0, "a\"b"), &mut buf)
+        .unwrap();
+
+    assert_eq!(&buf[..nwritten], b"\"0-a\"\"b\"\n");
+}
+
+#[test]
+fn serialize_custom_error() {
+    struct Failing;
+
+    impl serde::Serialize for Failing {
+        fn serialize<S: serde::Serializer>(&self, _serializer: S) -> Result<S::Ok, S::Error> {
+            Err(serde::ser::Error::custom("failure"))
+        }
+    }
+
+    let mut writer = serde_csv_core::Writer::new();
+    let mut buf = [0; 32];
+    let result = writer.serialize(&Failing, &mut buf);
+
+    assert_eq!(result, Err(Error::Custom));
+}
+
+#[test]
+fn serialize_control_chars_escaped() {
+    let data = ("nul\0", "esc\x1b[0m", "del\x7f");
+
+    let mut writer = serde_csv_core::Writer::new();
+    writer.escape_control_chars(true);
+    let mut buf = [0; 64];
+    let nwritten = writer.serialize(&data, &mut buf).unwrap();
     let record = std::str::from_utf8(&buf[..nwritten]).unwrap();
-    assert_eq!(record, "0,1\n");
 
-    let nwritten = writer.serialize(&data_b, &mut buf).unwrap();
+    assert_eq!(record, "nul\\x00,esc\\x1B[0m,del\\x7F\n");
+}
+
+#[test]
+fn serialize_control_chars_escaped_quoted() {
+    let data = ("a,\"b\"\n", 1);
+
+    let mut writer = serde_csv_core::Writer::new();
+    writer.escape_control_chars(true);
+    let mut buf = [0; 32];
+    let nwritten = writer.serialize(&data, &mut buf).unwrap();
+    let record = std::str::from_utf8(&buf[..nwritten]).unwrap();
+
+    assert_eq!(record, "\"a,\"\"b\"\"\\x0A\",1\n");
+}
+
+#[test]
+fn serialize_control_chars_escaped_single_field() {
+    let data = "\0";
+
+    let mut writer = serde_csv_core::Writer::new();
+    writer.escape_control_chars(true);
+    let mut buf = [0; 32];
+    let nwritten = writer.serialize(&data, &mut buf).unwrap();
+    let record = std::str::from_utf8(&buf[..nwritten]).unwrap();
+
+    assert_eq!(record, "\\x00\n");
+}
+
+#[test]
+fn serialize_control_chars_escaped_overflow() {
+    let data = "\0\0";
+
+    let mut writer = serde_csv_core::Writer::new();
+    writer.escape_control_chars(true);
+    let mut buf = [0; 6];
+    let result = writer.serialize(&data, &mut buf);
+
+    assert_eq!(result, Err(Error::Overflow));
+}
+
+#[test]
+fn serialize_control_chars_not_escaped_by_default() {
+    let data = "nul\0";
+
+    let mut writer = serde_csv_core::Writer::new();
+    let mut buf = [0; 32];
+    let nwritten = writer.serialize(&data, &mut buf).unwrap();
+
+    assert_eq!(&buf[..nwritten], b"nul\0\n");
+}
+
+#[test]
+fn serialize_control_chars_escaped_roundtrip() {
+    #[derive(serde::Serialize)]
+    struct Log<'a> {
+        level: u8,
+        message: &'a str,
+    }
+
+    #[derive(Debug, PartialEq, serde::Deserialize)]
+    struct Parsed {
+        level: u8,
+        message: heapless::String<32>,
+    }
+
+    let data = Log {
+        level: 3,
+        message: "boot\0\x1b,done\x7f",
+    };
+
+    let mut writer = serde_csv_core::Writer::new();
+    writer.escape_control_chars(true);
+    let mut buf = [0; 64];
+    let nwritten = writer.serialize(&data, &mut buf).unwrap();
+
+    let mut reader = serde_csv_core::Reader::<32>::new();
+    let (parsed, nread) = reader.deserialize::<Parsed>(&buf[..nwritten]).unwrap();
+
+    assert_eq!(nread, nwritten);
+    assert_eq!(
+        parsed,
+        Parsed {
+            level: 3,
+            message: "boot\\x00\\x1B,done\\x7F".into(),
+        }
+    );
+}
+
+#[test]
+fn serialize_sanitize_formulas() {
+    let data = ("=A1", "+1", "-1", "@SUM(A1:A2)", "\tx", "\rx");
+
+    let mut writer = serde_csv_core::Writer::new();
+    writer.sanitize_formulas(true);
+    let mut buf = [0; 64];
+    let nwritten = writer.serialize(&data, &mut buf).unwrap();
+    let record = std::str::from_utf8(&buf[..nwritten]).unwrap();
+
+    assert_eq!(record, "'=A1,'+1,'-1,'@SUM(A1:A2),'\tx,\"'\rx\"\n");
+}
+
+#[test]
+fn serialize_sanitize_formulas_untouched() {
+    let data = ("A1=B1", "", 'x', -1, -1.5, "1-2");
+
+    let mut writer = serde_csv_core::Writer::new();
+    writer.sanitize_formulas(true);
+    let mut buf = [0; 64];
+    let nwritten = writer.serialize(&data, &mut buf).unwrap();
+    let record = std::str::from_utf8(&buf[..nwritten]).unwrap();
+
+    assert_eq!(record, "A1=B1,,x,-1,-1.5,1-2\n");
+}
+
+#[test]
+fn serialize_sanitize_formulas_quoted() {
+    let data = "=1,2";
+
+    let mut writer = serde_csv_core::Writer::new();
+    writer.sanitize_formulas(true);
+    let mut buf = [0; 32];
+    let nwritten = writer.serialize(&data, &mut buf).unwrap();
+    let record = std::str::from_utf8(&buf[..nwritten]).unwrap();
+
+    assert_eq!(record, "\"'=1,2\"\n");
+}
+
+#[test]
+fn serialize_sanitize_formulas_custom_guard() {
+    let data = ('=', "-x");
+
+    let mut writer = serde_csv_core::Writer::new();
+    writer.sanitize_formulas(true).formula_guard(b' ');
+    let mut buf = [0; 32];
+    let nwritten = writer.serialize(&data, &mut buf).unwrap();
+    let record = std::str::from_utf8(&buf[..nwritten]).unwrap();
+
+    assert_eq!(record, " =, -x\n");
+}
+
+#[test]
+fn serialize_sanitize_formulas_with_control_chars() {
+    let data = "=\0";
+
+    let mut writer = serde_csv_core::Writer::new();
+    writer.sanitize_formulas(true).escape_control_chars(true);
+    let mut buf = [0; 32];
+    let nwritten = writer.serialize(&data, &mut buf).unwrap();
+    let record = std::str::from_utf8(&buf[..nwritten]).unwrap();
+
+    assert_eq!(record, "'=\\x00\n");
+}
+
+#[test]
+fn serialize_nested_option_some_some() {
+    let data: (u8, Option<Option<u8>>, u8) = (0, Some(Some(1)), 2);
+
+    let mut writer = serde_csv_core::Writer::new();
+    let mut buf = [0; 32];
+    let nwritten = writer.serialize(&data, &mut buf).unwrap();
+    let record = std::str::from_utf8(&buf[..nwritten]).unwrap();
+
+    assert_eq!(record, "0,1,2\n");
+}
+
+#[test]
+fn serialize_nested_option_some_none() {
+    let data: (u8, Option<Option<u8>>, u8) = (0, Some(None), 2);
+
+    let mut writer = serde_csv_core::Writer::new();
+    let mut buf = [0; 32];
+    let nwritten = writer.serialize(&data, &mut buf).unwrap();
+    let record = std::str::from_utf8(&buf[..nwritten]).unwrap();
+
+    assert_eq!(record, "0,,2\n");
+}
+
+#[test]
+fn serialize_nested_option_none() {
+    let data: (u8, Option<Option<u8>>, u8) = (0, None, 2);
+
+    let mut writer = serde_csv_core::Writer::new();
+    let mut buf = [0; 32];
+    let nwritten = writer.serialize(&data, &mut buf).unwrap();
+    let record = std::str::from_utf8(&buf[..nwritten]).unwrap();
+
+    assert_eq!(record, "0,,2\n");
+}
+
+#[test]
+fn serialize_nested_option_in_compound() {
+    let data: Option<(Option<u8>, u8)> = Some((None, 1));
+
+    let mut writer = serde_csv_core::Writer::new();
+    let mut buf = [0; 32];
+    let nwritten = writer.serialize(&data, &mut buf).unwrap();
+
+    assert_eq!(&buf[..nwritten], b",1\n");
+}
+
+#[test]
+fn serialize_after_overflow() {
+    let mut writer = serde_csv_core::Writer::new();
+
+    let mut buf = [0; 8];
+    let result = writer.serialize(&("first", "second", "third"), &mut buf);
+    assert_eq!(result, Err(Error::Overflow));
+
+    let mut buf = [0; 32];
+    let nwritten = writer.serialize(&("a", "b"), &mut buf).unwrap();
+    let record = std::str::from_utf8(&buf[..nwritten]).unwrap();
+
+    assert_eq!(record, "a,b\n");
+}
+
+#[test]
+fn serialize_after_overflow_in_quoted_field() {
+    let mut writer = serde_csv_core::Writer::new();
+
+    let mut buf = [0; 8];
+    let result = writer.serialize(&("a", "needs \"quotes\""), &mut buf);
+    assert_eq!(result, Err(Error::Overflow));
+
+    let mut buf = [0; 32];
+    let nwritten = writer.serialize(&("a", "b"), &mut buf).unwrap();
+    let record = std::str::from_utf8(&buf[..nwritten]).unwrap();
+
+    assert_eq!(record, "a,b\n");
+}
+
+#[test]
+fn serialize_value_composes_record() {
+    let mut writer = serde_csv_core::Writer::new();
+    let mut buf = [0; 32];
+
+    let mut nwritten = writer.serialize_value(&1, &mut buf).unwrap();
+    nwritten += writer
+        .serialize_value(&("a,b", 2.5), &mut buf[nwritten..])
+        .unwrap();
+    nwritten += writer.terminate(&mut buf[nwritten..]).unwrap();
+    nwritten += writer.serialize(&(3, 4), &mut buf[nwritten..]).unwrap();
+    let record = std::str::from_utf8(&buf[..nwritten]).unwrap();
+
+    assert_eq!(record, "1,\"a,b\",2.5\n3,4\n");
+}
+
+#[test]
+fn serialize_value_finished_by_serialize() {
+    let mut writer = serde_csv_core::Writer::new();
+    let mut buf = [0; 32];
+
+    let mut nwritten = writer.serialize_value(&"head", &mut buf).unwrap();
+    nwritten += writer
+        .serialize(&("tail", 1), &mut buf[nwritten..])
+        .unwrap();
     let record = std::str::from_utf8(&buf[..nwritten]).unwrap();
-    assert_eq!(record, "0\n");
+
+    assert_eq!(record, "head,tail,1\n");
+}
+
+#[test]
+fn serialize_value_escaped_fields() {
+    let mut writer = serde_csv_core::Writer::new();
+    writer.escape_control_chars(true);
+    let mut buf = [0; 32];
+
+    let mut nwritten = writer.serialize_value(&"a\tb", &mut buf).unwrap();
+    nwritten += writer.terminate(&mut buf[nwritten..]).unwrap();
+    let record = std::str::from_utf8(&buf[..nwritten]).unwrap();
+
+    assert_eq!(record, "a\\x09b\n");
+}
+
+#[test]
+fn serialize_value_overflow() {
+    let mut writer = serde_csv_core::Writer::new();
+    let mut buf = [0; 32];
+
+    let mut nwritten = writer.serialize_value(&"a", &mut buf).unwrap();
+    let result = writer.serialize_value(&"too long", &mut buf[nwritten..nwritten + 4]);
+    assert_eq!(result, Err(Error::Overflow));
+    nwritten += writer.serialize_value(&"b", &mut buf[nwritten..]).unwrap();
+    nwritten += writer.terminate(&mut buf[nwritten..]).unwrap();
+    let record = std::str::from_utf8(&buf[..nwritten]).unwrap();
+
+    assert_eq!(record, "a,b\n");
+}
+
+#[test]
+fn serialize_empty_seq() {
+    let data: (u8, [u8; 0], u8) = (1, [], 2);
+
+    let mut writer = serde_csv_core::Writer::new();
+    let mut buf = [0; 32];
+    let nwritten = writer.serialize(&data, &mut buf).unwrap();
+    let record = std::str::from_utf8(&buf[..nwritten]).unwrap();
+
+    assert_eq!(record, "1,2\n");
+}
+
+#[derive(serde::Serialize)]
+struct Position {
+    lat: f32,
+    lon: f32,
+}
+
+#[derive(serde::Serialize)]
+struct Fix {
+    id: u8,
+    position: Position,
+    name: Option<&'static str>,
+}
+
+const FIXES: [Fix; 3] = [
+    Fix {
+        id: 1,
+        position: Position {
+            lat: 50.0,
+            lon: 20.0,
+        },
+        name: Some("Cracow"),
+    },
+    Fix {
+        id: 2,
+        position: Position {
+            lat: 52.5,
+            lon: 21.0,
+        },
+        name: None,
+    },
+    Fix {
+        id: 3,
+        position: Position {
+            lat: 54.5,
+            lon: 18.5,
+        },
+        name: Some("Gdynia"),
+    },
+];
+
+#[test]
+fn serialize_table() {
+    let mut writer = serde_csv_core::Writer::new();
+    let mut buf = [0; 128];
+    let nwritten = writer.serialize_table(&FIXES, &mut buf).unwrap();
+    let table = std::str::from_utf8(&buf[..nwritten]).unwrap();
+
+    assert_eq!(
+        table,
+        "id,lat,lon,name\n1,50.0,20.0,Cracow\n2,52.5,21.0,\n3,54.5,18.5,Gdynia\n"
+    );
+}
+
+#[test]
+fn serialize_table_empty() {
+    let mut writer = serde_csv_core::Writer::new();
+    let mut buf = [0; 32];
+    let nwritten = writer.serialize_table::<Fix>(&[], &mut buf).unwrap();
+
+    assert_eq!(nwritten, 0);
+}
+
+#[test]
+fn serialize_table_overflow() {
+    let mut writer = serde_csv_core::Writer::new();
+    let mut buf = [0; 56];
+
+    let result = writer.serialize_table(&FIXES, &mut buf);
+    assert_eq!(
+        result,
+        Err(Error::TableOverflow {
+            records: 2,
+            nwritten: 48
+        })
+    );
+    let mut table = std::vec::Vec::from(&buf[..48]);
+    let nwritten = writer.serialize(&FIXES[2], &mut buf).unwrap();
+    table.extend_from_slice(&buf[..nwritten]);
+
+    assert_eq!(
+        std::str::from_utf8(&table).unwrap(),
+        "id,lat,lon,name\n1,50.0,20.0,Cracow\n2,52.5,21.0,\n3,54.5,18.5,Gdynia\n"
+    );
+}
+
+#[test]
+fn serialize_table_header_overflow() {
+    let mut writer = serde_csv_core::Writer::new();
+    let mut buf = [0; 8];
+
+    let result = writer.serialize_table(&FIXES, &mut buf);
+
+    assert_eq!(
+        result,
+        Err(Error::TableOverflow {
+            records: 0,
+            nwritten: 0
+        })
+    );
+}
+
+#[test]
+fn serialize_header_of_tuple_in_struct() {
+    #[derive(serde::Serialize)]
+    struct Record {
+        id: u8,
+        range: (u8, u8),
+    }
+
+    let mut writer = serde_csv_core::Writer::new();
+    let mut buf = [0; 32];
+    let nwritten = writer
+        .serialize_header(
+            &Record {
+                id: 1,
+                range: (2, 3),
+            },
+            &mut buf,
+        )
+        .unwrap();
+
+    assert_eq!(&buf[..nwritten], b"id,range,range\n");
+}
+
+#[test]
+fn serialize_header_without_names() {
+    let mut writer = serde_csv_core::Writer::new();
+    let mut buf = [0; 32];
+
+    let result = writer.serialize_header(&(1, 2), &mut buf);
+
+    assert_eq!(result, Err(Error::Unsupported("Writer::serialize_header")));
+}
+
+#[derive(serde::Serialize)]
+struct City {
+    name: &'static str,
+    country: &'static str,
+    population: u32,
+}
+
+const CRACOW: City = City {
+    name: "Cracow, Lesser Poland",
+    country: "Poland",
+    population: 766_683,
+};
+
+#[test]
+fn serialize_permuted_reversed() {
+    let mut writer = serde_csv_core::Writer::new();
+    let mut scratch = [0; 128];
+    let mut buf = [0; 64];
+    let mut nwritten = writer
+        .serialize_header_permuted(&CRACOW, &[2, 1, 0], &mut scratch, &mut buf)
+        .unwrap();
+    nwritten += writer
+        .serialize_permuted(&CRACOW, &[2, 1, 0], &mut scratch, &mut buf[nwritten..])
+        .unwrap();
+    let table = std::str::from_utf8(&buf[..nwritten]).unwrap();
+
+    assert_eq!(
+        table,
+        "population,country,name\n766683,Poland,\"Cracow, Lesser Poland\"\n"
+    );
+}
+
+#[test]
+fn serialize_permuted_invalid_permutation() {
+    let mut writer = serde_csv_core::Writer::new();
+    let mut scratch = [0; 128];
+    let mut buf = [0; 64];
+
+    for permutation in [&[0, 1][..], &[0, 1, 1], &[0, 1, 3], &[0, 1, 2, 3]] {
+        let result = writer.serialize_permuted(&CRACOW, permutation, &mut scratch, &mut buf);
+        assert_eq!(result, Err(Error::InvalidPermutation));
+    }
+}
+
+#[test]
+fn serialize_permuted_scratch_overflow() {
+    let mut writer = serde_csv_core::Writer::new();
+    let mut scratch = [0; 16];
+    let mut buf = [0; 64];
+
+    let result = writer.serialize_permuted(&CRACOW, &[2, 1, 0], &mut scratch, &mut buf);
+
+    assert_eq!(result, Err(Error::Overflow));
+}
+
+#[test]
+fn serialize_to_string_multibyte() {
+    let mut writer = serde_csv_core::Writer::new();
+
+    let result = writer.serialize_to_string::<_, 32>(&("Zażółć", "gęślą", 'ś'));
+
+    assert_eq!(result.as_deref(), Ok("Zażółć,gęślą,ś\n"));
+}
+
+#[test]
+fn serialize_to_string_overflow() {
+    let mut writer = serde_csv_core::Writer::new();
+
+    let result = writer.serialize_to_string::<_, 8>(&("Zażółć", "gęślą"));
+
+    assert_eq!(result, Err(Error::Overflow));
+}
+
+/// Byte slice serialized with `serialize_bytes` instead of as a sequence.
+struct Bytes(&'static [u8]);
+
+impl serde::Serialize for Bytes {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_bytes(self.0)
+    }
+}
+
+#[test]
+fn serialize_to_string_invalid_utf8() {
+    let mut writer = serde_csv_core::Writer::new();
+
+    let result = writer.serialize_to_string::<_, 32>(&("caf", Bytes(b"caf\xe9")));
+
+    assert_eq!(result, Err(Error::InvalidUtf8));
+}
+
+#[test]
+fn serialize_to_string_latin1() {
+    let mut writer = serde_csv_core::Writer::new();
+    writer.encoding(Encoding::Latin1);
+
+    let result = writer.serialize_to_string::<_, 32>(&("Ã©", 1));
+
+    assert_eq!(result, Err(Error::NonUtf8Encoding));
+}
+
+#[test]
+fn serialize_quote_policy_minimal() {
+    let mut writer = serde_csv_core::Writer::new();
+    let mut buf = [0; 32];
+    let nwritten = writer.serialize(&("a", "b,c", 'd', 1), &mut buf).unwrap();
+
+    assert_eq!(&buf[..nwritten], b"a,\"b,c\",d,1\n");
+}
+
+#[test]
+fn serialize_quote_policy_always_strings() {
+    let mut writer = serde_csv_core::Writer::new();
+    writer.quote_policy(QuotePolicy::AlwaysStrings);
+    let mut buf = [0; 32];
+    let nwritten = writer
+        .serialize(&("a", "b\"c", 'd', 1, true, ""), &mut buf)
+        .unwrap();
+
+    assert_eq!(&buf[..nwritten], b"\"a\",\"b\"\"c\",\"d\",1,true,\"\"\n");
+}
+
+#[test]
+fn serialize_quote_policy_always_strings_sanitized() {
+    let mut writer = serde_csv_core::Writer::new();
+    writer
+        .quote_policy(QuotePolicy::AlwaysStrings)
+        .sanitize_formulas(true);
+    let mut buf = [0; 32];
+    let nwritten = writer.serialize(&("=1+2", -3), &mut buf).unwrap();
+
+    assert_eq!(&buf[..nwritten], b"\"'=1+2\",-3\n");
+}
+
+#[test]
+fn serialize_quote_policy_always_strings_header_permuted() {
+    let mut writer = serde_csv_core::Writer::new();
+    writer.quote_policy(QuotePolicy::AlwaysStrings);
+    let mut scratch = [0; 128];
+    let mut buf = [0; 128];
+    let mut nwritten = writer
+        .serialize_header_permuted(&CRACOW, &[2, 1, 0], &mut scratch, &mut buf)
+        .unwrap();
+    nwritten += writer
+        .serialize_permuted(&CRACOW, &[2, 1, 0], &mut scratch, &mut buf[nwritten..])
+        .unwrap();
+    let table = std::str::from_utf8(&buf[..nwritten]).unwrap();
+
+    assert_eq!(
+        table,
+        "\"population\",\"country\",\"name\"\n766683,\"Poland\",\"Cracow, Lesser Poland\"\n"
+    );
+}
+
+#[test]
+fn serialize_quote_policy_never() {
+    let mut writer = serde_csv_core::Writer::new();
+    writer.quote_policy(QuotePolicy::Never);
+    let mut buf = [0; 32];
+    let nwritten = writer.serialize(&("a", 'b', 1.5), &mut buf).unwrap();
+
+    assert_eq!(&buf[..nwritten], b"a,b,1.5\n");
+}
+
+#[test]
+fn serialize_quote_policy_never_requires_quoting() {
+    let mut writer = serde_csv_core::Writer::new();
+    writer.quote_policy(QuotePolicy::Never);
+    let mut buf = [0; 32];
+
+    for value in ["b,c", "b\"c", "b\nc"] {
+        let result = writer.serialize(&("a", value), &mut buf);
+        assert_eq!(result, Err(Error::QuotingRequired));
+    }
+    let nwritten = writer.serialize(&("a", "b"), &mut buf).unwrap();
+
+    assert_eq!(&buf[..nwritten], b"a,b\n");
+}
+
+fn serialize_bytes_with(mode: BytesMode, value: &'static [u8]) -> std::string::String {
+    let mut writer = serde_csv_core::Writer::new();
+    writer.bytes_mode(mode);
+    let mut buf = [0; 64];
+    let nwritten = writer.serialize(&(1, Bytes(value), 2), &mut buf).unwrap();
+    std::string::String::from_utf8(buf[..nwritten].to_vec()).unwrap()
+}
+
+#[test]
+fn serialize_bytes_single_field() {
+    assert_eq!(
+        serialize_bytes_with(BytesMode::SingleField, b"a,b"),
+        "1,\"a,b\",2\n"
+    );
+}
+
+#[test]
+fn serialize_bytes_per_byte() {
+    assert_eq!(
+        serialize_bytes_with(BytesMode::PerByte, b"a,b"),
+        "1,97,44,98,2\n"
+    );
+    assert_eq!(serialize_bytes_with(BytesMode::PerByte, b""), "1,2\n");
+}
+
+#[test]
+fn serialize_bytes_hex() {
+    assert_eq!(
+        serialize_bytes_with(BytesMode::Hex, &[0x00, 0x7f, 0xab, 0xff]),
+        "1,007FABFF,2\n"
+    );
+    assert_eq!(serialize_bytes_with(BytesMode::Hex, b""), "1,,2\n");
+}
+
+#[test]
+fn serialize_bytes_base64() {
+    let cases: [(&[u8], &str); 5] = [
+        (b"", "1,,2\n"),
+        (b"f", "1,Zg==,2\n"),
+        (b"fo", "1,Zm8=,2\n"),
+        (b"foo", "1,Zm9v,2\n"),
+        (&[0xfb, 0xff, 0xbf, 0x00], "1,+/+/AA==,2\n"),
+    ];
+    for (bytes, expected) in cases {
+        assert_eq!(serialize_bytes_with(BytesMode::Base64, bytes), expected);
+    }
+}
+
+#[test]
+fn serialize_byte_slice_as_seq() {
+    let value: &[u8] = b"ab";
+
+    let mut writer = serde_csv_core::Writer::new();
+    writer.bytes_mode(BytesMode::Hex);
+    let mut buf = [0; 16];
+    let nwritten = writer.serialize(&value, &mut buf).unwrap();
+
+    assert_eq!(&buf[..nwritten], b"97,98\n");
+}
+
+#[test]
+fn serialize_consistent_field_count() {
+    let mut writer = serde_csv_core::Writer::new();
+    writer.check_field_count(true);
+    let mut buf = [0; 96];
+
+    let mut nwritten = writer.serialize_header(&CRACOW, &mut buf).unwrap();
+    nwritten += writer.serialize(&CRACOW, &mut buf[nwritten..]).unwrap();
+    nwritten += writer
+        .serialize(&("a", ("b", 3)), &mut buf[nwritten..])
+        .unwrap();
+
+    assert_eq!(
+        &buf[..nwritten],
+        b"name,country,population\n\"Cracow, Lesser Poland\",Poland,766683\na,b,3\n"
+    );
+}
+
+#[test]
+fn serialize_inconsistent_field_count() {
+    let mut writer = serde_csv_core::Writer::new();
+    writer.check_field_count(true);
+    let mut buf = [0; 64];
+
+    let nwritten = writer.serialize(&[1, 2, 3], &mut buf).unwrap();
+    let result = writer.serialize(&[1, 2, 3, 4], &mut buf[nwritten..]);
+    assert_eq!(
+        result,
+        Err(Error::InconsistentFieldCount {
+            expected: 3,
+            got: 4
+        })
+    );
+    let result = writer.serialize(&(1, [2; 0]), &mut buf[nwritten..]);
+    assert_eq!(
+        result,
+        Err(Error::InconsistentFieldCount {
+            expected: 3,
+            got: 1
+        })
+    );
+    let n = writer
+        .serialize(&(4, [5, 6]), &mut buf[nwritten..])
+        .unwrap();
+
+    assert_eq!(&buf[..nwritten + n], b"1,2,3\n4,5,6\n");
+}
+
+#[test]
+fn serialize_field_count_across_values() {
+    let mut writer = serde_csv_core::Writer::new();
+    writer.check_field_count(true);
+    let mut buf = [0; 64];
+
+    let mut nwritten = writer.serialize(&(1, 2), &mut buf).unwrap();
+    nwritten += writer.serialize_value(&3, &mut buf[nwritten..]).unwrap();
+    nwritten += writer.serialize(&4, &mut buf[nwritten..]).unwrap();
+    nwritten += writer.serialize_value(&5, &mut buf[nwritten..]).unwrap();
+    let result = writer.terminate(&mut buf[nwritten..]);
+
+    assert_eq!(
+        result,
+        Err(Error::InconsistentFieldCount {
+            expected: 2,
+            got: 1
+        })
+    );
+}
+
+#[test]
+fn serialize_field_count_reset() {
+    let mut writer = serde_csv_core::Writer::new();
+    writer.check_field_count(true);
+    let mut buf = [0; 64];
+
+    let nwritten = writer.serialize(&(1, 2), &mut buf).unwrap();
+    writer.reset();
+    let n = writer.serialize(&(1, 2, 3), &mut buf[nwritten..]).unwrap();
+
+    assert_eq!(&buf[..nwritten + n], b"1,2\n1,2,3\n");
+}
+
+#[test]
+fn serialize_nested() {
+    let mut writer = serde_csv_core::Writer::new();
+    let mut buf = [0; 64];
+    let record = (1, Nested::<_, b';', 32>(("E1", "a,b", "c\"d")), "ok");
+
+    let nwritten = writer.serialize(&record, &mut buf).unwrap();
+
+    assert_eq!(&buf[..nwritten], b"1,\"E1;a,b;\"\"c\"\"\"\"d\"\"\",ok\n");
+}
+
+#[test]
+fn serialize_nested_empty() {
+    let mut writer = serde_csv_core::Writer::new();
+    let mut buf = [0; 16];
+
+    let nwritten = writer
+        .serialize(&(1, Nested::<[u8; 0]>([]), 2), &mut buf)
+        .unwrap();
+
+    assert_eq!(&buf[..nwritten], b"1,,2\n");
+}
+
+#[test]
+fn serialize_nested_overflow() {
+    let mut writer = serde_csv_core::Writer::new();
+    let mut buf = [0; 64];
+
+    let inner = writer.serialize(&(1, Nested::<_, b';', 4>(("abc", "def"))), &mut buf);
+    let outer = writer.serialize(&(1, Nested::<_, b';', 16>(("abc", "def"))), &mut buf[..4]);
+
+    assert_eq!(inner, Err(Error::NestedOverflow));
+    assert_eq!(outer, Err(Error::Overflow));
+}
+
+#[test]
+fn serialize_custom_error_message() {
+    struct Failing;
+    impl serde::Serialize for Failing {
+        fn serialize<S: serde::Serializer>(&self, _serializer: S) -> Result<S::Ok, S::Error> {
+            Err(serde::ser::Error::custom("nested record overflow"))
+        }
+    }
+    let mut writer = serde_csv_core::Writer::new();
+    let mut buf = [0; 16];
+
+    let result = writer.serialize(&Failing, &mut buf);
+
+    assert_eq!(result, Err(Error::Custom));
+}
+
+#[test]
+fn write_fmt_field() {
+    let mut writer = serde_csv_core::Writer::new();
+    let mut buf = [0; 32];
+
+    let mut nwritten = writer
+        .write_fmt_field(format_args!("{:08.3}", -21.4567), &mut buf)
+        .unwrap();
+    nwritten += writer.serialize(&1, &mut buf[nwritten..]).unwrap();
+
+    assert_eq!(&buf[..nwritten], b"-021.457,1\n");
+}
+
+#[test]
+fn write_fmt_field_sanitized() {
+    let mut writer = serde_csv_core::Writer::new();
+    writer.sanitize_formulas(true).escape_control_chars(true);
+    let mut buf = [0; 32];
+
+    let mut nwritten = writer
+        .write_fmt_field(format_args!("{}{}", '=', "1+1"), &mut buf)
+        .unwrap();
+    nwritten += writer
+        .write_fmt_field(format_args!("a{}b", '\t'), &mut buf[nwritten..])
+        .unwrap();
+
+    assert_eq!(&buf[..nwritten], b"'=1+1,a\\x09b");
+}
+
+#[test]
+fn write_fmt_field_overflow() {
+    let mut writer = serde_csv_core::Writer::new();
+    let mut buf = [0; 32];
+
+    let result = writer.write_fmt_field(format_args!("{:>40}", 1), &mut buf);
+    let nwritten = writer.serialize(&2, &mut buf).unwrap();
+
+    assert_eq!(result, Err(Error::Overflow));
+    assert_eq!(&buf[..nwritten], b"2\n");
+}
+
+#[test]
+fn write_fmt_field_too_long() {
+    let mut writer = serde_csv_core::Writer::new();
+    let mut buf = [0; 128];
+
+    let result = writer.write_fmt_field(format_args!("{:>65}", 1), &mut buf);
+    let fits = writer.write_fmt_field(format_args!("{:>64}", 1), &mut buf);
+
+    assert_eq!(result, Err(Error::FmtOverflow));
+    assert_eq!(fits, Ok(64));
+}
+
+#[test]
+fn serialize_field_only() {
+    let mut writer = serde_csv_core::Writer::new();
+    let mut buf = [0; 32];
+
+    let mut nwritten = writer.serialize_field_only(&-42, &mut buf).unwrap();
+    nwritten += writer
+        .serialize_field_only(&"a,b", &mut buf[nwritten..])
+        .unwrap();
+    nwritten += writer.serialize(&("c", 1), &mut buf[nwritten..]).unwrap();
+
+    assert_eq!(&buf[..nwritten], b"-42\"a,b\"c,1\n");
+}
+
+#[test]
+fn serialize_field_only_compound() {
+    let mut writer = serde_csv_core::Writer::new();
+    let mut buf = [0; 32];
+
+    let nwritten = writer
+        .serialize_field_only(&(1, "x\"y", ""), &mut buf)
+        .unwrap();
+
+    assert_eq!(&buf[..nwritten], b"1,\"x\"\"y\",");
+}
+
+#[test]
+fn serialize_field_only_overflow() {
+    let mut writer = serde_csv_core::Writer::new();
+    let mut buf = [0; 32];
+
+    let result = writer.serialize_field_only(&"a,b", &mut buf[..4]);
+    let nwritten = writer.serialize_field_only(&"a,b", &mut buf).unwrap();
+
+    assert_eq!(result, Err(Error::Overflow));
+    assert_eq!(&buf[..nwritten], b"\"a,b\"");
+}
+
+#[test]
+fn serialize_field_only_check_field_count() {
+    let mut writer = serde_csv_core::Writer::new();
+    writer.check_field_count(true);
+    let mut buf = [0; 32];
+
+    let mut nwritten = writer.serialize(&(1, 2), &mut buf).unwrap();
+    nwritten += writer
+        .serialize_field_only(&"a", &mut buf[nwritten..])
+        .unwrap();
+    nwritten += writer.serialize(&(3, 4), &mut buf[nwritten..]).unwrap();
+    let result = writer.serialize(&(5,), &mut buf[nwritten..]);
+
+    assert_eq!(&buf[..nwritten], b"1,2\na3,4\n");
+    assert_eq!(
+        result,
+        Err(Error::InconsistentFieldCount {
+            expected: 2,
+            got: 1
+        })
+    );
+}
+
+fn slices_writer(policy: QuotePolicy) -> serde_csv_core::Writer {
+    let mut writer = serde_csv_core::Writer::from_builder(
+        serde_csv_core::csv_core::WriterBuilder::new()
+            .terminator(serde_csv_core::csv_core::Terminator::CRLF),
+    );
+    writer
+        .quote_policy(policy)
+        .escape_control_chars(true)
+        .sanitize_formulas(true);
+    writer
+}
+
+const SLICES_RECORD: (&str, &str, &str, f32, &str, &str, &str) =
+    ("a\"b", "x,y", "", 1.5, "=1", "\t", "\"\"");
+
+/// Serializes `value` split into three parts at every pair of positions
+/// and compares the result with the output of `Writer::serialize`.
+fn check_slices<T: serde::Serialize>(policy: QuotePolicy, value: &T) {
+    let mut expected = [0; 64];
+    let len = slices_writer(policy)
+        .serialize(value, &mut expected)
+        .unwrap();
+    let expected = &expected[..len];
+
+    for i in 0..=len {
+        for j in i..=len {
+            let mut buf = [0; 64];
+            let (first, rest) = buf[..len].split_at_mut(i);
+            let (second, third) = rest.split_at_mut(j - i);
+
+            let nwritten = slices_writer(policy)
+                .serialize_to_slices(value, &mut [first, second, third])
+                .unwrap();
+
+            assert_eq!(nwritten, len);
+            assert_eq!(&buf[..len], expected, "split at {i} and {j}");
+        }
+    }
+}
+
+#[test]
+fn serialize_to_slices() {
+    check_slices(QuotePolicy::Minimal, &SLICES_RECORD);
+}
+
+#[test]
+fn serialize_to_slices_always_strings() {
+    check_slices(QuotePolicy::AlwaysStrings, &SLICES_RECORD);
+    check_slices(QuotePolicy::AlwaysStrings, &("a",));
+}
+
+#[test]
+fn serialize_to_slices_empty_record() {
+    check_slices(QuotePolicy::Minimal, &("",));
+}
+
+#[test]
+fn serialize_to_slices_overflow() {
+    let mut expected = [0; 64];
+    let len = slices_writer(QuotePolicy::Minimal)
+        .serialize(&SLICES_RECORD, &mut expected)
+        .unwrap();
+
+    for i in 0..len {
+        let mut writer = slices_writer(QuotePolicy::Minimal);
+        let mut buf = [0; 64];
+        let (first, second) = buf[..len - 1].split_at_mut(i);
+
+        let result = writer.serialize_to_slices(&SLICES_RECORD, &mut [first, second]);
+        let nwritten = writer.serialize(&SLICES_RECORD, &mut buf).unwrap();
+
+        assert_eq!(result, Err(Error::Overflow));
+        assert_eq!(&buf[..nwritten], &expected[..len]);
+    }
+}
+
+#[test]
+fn serialize_to_slices_multiple_records() {
+    let mut writer = serde_csv_core::Writer::new();
+    let mut first = [0; 3];
+    let mut second = [0; 3];
+
+    let mut nwritten = writer
+        .serialize_to_slices(&(1, "a"), &mut [&mut first[..], &mut second[..]])
+        .unwrap();
+    nwritten += writer
+        .serialize_to_slices(&(2,), &mut [&mut second[1..]])
+        .unwrap();
+
+    assert_eq!(nwritten, 6);
+    assert_eq!(&first, b"1,a");
+    assert_eq!(&second, b"\n2\n");
+}
+
+#[derive(serde::Serialize)]
+struct Row {
+    name: &'static str,
+    values: [u8; 2],
+}
+
+#[test]
+fn serialize_rows() {
+    let rows = [
+        Row {
+            name: "a",
+            values: [1, 2],
+        },
+        Row {
+            name: "b,c",
+            values: [3, 4],
+        },
+    ];
+
+    let mut writer = serde_csv_core::Writer::new();
+    let mut buf = [0; 32];
+    let nwritten = writer.serialize_rows(&rows[..], &mut buf).unwrap();
+
+    assert_eq!(&buf[..nwritten], b"a,1,2\n\"b,c\",3,4\n");
+}
+
+#[test]
+fn serialize_rows_empty() {
+    let rows: [Row; 0] = [];
+
+    let mut writer = serde_csv_core::Writer::new();
+    let mut buf = [0; 32];
+    let nwritten = writer.serialize_rows(&rows[..], &mut buf).unwrap();
+
+    assert_eq!(nwritten, 0);
+}
+
+#[test]
+fn serialize_rows_nested_sequences() {
+    let rows = vec![(1, [2, 3], vec![4]), (5, [6, 7], vec![])];
+
+    let mut writer = serde_csv_core::Writer::new();
+    let mut buf = [0; 32];
+    let nwritten = writer.serialize_rows(&rows, &mut buf).unwrap();
+
+    assert_eq!(&buf[..nwritten], b"1,2,3,4\n5,6,7\n");
+}
+
+#[test]
+fn serialize_rows_not_a_sequence() {
+    let mut writer = serde_csv_core::Writer::new();
+    let mut buf = [0; 32];
+    let nwritten = writer
+        .serialize_rows(
+            &Row {
+                name: "a",
+                values: [1, 2],
+            },
+            &mut buf,
+        )
+        .unwrap();
+
+    assert_eq!(&buf[..nwritten], b"a,1,2\n");
+}
+
+#[test]
+fn serialize_rows_inconsistent_field_count() {
+    let rows = vec![vec![1, 2], vec![3]];
+
+    let mut writer = serde_csv_core::Writer::new();
+    writer.check_field_count(true);
+    let mut buf = [0; 32];
+    let result = writer.serialize_rows(&rows, &mut buf);
+    let nwritten = writer.serialize(&(5, 6), &mut buf).unwrap();
+
+    assert_eq!(
+        result,
+        Err(Error::InconsistentFieldCount {
+            expected: 2,
+            got: 1
+        })
+    );
+    assert_eq!(&buf[..nwritten], b"5,6\n");
+}
+
+#[test]
+fn writer_builder_default() {
+    #[derive(serde::Serialize)]
+    struct Record<'a> {
+        text: &'a str,
+        quoted: &'a str,
+        formula: &'a str,
+        control: &'a str,
+        none: Option<u8>,
+        bytes: Bytes,
+        float: f64,
+        flag: bool,
+    }
+    let record = Record {
+        text: "a",
+        quoted: "b,\"c\"",
+        formula: "=1",
+        control: "\t",
+        none: None,
+        bytes: Bytes(&[0xca, 0xfe]),
+        float: 1.5,
+        flag: true,
+    };
+
+    let mut built = serde_csv_core::ser::WriterBuilder::new().build();
+    let mut writer = serde_csv_core::Writer::new();
+    let mut built_buf = [0; 64];
+    let mut buf = [0; 64];
+    let built_nwritten = built.serialize(&record, &mut built_buf).unwrap();
+    let nwritten = writer.serialize(&record, &mut buf).unwrap();
+
+    assert_eq!(&built_buf[..built_nwritten], &buf[..nwritten]);
+}
+
+#[test]
+fn writer_builder_options() {
+    fn serialize<T: serde::Serialize>(
+        builder: &mut serde_csv_core::ser::WriterBuilder,
+        value: &T,
+    ) -> Result<std::vec::Vec<u8>, Error> {
+        let mut buf = [0; 32];
+        let nwritten = builder.build().serialize(value, &mut buf)?;
+        Ok(buf[..nwritten].to_vec())
+    }
+    use serde_csv_core::ser::WriterBuilder;
+
+    let record = ("a b", Some("=1"), None::<u8>, "\x01", Bytes(b"\x0f"));
+    assert_eq!(
+        serialize(&mut WriterBuilder::new(), &record).unwrap(),
+        b"a b,=1,,\x01,\x0f\n"
+    );
+    assert_eq!(
+        serialize(
+            WriterBuilder::new()
+                .delimiter(b' ')
+                .terminator(csv_core::Terminator::CRLF),
+            &record
+        )
+        .unwrap(),
+        b"\"a b\" =1  \x01 \x0f\r\n"
+    );
+    assert_eq!(
+        serialize(WriterBuilder::new().quote(b'\''), &("a'b", 1)).unwrap(),
+        b"'a''b',1\n"
+    );
+    assert_eq!(
+        serialize(
+            WriterBuilder::new().double_quote(false).escape(b'!'),
+            &("a\"b", 1)
+        )
+        .unwrap(),
+        b"\"a!\"b\",1\n"
+    );
+    assert_eq!(
+        serialize(
+            WriterBuilder::new().quote_style(csv_core::QuoteStyle::Always),
+            &(1, 2)
+        )
+        .unwrap(),
+        b"\"1\",\"2\"\n"
+    );
+    assert_eq!(
+        serialize(WriterBuilder::new().comment(Some(b'#')), &("#a", 1)).unwrap(),
+        b"\"#a\",1\n"
+    );
+    assert_eq!(
+        serialize(WriterBuilder::new().escape_control_chars(true), &record).unwrap(),
+        b"a b,=1,,\\x01,\\x0F\n"
+    );
+    assert_eq!(
+        serialize(WriterBuilder::new().sanitize_formulas(true), &record).unwrap(),
+        b"a b,'=1,,\x01,\x0f\n"
+    );
+    assert_eq!(
+        serialize(
+            WriterBuilder::new()
+                .sanitize_formulas(true)
+                .formula_guard(b'_'),
+            &record
+        )
+        .unwrap(),
+        b"a b,_=1,,\x01,\x0f\n"
+    );
+    assert_eq!(
+        serialize(WriterBuilder::new().null_literal(Some(b"NULL")), &record).unwrap(),
+        b"a b,=1,NULL,\x01,\x0f\n"
+    );
+    assert_eq!(
+        serialize(
+            WriterBuilder::new().quote_policy(QuotePolicy::AlwaysStrings),
+            &record
+        )
+        .unwrap(),
+        b"\"a b\",\"=1\",,\"\x01\",\"\x0f\"\n"
+    );
+    assert_eq!(
+        serialize(
+            WriterBuilder::new().quote_policy(QuotePolicy::Never),
+            &("a,b", 1)
+        ),
+        Err(Error::QuotingRequired)
+    );
+    assert_eq!(
+        serialize(WriterBuilder::new().bytes_mode(BytesMode::Hex), &record).unwrap(),
+        b"a b,=1,,\x01,0F\n"
+    );
+
+    let mut writer = WriterBuilder::new().check_field_count(true).build();
+    let mut buf = [0; 32];
+    writer.serialize(&(1, 2), &mut buf).unwrap();
+    assert_eq!(
+        writer.serialize(&(1, 2, 3), &mut buf),
+        Err(Error::InconsistentFieldCount {
+            expected: 2,
+            got: 3
+        })
+    );
+}
+
+#[test]
+fn writer_builder_presets() {
+    use serde_csv_core::ser::WriterBuilder;
+
+    let record = ("a\tb;c", "\"d\"\n", 1.5);
+    let mut buf = [0; 32];
+
+    let nwritten = WriterBuilder::tsv()
+        .build()
+        .serialize(&record, &mut buf)
+        .unwrap();
+    assert_eq!(&buf[..nwritten], b"\"a\tb;c\"\t\"\"\"d\"\"\n\"\t1.5\n");
+
+    let nwritten = WriterBuilder::excel_eu()
+        .build()
+        .serialize(&record, &mut buf)
+        .unwrap();
+    assert_eq!(&buf[..nwritten], b"\"a\tb;c\";\"\"\"d\"\"\n\";1.5\r\n");
+
+    let nwritten = WriterBuilder::ascii_delimited()
+        .build()
+        .serialize(&record, &mut buf)
+        .unwrap();
+    assert_eq!(&buf[..nwritten], b"a\tb;c\x1F\"d\"\n\x1F1.5\x1E");
+    assert_eq!(
+        WriterBuilder::ascii_delimited()
+            .build()
+            .serialize(&("a\x1Eb", 1), &mut buf),
+        Err(Error::QuotingRequired)
+    );
+}
+
+#[test]
+fn serialize_status_filled_exactly() {
+    let mut writer = serde_csv_core::Writer::new();
+    let mut buf = [0; 6];
+
+    let status = writer.serialize_status(&("ab", 12), &mut buf).unwrap();
+
+    assert_eq!(
+        status,
+        SerializeStatus {
+            bytes_written: 6,
+            filled_exactly: true
+        }
+    );
+    assert_eq!(&buf, b"ab,12\n");
+}
+
+#[test]
+fn serialize_status_under_filled() {
+    let mut writer = serde_csv_core::Writer::new();
+    let mut buf = [0; 8];
+
+    let status = writer.serialize_status(&("ab", 12), &mut buf).unwrap();
+
+    assert_eq!(
+        status,
+        SerializeStatus {
+            bytes_written: 6,
+            filled_exactly: false
+        }
+    );
+    assert_eq!(&buf[..6], b"ab,12\n");
+}
+
+#[test]
+fn serialize_status_overflow() {
+    let mut writer = serde_csv_core::Writer::new();
+    let mut buf = [0; 5];
+
+    let result = writer.serialize_status(&("ab", 12), &mut buf);
+    let status = writer.serialize_status(&1, &mut buf);
+
+    assert_eq!(result, Err(Error::Overflow));
+    assert_eq!(
+        status,
+        Ok(SerializeStatus {
+            bytes_written: 2,
+            filled_exactly: false
+        })
+    );
+}
+
+fn checksum_writer(checksum: Checksum) -> serde_csv_core::Writer {
+    let mut writer = serde_csv_core::Writer::new();
+    writer.checksum(Some(checksum));
+    writer
+}
+
+#[test]
+fn checksum_crc8() {
+    let mut writer = checksum_writer(Checksum::Crc8);
+    let mut buf = [0; 32];
+
+    let nwritten = writer.serialize(&("123456789",), &mut buf).unwrap();
+
+    assert_eq!(&buf[..nwritten], b"123456789,F4\n");
+}
+
+#[test]
+fn checksum_crc16_ccitt() {
+    let mut writer = checksum_writer(Checksum::Crc16Ccitt);
+    let mut buf = [0; 32];
+
+    let nwritten = writer.serialize(&("123456789",), &mut buf).unwrap();
+
+    assert_eq!(&buf[..nwritten], b"123456789,29B1\n");
+}
+
+#[test]
+fn checksum_crc32() {
+    let mut writer = checksum_writer(Checksum::Crc32);
+    let mut buf = [0; 32];
+
+    let nwritten = writer.serialize(&("123456789",), &mut buf).unwrap();
+
+    assert_eq!(&buf[..nwritten], b"123456789,CBF43926\n");
+}
+
+#[test]
+fn checksum_covers_quotes() {
+    let mut writer = checksum_writer(Checksum::Crc32);
+    let mut buf = [0; 32];
+
+    let nwritten = writer.serialize(&("a", "b,c"), &mut buf).unwrap();
+
+    assert_eq!(&buf[..nwritten], b"a,\"b,c\",400AD88A\n");
+}
+
+#[test]
+fn checksum_empty_record() {
+    let mut writer = checksum_writer(Checksum::Crc16Ccitt);
+    let mut buf = [0; 32];
+
+    let nwritten = writer.serialize(&(), &mut buf).unwrap();
+
+    assert_eq!(&buf[..nwritten], b"\"\",79AB\n");
+}
+
+#[test]
+fn checksum_serialize_value() {
+    let mut writer = checksum_writer(Checksum::Crc16Ccitt);
+    let mut buf = [0; 64];
+
+    let mut nwritten = writer.serialize_value(&"Cracow", &mut buf).unwrap();
+    nwritten += writer
+        .serialize_value(&766_683, &mut buf[nwritten..])
+        .unwrap();
+    nwritten += writer.terminate(&mut buf[nwritten..]).unwrap();
+    nwritten += writer
+        .serialize(&("123456789",), &mut buf[nwritten..])
+        .unwrap();
+
+    assert_eq!(&buf[..nwritten], b"Cracow,766683,083A\n123456789,29B1\n");
+}
+
+#[test]
+fn checksum_header() {
+    #[derive(serde::Serialize)]
+    struct Record {
+        city: &'static str,
+        population: u32,
+    }
+
+    let mut writer = checksum_writer(Checksum::Crc16Ccitt);
+    let mut buf = [0; 64];
+    let record = Record {
+        city: "Cracow",
+        population: 766_683,
+    };
+
+    let mut nwritten = writer.serialize_header(&record, &mut buf).unwrap();
+    nwritten += writer.serialize(&record, &mut buf[nwritten..]).unwrap();
+
+    assert_eq!(
+        &buf[..nwritten],
+        b"city,population,93B0\nCracow,766683,083A\n"
+    );
+}
+
+#[test]
+fn checksum_rows() {
+    let mut writer = checksum_writer(Checksum::Crc16Ccitt);
+    let mut buf = [0; 64];
+
+    let nwritten = writer
+        .serialize_rows(&[("Cracow", 766_683), ("Cracow", 766_683)], &mut buf)
+        .unwrap();
+
+    assert_eq!(
+        &buf[..nwritten],
+        b"Cracow,766683,083A\nCracow,766683,083A\n"
+    );
+}
+
+#[test]
+fn checksum_slices() {
+    let mut writer = checksum_writer(Checksum::Crc32);
+    let mut first = [0; 5];
+    let mut second = [0; 32];
+
+    let nwritten = writer
+        .serialize_to_slices(&("a", "b,c"), &mut [&mut first, &mut second])
+        .unwrap();
+
+    assert_eq!(nwritten, 17);
+    assert_eq!(&first, b"a,\"b,");
+    assert_eq!(&second[..12], b"c\",400AD88A\n");
+}
+
+#[test]
+fn checksum_overflow() {
+    let mut writer = checksum_writer(Checksum::Crc16Ccitt);
+    let mut buf = [0; 16];
+
+    let result = writer.serialize(&("123456789",), &mut buf[..12]);
+    let nwritten = writer.serialize(&("123456789",), &mut buf).unwrap();
+
+    assert_eq!(result, Err(Error::Overflow));
+    assert_eq!(&buf[..nwritten], b"123456789,29B1\n");
+}
+
+#[test]
+fn checksum_field_only() {
+    let mut writer = checksum_writer(Checksum::Crc16Ccitt);
+    let mut buf = [0; 32];
+
+    let nwritten = writer.serialize_field_only(&"a,b", &mut buf).unwrap();
+
+    assert_eq!(&buf[..nwritten], b"\"a,b\"");
+}
+
+fn prefix_writer(prefix: &'static [u8]) -> serde_csv_core::Writer {
+    let mut writer = serde_csv_core::Writer::new();
+    writer.record_prefix(Some(prefix));
+    writer
+}
+
+#[test]
+fn record_prefix() {
+    let mut writer = prefix_writer(b"IMU");
+    let mut buf = [0; 32];
+
+    let mut nwritten = writer.serialize(&(1, "a"), &mut buf).unwrap();
+    nwritten += writer.serialize(&(2, "b"), &mut buf[nwritten..]).unwrap();
+
+    assert_eq!(&buf[..nwritten], b"IMU,1,a\nIMU,2,b\n");
+}
+
+#[test]
+fn record_prefix_quoted() {
+    let mut writer = prefix_writer(b"a,b");
+    let mut buf = [0; 32];
+
+    let nwritten = writer.serialize(&("c,d",), &mut buf).unwrap();
+
+    assert_eq!(&buf[..nwritten], b"\"a,b\",\"c,d\"\n");
+}
+
+#[test]
+fn record_prefix_first_field_empty() {
+    let mut writer = prefix_writer(b"IMU");
+    let mut buf = [0; 32];
+
+    let nwritten = writer.serialize(&("", 1), &mut buf).unwrap();
+
+    assert_eq!(&buf[..nwritten], b"IMU,,1\n");
+}
+
+#[test]
+fn record_prefix_empty_record() {
+    let mut writer = prefix_writer(b"IMU");
+    let mut buf = [0; 32];
+
+    let nwritten = writer.serialize(&[0u8; 0], &mut buf).unwrap();
+
+    assert_eq!(&buf[..nwritten], b"IMU\n");
+}
+
+#[test]
+fn record_prefix_serialize_value() {
+    let mut writer = prefix_writer(b"IMU");
+    let mut buf = [0; 32];
+
+    let mut nwritten = writer.serialize_value(&1, &mut buf).unwrap();
+    nwritten += writer.serialize_value(&2, &mut buf[nwritten..]).unwrap();
+    nwritten += writer.terminate(&mut buf[nwritten..]).unwrap();
+
+    assert_eq!(&buf[..nwritten], b"IMU,1,2\n");
+}
+
+#[test]
+fn record_prefix_rows_and_header() {
+    #[derive(serde::Serialize)]
+    struct Sample {
+        x: u8,
+        y: u8,
+    }
+
+    let mut writer = prefix_writer(b"IMU");
+    writer.check_field_count(true);
+    let mut buf = [0; 64];
+    let samples = [Sample { x: 1, y: 2 }, Sample { x: 3, y: 4 }];
+
+    let mut nwritten = writer.serialize_header(&samples[0], &mut buf).unwrap();
+    nwritten += writer
+        .serialize_rows(&samples, &mut buf[nwritten..])
+        .unwrap();
+
+    assert_eq!(&buf[..nwritten], b"IMU,x,y\nIMU,1,2\nIMU,3,4\n");
+}
+
+#[test]
+fn record_prefix_field_only() {
+    let mut writer = prefix_writer(b"IMU");
+    let mut buf = [0; 32];
+
+    let nwritten = writer.serialize_field_only(&"a", &mut buf).unwrap();
+
+    assert_eq!(&buf[..nwritten], b"a");
+}
+
+#[test]
+fn sequence_column() {
+    let mut writer = serde_csv_core::Writer::new();
+    writer.sequence_column(Some(0));
+    let mut buf = [0; 32];
+
+    let mut nwritten = 0;
+    for value in ["a", "b", "c"] {
+        nwritten += writer.serialize(&(value,), &mut buf[nwritten..]).unwrap();
+    }
+
+    assert_eq!(&buf[..nwritten], b"0,a\n1,b\n2,c\n");
+}
+
+#[test]
+fn sequence_column_wraps() {
+    let mut writer = serde_csv_core::Writer::new();
+    writer.sequence_column(Some(u32::MAX));
+    let mut buf = [0; 32];
+
+    let nwritten = writer.serialize_rows(&[1, 2], &mut buf).unwrap();
+
+    assert_eq!(&buf[..nwritten], b"4294967295,1\n0,2\n");
+}
+
+#[test]
+fn sequence_column_header_and_reset() {
+    #[derive(serde::Serialize)]
+    struct Sample {
+        x: u8,
+    }
+
+    let mut writer = serde_csv_core::ser::WriterBuilder::new()
+        .record_prefix(Some(b"IMU"))
+        .sequence_column(Some(10))
+        .sequence_column_name("n")
+        .build();
+    let mut buf = [0; 64];
+
+    let mut nwritten = writer.serialize_header(&Sample { x: 0 }, &mut buf).unwrap();
+    nwritten += writer
+        .serialize(&Sample { x: 1 }, &mut buf[nwritten..])
+        .unwrap();
+    nwritten += writer
+        .serialize(&Sample { x: 2 }, &mut buf[nwritten..])
+        .unwrap();
+    writer.reset();
+    nwritten += writer
+        .serialize(&Sample { x: 3 }, &mut buf[nwritten..])
+        .unwrap();
+
+    assert_eq!(&buf[..nwritten], b"IMU,n,x\nIMU,10,1\nIMU,11,2\nIMU,10,3\n");
+}
+
+#[test]
+fn sequence_column_error_keeps_number() {
+    let mut writer = serde_csv_core::Writer::new();
+    writer.sequence_column(Some(7));
+    let mut buf = [0; 16];
+
+    let result = writer.serialize(&("too long",), &mut buf[..4]);
+    let nwritten = writer.serialize(&("ok",), &mut buf).unwrap();
+
+    assert_eq!(result, Err(Error::Overflow));
+    assert_eq!(&buf[..nwritten], b"7,ok\n");
+}
+
+#[test]
+fn serialize_keyed() {
+    let first = std::collections::BTreeMap::from([("pressure", 1013), ("temperature", 21)]);
+    let second = std::collections::BTreeMap::from([("pressure", 1009), ("temperature", 23)]);
+
+    let mut writer = serde_csv_core::Writer::new();
+    let mut keys = serde_csv_core::ser::Keys::<4, 32>::new();
+    let mut buf = [0; 64];
+    let mut nwritten = writer.serialize_keyed(&first, &mut keys, &mut buf).unwrap();
+    nwritten += writer
+        .serialize_keyed(&second, &mut keys, &mut buf[nwritten..])
+        .unwrap();
+
+    assert_eq!(
+        &buf[..nwritten],
+        b"pressure,temperature\n1013,21\n1009,23\n"
+    );
+    assert_eq!(keys.len(), 2);
+    assert_eq!(keys.get(1), Some("temperature"));
+}
+
+#[test]
+fn serialize_keyed_flatten() {
+    let sample = Sample {
+        id: 1,
+        position: Coordinates { lat: 0.5, lon: 1.5 },
+        valid: true,
+    };
+
+    let mut writer = serde_csv_core::Writer::new();
+    let mut keys = serde_csv_core::ser::Keys::<4, 32>::new();
+    let mut buf = [0; 64];
+    let mut nwritten = writer
+        .serialize_keyed(&sample, &mut keys, &mut buf)
+        .unwrap();
+    nwritten += writer
+        .serialize_keyed(&sample, &mut keys, &mut buf[nwritten..])
+        .unwrap();
+
+    assert_eq!(
+        &buf[..nwritten],
+        b"id,lat,lon,valid\n1,0.5,1.5,true\n1,0.5,1.5,true\n"
+    );
+}
+
+#[test]
+fn serialize_keyed_compound_values() {
+    let first = std::collections::BTreeMap::from([("a", (0, 1)), ("b", (2, 3))]);
+    let second = std::collections::BTreeMap::from([("a", (4, 5)), ("b", (6, 7))]);
+
+    let mut writer = serde_csv_core::Writer::new();
+    let mut keys = serde_csv_core::ser::Keys::<4, 32>::new();
+    let mut buf = [0; 64];
+    let mut nwritten = writer.serialize_keyed(&first, &mut keys, &mut buf).unwrap();
+    nwritten += writer
+        .serialize_keyed(&second, &mut keys, &mut buf[nwritten..])
+        .unwrap();
+
+    assert_eq!(keys.len(), 2);
+    assert_eq!(&buf[..nwritten], b"a,a,b,b\n0,1,2,3\n4,5,6,7\n");
+}
+
+#[test]
+fn serialize_keyed_missing_key() {
+    let first = std::collections::BTreeMap::from([("a", 1), ("b", 2), ("c", 3)]);
+    let missing_last = std::collections::BTreeMap::from([("a", 4), ("b", 5)]);
+    let missing_middle = std::collections::BTreeMap::from([("a", 4), ("c", 6)]);
+    let extra = std::collections::BTreeMap::from([("a", 4), ("b", 5), ("c", 6), ("d", 7)]);
+
+    let mut writer = serde_csv_core::Writer::new();
+    let mut keys = serde_csv_core::ser::Keys::<4, 32>::new();
+    let mut buf = [0; 32];
+    writer.serialize_keyed(&first, &mut keys, &mut buf).unwrap();
+
+    assert_eq!(
+        writer.serialize_keyed(&missing_last, &mut keys, &mut buf),
+        Err(Error::KeyMismatch { index: 2 })
+    );
+    assert_eq!(
+        writer.serialize_keyed(&missing_middle, &mut keys, &mut buf),
+        Err(Error::KeyMismatch { index: 1 })
+    );
+    assert_eq!(
+        writer.serialize_keyed(&extra, &mut keys, &mut buf),
+        Err(Error::KeyMismatch { index: 3 })
+    );
+    let nwritten = writer.serialize_keyed(&first, &mut keys, &mut buf).unwrap();
+    assert_eq!(&buf[..nwritten], b"1,2,3\n");
+}
+
+#[test]
+fn serialize_keyed_long_keys() {
+    let first = std::collections::BTreeMap::from([("a_rather_long_key", 1)]);
+    let second = std::collections::BTreeMap::from([("a_rather_long_kez", 1)]);
+
+    let mut writer = serde_csv_core::Writer::new();
+    let mut keys = serde_csv_core::ser::Keys::<4, 32>::new();
+    let mut buf = [0; 32];
+    writer.serialize_keyed(&first, &mut keys, &mut buf).unwrap();
+
+    assert_eq!(
+        writer.serialize_keyed(&second, &mut keys, &mut buf),
+        Err(Error::KeyMismatch { index: 0 })
+    );
+}
+
+#[test]
+fn serialize_keyed_overflow() {
+    let map = std::collections::BTreeMap::from([("a", 1), ("b", 2), ("c", 3)]);
+
+    let mut writer = serde_csv_core::Writer::new();
+    let mut buf = [0; 32];
+
+    let mut keys = serde_csv_core::ser::Keys::<2, 32>::new();
+    assert_eq!(
+        writer.serialize_keyed(&map, &mut keys, &mut buf),
+        Err(Error::KeysOverflow)
+    );
+    assert!(keys.is_empty());
+
+    let mut keys = serde_csv_core::ser::Keys::<4, 2>::new();
+    assert_eq!(
+        writer.serialize_keyed(&map, &mut keys, &mut buf),
+        Err(Error::KeysOverflow)
+    );
+
+    let mut keys = serde_csv_core::ser::Keys::<4, 32>::new();
+    assert_eq!(
+        writer.serialize_keyed(&map, &mut keys, &mut buf[..8]),
+        Err(Error::Overflow)
+    );
+    assert!(keys.is_empty());
+    let nwritten = writer.serialize_keyed(&map, &mut keys, &mut buf).unwrap();
+    assert_eq!(&buf[..nwritten], b"a,b,c\n1,2,3\n");
+}
+
+#[test]
+fn defer_terminator() {
+    let mut writer = serde_csv_core::Writer::new();
+    writer.defer_terminator(true);
+    let mut buf = [0; 32];
+
+    let mut nwritten = writer.serialize(&("a", "b"), &mut buf).unwrap();
+    nwritten += writer.serialize(&("c", "d"), &mut buf[nwritten..]).unwrap();
+
+    assert_eq!(&buf[..nwritten], b"a,b\nc,d");
+}
+
+#[test]
+fn defer_terminator_quoted_and_empty() {
+    let mut writer = serde_csv_core::ser::WriterBuilder::new()
+        .terminator(csv_core::Terminator::CRLF)
+        .defer_terminator(true)
+        .build();
+    let mut buf = [0; 32];
+
+    let mut nwritten = writer.serialize(&("a,b",), &mut buf).unwrap();
+    assert_eq!(&buf[..nwritten], b"\"a,b\"");
+    nwritten += writer.serialize(&(), &mut buf[nwritten..]).unwrap();
+    nwritten += writer.serialize(&("",), &mut buf[nwritten..]).unwrap();
+    nwritten += writer.serialize(&[1, 2], &mut buf[nwritten..]).unwrap();
+
+    assert_eq!(&buf[..nwritten], b"\"a,b\"\r\n\"\"\r\n\"\"\r\n1,2");
+}
+
+#[test]
+fn defer_terminator_reset() {
+    let mut writer = serde_csv_core::Writer::new();
+    writer.defer_terminator(true);
+    let mut buf = [0; 32];
+
+    writer.serialize(&("a", "b"), &mut buf).unwrap();
+    writer.reset();
+    let nwritten = writer.serialize(&("c", "d"), &mut buf).unwrap();
+
+    assert_eq!(&buf[..nwritten], b"c,d");
+}
+
+#[test]
+fn defer_terminator_overflow() {
+    let mut writer = serde_csv_core::Writer::new();
+    writer.defer_terminator(true);
+    let mut buf = [0; 32];
+
+    let nwritten = writer.serialize(&("a", "b"), &mut buf).unwrap();
+    let result = writer.serialize(&("c", "d"), &mut buf[nwritten..nwritten + 3]);
+    let n = writer.serialize(&("c", "d"), &mut buf[nwritten..]).unwrap();
+
+    assert_eq!(result, Err(Error::Overflow));
+    assert_eq!(&buf[..nwritten + n], b"a,b\nc,d");
+}
+
+#[test]
+fn defer_terminator_checksum() {
+    let mut writer = serde_csv_core::Writer::new();
+    writer
+        .defer_terminator(true)
+        .checksum(Some(serde_csv_core::Checksum::Crc16Ccitt));
+    let mut buf = [0; 64];
+
+    let mut nwritten = writer.serialize(&("123456789",), &mut buf).unwrap();
+    nwritten += writer
+        .serialize(&("123456789",), &mut buf[nwritten..])
+        .unwrap();
+
+    assert_eq!(&buf[..nwritten], b"123456789,29B1\n123456789,29B1");
+}
+
+#[test]
+fn defer_terminator_rows_and_header() {
+    #[derive(serde::Serialize)]
+    struct Row {
+        x: u8,
+    }
+
+    let rows = [Row { x: 1 }, Row { x: 2 }];
+    let mut writer = serde_csv_core::Writer::new();
+    writer.defer_terminator(true);
+    let mut buf = [0; 32];
+
+    let mut nwritten = writer.serialize_header(&rows[0], &mut buf).unwrap();
+    nwritten += writer.serialize_rows(&rows, &mut buf[nwritten..]).unwrap();
+
+    assert_eq!(&buf[..nwritten], b"x\n1\n2");
+}
+
+#[cfg(feature = "percent-encoding")]
+#[test]
+fn field_encoder_percent() {
+    let mut writer = serde_csv_core::Writer::new();
+    writer.field_encoder(Some(&serde_csv_core::ser::PercentEncoder));
+    let mut buf = [0; 64];
+
+    let nwritten = writer
+        .serialize(&("a b,c", "\"é\"", -1.5, None::<u8>), &mut buf)
+        .unwrap();
+
+    assert_eq!(&buf[..nwritten], b"a%20b%2Cc,%22%C3%A9%22,-1.5,\n");
+}
+
+#[cfg(feature = "percent-encoding")]
+#[test]
+fn field_encoder_percent_header() {
+    #[derive(serde::Serialize)]
+    struct Row {
+        #[serde(rename = "first name")]
+        name: &'static str,
+    }
+
+    let mut writer = serde_csv_core::ser::WriterBuilder::new()
+        .field_encoder(Some(&serde_csv_core::ser::PercentEncoder))
+        .record_prefix(Some(b"a b"))
+        .build();
+    let mut buf = [0; 64];
+
+    let nwritten = writer
+        .serialize_header(&Row { name: "x" }, &mut buf)
+        .unwrap();
+
+    assert_eq!(&buf[..nwritten], b"a b,first%20name\n");
+}
+
+#[derive(Debug)]
+struct Semicolons;
+
+impl serde_csv_core::ser::FieldEncoder for Semicolons {
+    fn encode(
+        &self,
+        input: &[u8],
+        f: &mut dyn FnMut(&[u8]) -> serde_csv_core::ser::Result<()>,
+    ) -> serde_csv_core::ser::Result<()> {
+        input
+            .iter()
+            .try_for_each(|&b| f(&[if b == b';' { b',' } else { b }]))
+    }
+}
+
+#[test]
+fn field_encoder_before_quoting() {
+    let mut writer = serde_csv_core::Writer::new();
+    writer.field_encoder(Some(&Semicolons));
+    let mut buf = [0; 32];
+
+    let nwritten = writer.serialize(&("a;b", 1), &mut buf).unwrap();
+
+    assert_eq!(&buf[..nwritten], b"\"a,b\",1\n");
+}
+
+#[test]
+fn field_encoder_quote_policy() {
+    let mut writer = serde_csv_core::ser::WriterBuilder::new()
+        .field_encoder(Some(&Semicolons))
+        .quote_policy(QuotePolicy::Never)
+        .build();
+    let mut buf = [0; 32];
+
+    let result = writer.serialize(&("a;b",), &mut buf);
+
+    assert_eq!(result, Err(Error::QuotingRequired));
+}
+
+#[test]
+fn serialize_overflow_is_atomic() {
+    let mut writer = serde_csv_core::ser::WriterBuilder::new()
+        .check_field_count(true)
+        .checksum(Some(Checksum::Crc16Ccitt))
+        .sequence_column(Some(1))
+        .build();
+    let mut buf = [0; 64];
+
+    let mut nwritten = writer.serialize(&("a", 1), &mut buf).unwrap();
+    let expected = buf;
+    let result = writer.serialize(&("needs \"quotes\"", 2), &mut buf[nwritten..nwritten + 8]);
+    assert_eq!(result, Err(Error::Overflow));
+    assert_eq!(&buf[..nwritten], &expected[..nwritten]);
+    nwritten += writer
+        .serialize(&("needs \"quotes\"", 2), &mut buf[nwritten..])
+        .unwrap();
+
+    let mut reader = serde_csv_core::de::ReaderBuilder::new()
+        .checksum(Some(Checksum::Crc16Ccitt))
+        .build::<32>();
+    let (first, n) = reader
+        .deserialize::<(u32, heapless::String<16>, u8)>(&buf[..nwritten])
+        .unwrap();
+    let (second, _) = reader
+        .deserialize::<(u32, heapless::String<16>, u8)>(&buf[n..nwritten])
+        .unwrap();
+    assert_eq!(first, (1, "a".into(), 1));
+    assert_eq!(second, (2, "needs \"quotes\"".into(), 2));
+}
+
+#[test]
+fn serialize_overflow_first_record_sets_no_field_count() {
+    let mut writer = serde_csv_core::Writer::new();
+    writer.check_field_count(true);
+    let mut buf = [0; 32];
+
+    let result = writer.serialize(&("abcdef", "ghijkl", "mnopqr"), &mut buf[..8]);
+    let nwritten = writer.serialize(&("a", "b"), &mut buf).unwrap();
+
+    assert_eq!(result, Err(Error::Overflow));
+    assert_eq!(&buf[..nwritten], b"a,b\n");
+}
+
+#[test]
+fn serialize_keyed_overflow_keeps_partial_record() {
+    let mut writer = serde_csv_core::Writer::new();
+    let mut keys = serde_csv_core::ser::Keys::<2, 8>::new();
+    let map = std::collections::BTreeMap::from([("x", 1), ("y", 2)]);
+    let mut buf = [0; 32];
+
+    let mut nwritten = writer.serialize_value(&"id", &mut buf).unwrap();
+    let result = writer.serialize_keyed(&map, &mut keys, &mut buf[nwritten..nwritten + 2]);
+    nwritten += writer
+        .serialize_keyed(&map, &mut keys, &mut buf[nwritten..])
+        .unwrap();
+
+    assert_eq!(result, Err(Error::Overflow));
+    assert_eq!(&buf[..nwritten], b"id,x,y\n1,2\n");
+}
+
+#[test]
+fn serialize_latin1() {
+    let data = ("Crème brûlée", 'é', -12, 1.5);
+
+    let mut writer = serde_csv_core::Writer::new();
+    writer.encoding(Encoding::Latin1);
+    let mut buf = [0; 32];
+    let nwritten = writer.serialize(&data, &mut buf).unwrap();
+
+    assert_eq!(&buf[..nwritten], b"Cr\xe8me br\xfbl\xe9e,\xe9,-12,1.5\n");
+}
+
+#[test]
+fn serialize_latin1_quoted() {
+    let data = ("\"é\",ü", 1);
+
+    let mut writer = serde_csv_core::Writer::new();
+    writer.encoding(Encoding::Latin1);
+    let mut buf = [0; 32];
+    let nwritten = writer.serialize(&data, &mut buf).unwrap();
+
+    assert_eq!(&buf[..nwritten], b"\"\"\"\xe9\"\",\xfc\",1\n");
+}
+
+#[test]
+fn serialize_latin1_unrepresentable() {
+    let mut writer = serde_csv_core::Writer::new();
+    writer.encoding(Encoding::Latin1);
+    let mut buf = [0; 32];
+
+    let string = writer.serialize(&("id", "ząb"), &mut buf);
+    let char = writer.serialize(&'ą', &mut buf);
+
+    assert_eq!(string, Err(Error::Unrepresentable('ą')));
+    assert_eq!(char, Err(Error::Unrepresentable('ą')));
+}
+
+#[test]
+fn serialize_windows1252() {
+    let data = ("€™", '\u{81}');
+
+    let mut writer = serde_csv_core::Writer::new();
+    writer.encoding(Encoding::Windows1252);
+    let mut buf = [0; 32];
+    let nwritten = writer.serialize(&data, &mut buf).unwrap();
+    let unrepresentable = writer.serialize(&'\u{80}', &mut buf);
+
+    assert_eq!(&buf[..nwritten], b"\x80\x99,\x81\n");
+    assert_eq!(unrepresentable, Err(Error::Unrepresentable('\u{80}')));
+}
+
+#[test]
+fn serialize_latin1_long_string() {
+    let data = "é".repeat(40);
+
+    let mut writer = serde_csv_core::Writer::new();
+    writer.encoding(Encoding::Latin1);
+    let mut buf = [0; 64];
+    let nwritten = writer.serialize(&data, &mut buf).unwrap();
+
+    assert_eq!(&buf[..nwritten - 1], [0xe9; 40]);
 }
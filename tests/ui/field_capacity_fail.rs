@@ -0,0 +1,8 @@
+const N: usize = 16;
+const MAX_FIELD_LEN: usize = 24;
+
+serde_csv_core::assert_field_capacity!(N, MAX_FIELD_LEN);
+
+fn main() {
+    let _reader = serde_csv_core::Reader::<N>::new();
+}
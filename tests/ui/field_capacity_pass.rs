@@ -0,0 +1,10 @@
+const N: usize = 32;
+
+serde_csv_core::assert_field_capacity!(N, 32);
+serde_csv_core::assert_field_capacity!(N, 8);
+
+fn main() {
+    serde_csv_core::assert_field_capacity!(64, N);
+
+    let _reader = serde_csv_core::Reader::<N>::new();
+}